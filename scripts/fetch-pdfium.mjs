@@ -0,0 +1,34 @@
+// Baixa o PDFium do Windows para src-tauri/pdfium, de onde o instalador o
+// copia (ver src-tauri/tauri.windows.conf.json). Roda antes do `tauri dev` e
+// do `tauri build` no Windows; se a DLL já estiver lá, não faz nada.
+import { execFileSync } from "node:child_process";
+import { existsSync, mkdtempSync, copyFileSync, rmSync, writeFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+// Precisa bater com a feature `pdfium_latest` do `pdfium-render` no Cargo.toml.
+const RELEASE = "chromium/7543";
+const ARCHIVE = "pdfium-win-x64.tgz";
+
+const target = join(dirname(fileURLToPath(import.meta.url)), "..", "src-tauri", "pdfium", "pdfium.dll");
+
+if (process.platform === "win32" && !existsSync(target)) {
+  const url = `https://github.com/bblanchon/pdfium-binaries/releases/download/${RELEASE}/${ARCHIVE}`;
+  console.log(`Baixando ${url}`);
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`Falha ao baixar o PDFium: ${response.status} ${response.statusText}`);
+  }
+
+  const folder = mkdtempSync(join(tmpdir(), "pdfium-"));
+  try {
+    const archive = join(folder, ARCHIVE);
+    writeFileSync(archive, Buffer.from(await response.arrayBuffer()));
+    // O tar acompanha o Windows 10 e posteriores.
+    execFileSync("tar", ["-xzf", archive, "-C", folder, "bin/pdfium.dll"], { stdio: "inherit" });
+    copyFileSync(join(folder, "bin", "pdfium.dll"), target);
+  } finally {
+    rmSync(folder, { recursive: true, force: true });
+  }
+}
@@ -0,0 +1,256 @@
+use crate::catalog::options::BarcodeFormat;
+use crate::i18n;
+
+/// Módulos em branco exigidos de cada lado das barras.
+pub const QUIET_ZONE: usize = 10;
+
+/// Larguras de barra/espaço de cada símbolo do Code 128 (0 a 102 são dados,
+/// 103 a 105 são os inícios A, B e C).
+const CODE128_PATTERNS: [u32; 106] = [
+    212222, 222122, 222221, 121223, 121322, 131222, 122213, 122312, 132212, 221213,
+    221312, 231212, 112232, 122132, 122231, 113222, 123122, 123221, 223211, 221132,
+    221231, 213212, 223112, 312131, 311222, 321122, 321221, 312212, 322112, 322211,
+    212123, 212321, 232121, 111323, 131123, 131321, 112313, 132113, 132311, 211313,
+    231113, 231311, 112133, 112331, 132131, 113123, 113321, 133121, 313121, 211331,
+    231131, 213113, 213311, 213131, 311123, 311321, 331121, 312113, 312311, 332111,
+    314111, 221411, 431111, 111224, 111422, 121124, 121421, 141122, 141221, 112214,
+    112412, 122114, 122411, 142112, 142211, 241211, 221114, 413111, 241112, 134111,
+    111242, 121142, 121241, 114212, 124112, 124211, 411212, 421112, 421211, 212141,
+    214121, 412121, 111143, 111341, 131141, 114113, 114311, 411113, 411311, 113141,
+    114131, 311141, 411131, 211412, 211214, 211232,
+];
+const CODE128_STOP: u32 = 2331112;
+const CODE128_START_B: u32 = 104;
+const CODE128_START_C: u32 = 105;
+const CODE128_TO_B: u32 = 100; // troca para o conjunto B estando no C
+const CODE128_TO_C: u32 = 99; // troca para o conjunto C estando no B
+
+/// Dígitos 0 a 9 no conjunto L do EAN-13 (7 módulos, 1 = barra).
+const EAN_L: [u8; 10] = [0x0D, 0x19, 0x13, 0x3D, 0x23, 0x31, 0x2F, 0x3B, 0x37, 0x0B];
+/// Quais dígitos da metade esquerda usam o conjunto G, pelo primeiro dígito.
+const EAN_PARITY: [u8; 10] = [0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110, 0b011010];
+
+/// Código de barras pronto para desenhar.
+#[derive(Clone, Debug)]
+pub struct Barcode {
+    pub modules: Vec<bool>, // true = barra; sem a zona de silêncio
+    pub text: String,       // texto impresso embaixo (no EAN-13, com o dígito verificador)
+}
+
+impl Barcode {
+    /// Barras como (x inicial, x final) em mm, a partir de `x`, com `module` mm por módulo.
+    pub fn bars(&self, x: f32, module: f32) -> Vec<(f32, f32)> {
+        let mut bars = Vec::new();
+        let mut start = None;
+        for (index, dark) in self.modules.iter().chain([&false]).enumerate() {
+            match (dark, start) {
+                (true, None) => start = Some(index),
+                (false, Some(first)) => {
+                    bars.push((x + first as f32 * module, x + index as f32 * module));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        bars
+    }
+}
+
+/// Código de barras do código do item. No automático, códigos numéricos de
+/// 13 dígitos com o verificador certo saem em EAN-13 e o resto em Code 128,
+/// sem mudar o código impresso. No EAN-13, códigos de 12 dígitos ganham o
+/// dígito verificador.
+pub fn encode(code: &str, format: BarcodeFormat) -> Result<Barcode, String> {
+    let code = code.trim();
+    if code.is_empty() {
+        return Err(i18n::text("barcode_empty").to_string());
+    }
+    match format {
+        BarcodeFormat::Ean13 => ean13(code),
+        BarcodeFormat::Code128 => code128(code),
+        BarcodeFormat::Auto if code.len() == 13 => ean13(code).or_else(|_| code128(code)),
+        BarcodeFormat::Auto => code128(code),
+    }
+}
+
+fn code128(code: &str) -> Result<Barcode, String> {
+    if let Some(character) = code.chars().find(|character| !(' '..='~').contains(character)) {
+        return Err(i18n::message("barcode_invalid_character", &[&character]));
+    }
+    let bytes = code.as_bytes();
+    let digit_run = |from: usize| bytes[from..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+
+    // Conjunto C (dois dígitos por símbolo) nas sequências longas de dígitos,
+    // conjunto B no resto.
+    let first_run = digit_run(0);
+    let mut in_c = (first_run >= 4 || first_run == bytes.len()) && first_run % 2 == 0;
+    let mut values = vec![if in_c { CODE128_START_C } else { CODE128_START_B }];
+    let mut index = 0;
+    while index < bytes.len() {
+        let run = digit_run(index);
+        if in_c {
+            if run >= 2 {
+                values.push(((bytes[index] - b'0') * 10 + bytes[index + 1] - b'0') as u32);
+                index += 2;
+            } else {
+                values.push(CODE128_TO_B);
+                in_c = false;
+            }
+        } else if run >= 6 || (run >= 4 && index + run == bytes.len()) {
+            if run % 2 == 1 {
+                values.push((bytes[index] - b' ') as u32);
+                index += 1;
+            }
+            values.push(CODE128_TO_C);
+            in_c = true;
+        } else {
+            values.push((bytes[index] - b' ') as u32);
+            index += 1;
+        }
+    }
+    let checksum = values.iter().enumerate().map(|(position, value)| value * position.max(1) as u32).sum::<u32>() % 103;
+    values.push(checksum);
+
+    let mut modules = Vec::new();
+    for value in values {
+        push_widths(&mut modules, CODE128_PATTERNS[value as usize]);
+    }
+    push_widths(&mut modules, CODE128_STOP);
+    Ok(Barcode { modules, text: code.to_string() })
+}
+
+/// Larguras alternadas de barra e espaço, escritas como dígitos (ex: 212222).
+fn push_widths(modules: &mut Vec<bool>, widths: u32) {
+    let digits = widths.to_string();
+    for (index, width) in digits.bytes().enumerate() {
+        modules.extend(std::iter::repeat_n(index % 2 == 0, (width - b'0') as usize));
+    }
+}
+
+fn ean13(code: &str) -> Result<Barcode, String> {
+    if !code.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(i18n::text("ean13_not_numeric").to_string());
+    }
+    let mut digits: Vec<u8> = code.bytes().map(|byte| byte - b'0').collect();
+    match digits.len() {
+        12 => digits.push(ean13_check_digit(&digits)),
+        13 => {
+            let expected = ean13_check_digit(&digits);
+            if digits[12] != expected {
+                return Err(i18n::message("ean13_check_digit", &[&expected]));
+            }
+        }
+        _ => return Err(i18n::text("ean13_length").to_string()),
+    }
+
+    let mut modules = Vec::with_capacity(95);
+    push_bits(&mut modules, 0b101, 3);
+    let parity = EAN_PARITY[digits[0] as usize];
+    for (position, &digit) in digits[1..7].iter().enumerate() {
+        let left = EAN_L[digit as usize];
+        let pattern = if parity & (0b100000 >> position) != 0 { ean_g(left) } else { left };
+        push_bits(&mut modules, pattern, 7);
+    }
+    push_bits(&mut modules, 0b01010, 5);
+    for &digit in &digits[7..] {
+        push_bits(&mut modules, !EAN_L[digit as usize] & 0x7F, 7); // conjunto R
+    }
+    push_bits(&mut modules, 0b101, 3);
+
+    let text = digits.iter().map(|digit| char::from(b'0' + digit)).collect();
+    Ok(Barcode { modules, text })
+}
+
+/// Dígito verificador do EAN-13 a partir dos 12 primeiros dígitos.
+pub fn ean13_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(index, &digit)| digit as u32 * if index % 2 == 0 { 1 } else { 3 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+// Conjunto G: o R (complemento do L) espelhado.
+fn ean_g(left: u8) -> u8 {
+    (!left & 0x7F).reverse_bits() >> 1
+}
+
+fn push_bits(modules: &mut Vec<bool>, bits: u8, count: u32) {
+    for bit in (0..count).rev() {
+        modules.push(bits & (1 << bit) != 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Valores dos símbolos do Code 128, lidos de volta das larguras dos módulos.
+    fn symbols(barcode: &Barcode) -> Vec<u32> {
+        let (data, stop) = barcode.modules.split_at(barcode.modules.len() - 13);
+        let mut expected_stop = Vec::new();
+        push_widths(&mut expected_stop, CODE128_STOP);
+        assert_eq!(stop, expected_stop.as_slice());
+        data.chunks(11)
+            .map(|symbol| {
+                let widths = symbol.chunk_by(|a, b| a == b).fold(0, |widths, run| widths * 10 + run.len() as u32);
+                CODE128_PATTERNS.iter().position(|&pattern| pattern == widths).unwrap() as u32
+            })
+            .collect()
+    }
+
+    fn code128_symbols(code: &str) -> Vec<u32> {
+        symbols(&encode(code, BarcodeFormat::Code128).unwrap())
+    }
+
+    #[test]
+    fn code128_checksum_weights_each_symbol_by_position() {
+        // "Wikipedia": 104 + 55·1 + 73·2 + ... + 65·9 = 3281, e 3281 mod 103 = 88.
+        let values = code128_symbols("Wikipedia");
+        assert_eq!(values, vec![104, 55, 73, 75, 73, 80, 69, 68, 73, 65, 88]);
+        assert_eq!(code128_symbols("AB"), vec![104, 33, 34, 102]);
+    }
+
+    #[test]
+    fn code128_switches_between_sets_b_and_c() {
+        assert_eq!(code128_symbols("123456"), vec![105, 12, 34, 56, 44]);
+        assert_eq!(code128_symbols("AB123456")[..7], [104, 33, 34, CODE128_TO_C, 12, 34, 56]);
+        // Sequência ímpar no fim: o primeiro dígito vai no conjunto B.
+        assert_eq!(code128_symbols("AB12345")[..7], [104, 33, 34, 17, CODE128_TO_C, 23, 45]);
+        assert_eq!(code128_symbols("1234AB")[..6], [105, 12, 34, CODE128_TO_B, 33, 34]);
+        // Poucos dígitos não compensam a troca.
+        assert_eq!(code128_symbols("AN-12")[..6], [104, 33, 46, 13, 17, 18]);
+        assert!(encode("Anel nº 1", BarcodeFormat::Code128).is_err());
+    }
+
+    #[test]
+    fn ean13_check_digit_and_patterns() {
+        assert_eq!(ean13_check_digit(&[4, 0, 0, 6, 3, 8, 1, 3, 3, 3, 9, 3]), 1);
+        let barcode = encode("4006381333931", BarcodeFormat::Ean13).unwrap();
+        assert_eq!(barcode.text, "4006381333931");
+        assert_eq!(barcode.modules.len(), 95);
+        let bits = |from: usize, to: usize| barcode.modules[from..to].iter().map(|&dark| if dark { '1' } else { '0' }).collect::<String>();
+        assert_eq!(bits(0, 3), "101");
+        // Primeiro dígito 4: L G L L G G na metade esquerda.
+        assert_eq!(bits(3, 10), "0001101"); // 0 no conjunto L
+        assert_eq!(bits(10, 17), "0100111"); // 0 no conjunto G
+        assert_eq!(bits(45, 50), "01010");
+        assert_eq!(bits(50, 57), "1000010"); // 3 no conjunto R
+        assert_eq!(bits(92, 95), "101");
+
+        assert_eq!(encode("400638133393", BarcodeFormat::Ean13).unwrap().text, "4006381333931");
+        assert!(encode("4006381333932", BarcodeFormat::Ean13).is_err());
+        assert!(encode("AN-1", BarcodeFormat::Ean13).is_err());
+    }
+
+    #[test]
+    fn auto_uses_ean13_only_for_complete_codes() {
+        assert_eq!(encode("4006381333931", BarcodeFormat::Auto).unwrap().modules.len(), 95);
+        // 12 dígitos não ganham um verificador que não está no código do item.
+        let twelve = encode("400638133393", BarcodeFormat::Auto).unwrap();
+        assert_eq!(twelve.text, "400638133393");
+        assert_eq!(symbols(&twelve)[0], CODE128_START_C);
+        assert_eq!(encode("4006381333932", BarcodeFormat::Auto).unwrap().text, "4006381333932");
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::catalog::format::format_attribute;
+use crate::catalog::labels::label;
+use crate::catalog::options::{CatalogOptions, CatalogSelection};
+use crate::db::{validation_error, Result, attribute_repository, info_repository, item_repository, section_repository, tag_repository, translation_repository, variant_repository};
+use crate::db::translation_repository::TranslationKey;
+use crate::i18n;
+use crate::models::{info::Info, item::Item, section::Section};
+use crate::models::section::SectionNode;
+use crate::models::attribute::{AttributeDefinition, AttributeType};
+use crate::models::variant::{Variant, VariantAxis};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Catalog {
+    pub sections: Vec<CatalogSection>,
+}
+
+impl Catalog {
+    /// Um catálogo para cada seção de primeiro nível, com as subseções dela.
+    pub fn split_by_section(&self) -> Vec<Catalog> {
+        let mut parts: Vec<Catalog> = Vec::new();
+        for section in &self.sections {
+            match parts.last_mut() {
+                Some(part) if section.depth > 0 => part.sections.push(section.clone()),
+                _ => parts.push(Catalog { sections: vec![section.clone()] }),
+            }
+        }
+        parts
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CatalogSection {
+    pub section: Section,
+    pub depth: usize, // 0 para seções de primeiro nível
+    pub items: Vec<CatalogItem>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CatalogItem {
+    pub item: Item,
+    pub infos: Vec<Info>, // `details` já formatado para o idioma do catálogo
+    /// Nome de cada info antes da tradução, na ordem de `infos`, para o
+    /// índice remissivo achar as infos pelo nome cadastrado.
+    #[serde(skip)]
+    pub info_names: Vec<String>,
+    pub badge: Option<String>, // ex: "Novidade"
+    pub variant_axes: Vec<VariantAxis>,
+    pub variants: Vec<Variant>,
+}
+
+/// Lê seções, itens e infos do banco na ordem em que aparecem no catálogo
+/// (subseções logo depois da seção mãe). Com `options.tag_id`, o catálogo tem
+/// uma única seção com os itens da tag. Com `options.selection`, só os itens
+/// selecionados entram. Nomes, descrições e infos saem no idioma do catálogo
+/// quando houver tradução.
+pub fn build_catalog(conn: &Connection, options: &CatalogOptions) -> Result<Catalog> {
+    let cutoff = match options.new_arrival_days {
+        Some(days) => Some(new_arrival_cutoff(conn, days)?),
+        None => None,
+    };
+    let badge = label("new_arrival", options.language());
+    let attributes: HashMap<String, AttributeDefinition> = attribute_repository::list_attributes(conn)?
+        .into_iter()
+        .map(|attribute| (attribute.id.clone(), attribute))
+        .collect();
+
+    let groups = match &options.tag_id {
+        Some(tag_id) => tag_group(conn, tag_id)?,
+        None => section_groups(conn)?,
+    };
+
+    let selection = options.selection.clone().unwrap_or_default();
+    let picked_sections = picked_sections(&groups, &selection.section_ids);
+    let tagged: Option<HashSet<String>> = match &selection.tag_id {
+        Some(tag_id) if tag_repository::get_tag(conn, tag_id)?.is_none() => {
+            return Err(validation_error(i18n::text("tag_not_found")));
+        }
+        Some(tag_id) => Some(
+            tag_repository::list_items_by_tag(conn, tag_id)?
+                .into_iter()
+                .map(|item| item.id)
+                .collect(),
+        ),
+        None => None,
+    };
+
+    let translations = translation_repository::load_locale(conn, options.content_locale())?;
+
+    let mut catalog_sections = Vec::new();
+    for ((mut section, depth, items), section_picked) in groups.into_iter().zip(picked_sections) {
+        let mut catalog_items = Vec::new();
+        for item in items {
+            if !is_picked(&selection, &item, section_picked)
+                || tagged.as_ref().is_some_and(|tagged| !tagged.contains(&item.id))
+                || (selection.with_image_only && item.image_path.as_deref().is_none_or(|path| path.trim().is_empty()))
+            {
+                continue;
+            }
+
+            let is_new = cutoff.as_ref().is_some_and(|cutoff| item.created_at >= *cutoff);
+            let mut entry = catalog_item(conn, item, is_new.then(|| badge.to_string()))?;
+            if !matches_filters(&selection, &entry) {
+                continue;
+            }
+            // Com `hide_empty_infos`, campos de template ainda não preenchidos ficam de fora.
+            if options.hide_empty_infos {
+                entry.infos.retain(|info| !info.details.trim().is_empty());
+            }
+            entry.info_names = entry.infos.iter().map(|info| info.name.clone()).collect();
+            // Com atributo, o nome vem da tradução do atributo e o valor de
+            // atributos com tipo é formatado no idioma do catálogo; infos
+            // livres têm nome e valor traduzidos na própria info.
+            for info in &mut entry.infos {
+                match info.attribute_id.as_ref().and_then(|id| attributes.get(id)) {
+                    Some(attribute) => {
+                        translate(&translations, "attribute", &attribute.id, "name", &mut info.name);
+                        if attribute.kind == AttributeType::Text {
+                            translate(&translations, "info", &info.id, "details", &mut info.details);
+                        } else {
+                            info.details = format_attribute(attribute, &info.details, options.language());
+                        }
+                    }
+                    None => {
+                        translate(&translations, "info", &info.id, "name", &mut info.name);
+                        translate(&translations, "info", &info.id, "details", &mut info.details);
+                    }
+                }
+            }
+            translate(&translations, "item", &entry.item.id, "description", &mut entry.item.description);
+            catalog_items.push(entry);
+        }
+
+        translate(&translations, "section", &section.id, "name", &mut section.name);
+        if let Some(description) = &mut section.description {
+            translate(&translations, "section", &section.id, "description", description);
+        }
+        catalog_sections.push(CatalogSection { section, depth, items: catalog_items });
+    }
+
+    Ok(Catalog { sections: prune_empty(catalog_sections) })
+}
+
+fn translate(translations: &HashMap<TranslationKey, String>, entity_type: &str, id: &str, field: &str, value: &mut String) {
+    let key = (entity_type.to_string(), id.to_string(), field.to_string());
+    if let Some(translated) = translations.get(&key) {
+        *value = translated.clone();
+    }
+}
+
+/// Remove seções sem itens, a não ser que alguma subseção tenha itens
+/// (a seção mãe continua como título do capítulo).
+fn prune_empty(sections: Vec<CatalogSection>) -> Vec<CatalogSection> {
+    let mut kept: Vec<CatalogSection> = Vec::new();
+    for section in sections.into_iter().rev() {
+        let has_content_below = kept.last().is_some_and(|next| next.depth > section.depth);
+        if !section.items.is_empty() || has_content_below {
+            kept.push(section);
+        }
+    }
+    kept.reverse();
+    kept
+}
+
+type Group = (Section, usize, Vec<Item>);
+
+/// Para cada grupo, se a seção foi escolhida direta ou indiretamente (por uma seção mãe).
+/// Os grupos vêm em profundidade, então a mãe sempre aparece antes das filhas.
+fn picked_sections(groups: &[Group], section_ids: &[String]) -> Vec<bool> {
+    let mut picked_depth: Option<usize> = None;
+    groups
+        .iter()
+        .map(|(section, depth, _)| {
+            if picked_depth.is_some_and(|picked| *depth <= picked) {
+                picked_depth = None;
+            }
+            if picked_depth.is_none() && section_ids.contains(&section.id) {
+                picked_depth = Some(*depth);
+            }
+            picked_depth.is_some()
+        })
+        .collect()
+}
+
+// Sem seções nem itens escolhidos, todos os itens entram.
+fn is_picked(selection: &CatalogSelection, item: &Item, section_picked: bool) -> bool {
+    (selection.section_ids.is_empty() && selection.item_ids.is_empty())
+        || section_picked
+        || selection.item_ids.contains(&item.id)
+}
+
+fn matches_filters(selection: &CatalogSelection, entry: &CatalogItem) -> bool {
+    if let Some(text) = selection.text.as_deref().map(str::trim).filter(|text| !text.is_empty()) {
+        let text = text.to_lowercase();
+        let found = [&entry.item.code, &entry.item.description]
+            .into_iter()
+            .chain(entry.infos.iter().map(|info| &info.details))
+            .chain(entry.variants.iter().map(|variant| &variant.code))
+            .any(|value| value.to_lowercase().contains(&text));
+        if !found {
+            return false;
+        }
+    }
+
+    if selection.min_price.is_some() || selection.max_price.is_some() {
+        let in_range = entry.variants.iter().filter_map(|variant| variant.price).any(|price| {
+            selection.min_price.is_none_or(|min| price >= min) && selection.max_price.is_none_or(|max| price <= max)
+        });
+        if !in_range {
+            return false;
+        }
+    }
+    true
+}
+
+fn section_groups(conn: &Connection) -> Result<Vec<Group>> {
+    let mut groups = Vec::new();
+    for node in section_repository::list_section_tree(conn)? {
+        push_groups(conn, node, 0, &mut groups)?;
+    }
+    Ok(groups)
+}
+
+// Paleta e layout não definidos na subseção são herdados da seção mãe.
+fn push_groups(conn: &Connection, node: SectionNode, depth: usize, groups: &mut Vec<Group>) -> Result<()> {
+    let SectionNode { section, children } = node;
+    let mut items = item_repository::list_items(conn, &section.id)?;
+    items.sort_by(|a, b| a.code.cmp(&b.code));
+
+    let (palette, layout) = (section.palette.clone(), section.layout.clone());
+    groups.push((section, depth, items));
+
+    for mut child in children {
+        child.section.palette = child.section.palette.or_else(|| palette.clone());
+        child.section.layout = child.section.layout.or_else(|| layout.clone());
+        push_groups(conn, child, depth + 1, groups)?;
+    }
+    Ok(())
+}
+
+// A tag é apresentada como se fosse uma seção. Uma tag que não existe é
+// erro, e não um catálogo vazio.
+fn tag_group(conn: &Connection, tag_id: &str) -> Result<Vec<Group>> {
+    let Some(tag) = tag_repository::get_tag(conn, tag_id)? else {
+        return Err(validation_error(i18n::text("tag_not_found")));
+    };
+    let items = tag_repository::list_items_by_tag(conn, tag_id)?;
+    let section = Section {
+        id: tag.id,
+        name: tag.name,
+        parent_id: None,
+        description: None,
+        cover_image: None,
+        palette: None,
+        layout: None,
+        created_at: tag.created_at,
+        updated_at: tag.updated_at,
+    };
+    Ok(vec![(section, 0, items)])
+}
+
+fn catalog_item(conn: &Connection, item: Item, badge: Option<String>) -> Result<CatalogItem> {
+    Ok(CatalogItem {
+        infos: info_repository::list_infos(conn, &item.code)?,
+        info_names: Vec::new(),
+        variant_axes: variant_repository::list_axes(conn, &item.id)?,
+        variants: variant_repository::list_variants(conn, &item.id)?,
+        badge,
+        item,
+    })
+}
+
+// As datas ficam no formato do SQLite ("YYYY-MM-DD HH:MM:SS"), que pode ser
+// comparado como texto.
+fn new_arrival_cutoff(conn: &Connection, days: u32) -> Result<String> {
+    Ok(conn.query_row(
+        "SELECT datetime('now', ?1)",
+        [format!("-{} days", days)],
+        |row| row.get(0),
+    )?)
+}
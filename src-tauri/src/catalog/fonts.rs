@@ -0,0 +1,241 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dirs::data_dir;
+use printpdf::BuiltinFont;
+use serde::Serialize;
+
+use crate::i18n;
+
+pub const DEFAULT_FAMILY: &str = "Helvetica";
+
+// Fontes padrão do PDF: não são embutidas, todo leitor já tem. A última
+// coluna é a fonte embutida equivalente, usada quando tudo precisa ir no
+// arquivo (PDF/A).
+const BUILTIN_FAMILIES: [(&str, BuiltinFont, BuiltinFont, f32, &str); 3] = [
+    ("Helvetica", BuiltinFont::Helvetica, BuiltinFont::HelveticaBold, 0.5, "DejaVu Sans"),
+    ("Times", BuiltinFont::TimesRoman, BuiltinFont::TimesBold, 0.45, "DejaVu Serif"),
+    ("Courier", BuiltinFont::Courier, BuiltinFont::CourierBold, 0.6, "DejaVu Sans Mono"),
+];
+
+// Fontes que vão dentro do executável (licenças em src-tauri/fonts).
+const BUNDLED_FAMILIES: [(&str, &[u8], &[u8]); 3] = [
+    (
+        "DejaVu Sans",
+        include_bytes!("../../fonts/DejaVuSans.ttf"),
+        include_bytes!("../../fonts/DejaVuSans-Bold.ttf"),
+    ),
+    (
+        "DejaVu Sans Mono",
+        include_bytes!("../../fonts/DejaVuSansMono.ttf"),
+        include_bytes!("../../fonts/DejaVuSansMono-Bold.ttf"),
+    ),
+    (
+        "DejaVu Serif",
+        include_bytes!("../../fonts/DejaVuSerif.ttf"),
+        include_bytes!("../../fonts/DejaVuSerif-Bold.ttf"),
+    ),
+];
+
+const REGULAR_FILE: &str = "regular";
+const BOLD_FILE: &str = "bold";
+const FONT_EXTENSIONS: [&str; 2] = ["ttf", "otf"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FontKind {
+    Builtin,
+    Bundled,
+    Custom, // registrada pelo usuário
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FontFamily {
+    pub name: String,
+    pub kind: FontKind,
+    pub has_bold: bool,
+}
+
+/// Uma variação (regular ou negrito) pronta para ir para o PDF.
+pub enum FontData {
+    Builtin(BuiltinFont),
+    Embedded(Vec<u8>), // TTF/OTF, embutida só com os caracteres usados
+}
+
+/// Família carregada: regular, negrito e a largura média de um caractere
+/// (em relação ao tamanho da fonte), usada para quebrar linhas.
+pub struct LoadedFamily {
+    pub regular: FontData,
+    pub bold: FontData,
+    pub char_width: f32,
+}
+
+// As fontes do usuário ficam junto das configurações, uma pasta por família.
+fn fonts_dir() -> PathBuf {
+    let mut path: PathBuf = data_dir().unwrap();
+    path.push("BrilliantPDF");
+    path.push("fonts");
+    path
+}
+
+// Nome da pasta da família: letras, números, espaço, hífen e sublinhado.
+fn family_dir(name: &str) -> PathBuf {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    fonts_dir().join(name)
+}
+
+// Pasta de uma família já registrada, sem diferenciar maiúsculas.
+fn existing_family_dir(name: &str) -> Option<PathBuf> {
+    let wanted = family_dir(name).file_name()?.to_string_lossy().to_lowercase();
+    fs::read_dir(fonts_dir())
+        .ok()?
+        .flatten()
+        .find(|entry| entry.file_name().to_string_lossy().to_lowercase() == wanted)
+        .map(|entry| entry.path())
+}
+
+fn find_file(dir: &Path, stem: &str) -> Option<PathBuf> {
+    FONT_EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{}.{}", stem, extension)))
+        .find(|path| path.exists())
+}
+
+pub fn list_families() -> Vec<FontFamily> {
+    let mut families: Vec<FontFamily> = BUILTIN_FAMILIES
+        .iter()
+        .map(|(name, ..)| FontFamily { name: name.to_string(), kind: FontKind::Builtin, has_bold: true })
+        .chain(
+            BUNDLED_FAMILIES
+                .iter()
+                .map(|(name, ..)| FontFamily { name: name.to_string(), kind: FontKind::Bundled, has_bold: true }),
+        )
+        .collect();
+
+    let mut custom: Vec<FontFamily> = fs::read_dir(fonts_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| find_file(&entry.path(), REGULAR_FILE).is_some())
+        .map(|entry| FontFamily {
+            name: entry.file_name().to_string_lossy().to_string(),
+            kind: FontKind::Custom,
+            has_bold: find_file(&entry.path(), BOLD_FILE).is_some(),
+        })
+        .collect();
+    custom.sort_by_key(|family| family.name.to_lowercase());
+    families.extend(custom);
+    families
+}
+
+/// Copia os arquivos da fonte para a pasta de fontes do app. Sem negrito, a
+/// versão regular é usada nos títulos.
+pub fn register_family(name: &str, regular_path: &str, bold_path: Option<&str>) -> Result<FontFamily, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(i18n::text("font_name_empty").to_string());
+    }
+    if BUILTIN_FAMILIES.iter().any(|(builtin, ..)| builtin.eq_ignore_ascii_case(name))
+        || BUNDLED_FAMILIES.iter().any(|(bundled, ..)| bundled.eq_ignore_ascii_case(name))
+    {
+        return Err(i18n::message("font_name_taken", &[&name]));
+    }
+
+    let dir = family_dir(name);
+    let files = std::iter::once((REGULAR_FILE, regular_path)).chain(bold_path.map(|path| (BOLD_FILE, path)));
+    let mut copies = Vec::new();
+    for (stem, source) in files {
+        let bytes = fs::read(source).map_err(|e| i18n::message("read_file_failed", &[&source, &e]))?;
+        char_width(&bytes).ok_or_else(|| i18n::message("invalid_font_file", &[&source]))?;
+
+        let extension = Path::new(source)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .filter(|extension| FONT_EXTENSIONS.contains(&extension.as_str()))
+            .unwrap_or_else(|| "ttf".to_string());
+        copies.push((format!("{}.{}", stem, extension), bytes));
+    }
+
+    // Uma família registrada de novo substitui os arquivos anteriores.
+    if let Some(existing) = existing_family_dir(name) {
+        fs::remove_dir_all(&existing).map_err(|e| i18n::message("replace_font_failed", &[&e]))?;
+    }
+    fs::create_dir_all(&dir).map_err(|e| i18n::message("create_folder_failed", &[&e]))?;
+    for (file_name, bytes) in copies {
+        fs::write(dir.join(file_name), bytes).map_err(|e| i18n::message("save_font_failed", &[&e]))?;
+    }
+
+    Ok(FontFamily {
+        name: dir.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+        kind: FontKind::Custom,
+        has_bold: bold_path.is_some(),
+    })
+}
+
+pub fn remove_family(name: &str) -> Result<(), String> {
+    if let Some(dir) = existing_family_dir(name) {
+        fs::remove_dir_all(&dir).map_err(|e| i18n::message("remove_font_failed", &[&e]))?;
+    }
+    Ok(())
+}
+
+/// Carrega a família pelo nome (sem diferenciar maiúsculas); `None` usa a padrão.
+/// Com `embedded_only`, as fontes padrão do PDF dão lugar às equivalentes embutidas.
+pub fn load_family(name: Option<&str>, embedded_only: bool) -> Result<LoadedFamily, String> {
+    let mut name = name.map(str::trim).filter(|name| !name.is_empty()).unwrap_or(DEFAULT_FAMILY);
+
+    if let Some((_, regular, bold, char_width, substitute)) =
+        BUILTIN_FAMILIES.iter().find(|(builtin, ..)| builtin.eq_ignore_ascii_case(name))
+    {
+        if embedded_only {
+            name = substitute;
+        } else {
+            return Ok(LoadedFamily {
+                regular: FontData::Builtin(*regular),
+                bold: FontData::Builtin(*bold),
+                char_width: *char_width,
+            });
+        }
+    }
+
+    if let Some((_, regular, bold)) = BUNDLED_FAMILIES.iter().find(|(bundled, ..)| bundled.eq_ignore_ascii_case(name)) {
+        return Ok(LoadedFamily {
+            char_width: char_width(regular).unwrap_or(0.5),
+            regular: FontData::Embedded(regular.to_vec()),
+            bold: FontData::Embedded(bold.to_vec()),
+        });
+    }
+
+    let not_found = || i18n::message("font_not_found", &[&name]);
+    let dir = existing_family_dir(name).ok_or_else(not_found)?;
+    let regular_path = find_file(&dir, REGULAR_FILE).ok_or_else(not_found)?;
+    let regular = fs::read(&regular_path).map_err(|e| i18n::message("read_font_failed", &[&name, &e]))?;
+    let bold = match find_file(&dir, BOLD_FILE) {
+        Some(path) => fs::read(&path).map_err(|e| i18n::message("read_font_failed", &[&name, &e]))?,
+        None => regular.clone(),
+    };
+
+    Ok(LoadedFamily {
+        char_width: char_width(&regular).ok_or_else(|| i18n::message("invalid_font", &[&name]))?,
+        regular: FontData::Embedded(regular),
+        bold: FontData::Embedded(bold),
+    })
+}
+
+/// Largura média das letras minúsculas e do espaço, em relação ao tamanho da
+/// fonte. `None` se o arquivo não for uma fonte válida.
+fn char_width(bytes: &[u8]) -> Option<f32> {
+    let face = ttf_parser::Face::parse(bytes, 0).ok()?;
+    let sample = "abcdefghijklmnopqrstuvwxyz ";
+    let total: u32 = sample
+        .chars()
+        .filter_map(|c| face.glyph_index(c))
+        .filter_map(|glyph| face.glyph_hor_advance(glyph))
+        .map(u32::from)
+        .sum();
+    Some(total as f32 / sample.len() as f32 / face.units_per_em() as f32)
+}
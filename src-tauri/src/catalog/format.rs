@@ -0,0 +1,54 @@
+use crate::catalog::labels::label;
+use crate::models::attribute::{parse_stored_number, AttributeDefinition, AttributeType};
+
+/// Formata um preço em reais com os separadores do idioma:
+/// "R$ 1.234,50" (pt/es) ou "R$ 1,234.50" (en).
+pub fn format_price(value: f64, language: &str) -> String {
+    let (thousands, decimal) = match language {
+        "en" => (',', '.'),
+        _ => ('.', ','),
+    };
+
+    let cents = (value.abs() * 100.0).round() as u64;
+    let integer = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index).is_multiple_of(3) {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+
+    let sign = if value < 0.0 { "-" } else { "" };
+    format!("{}R$ {}{}{:02}", sign, grouped, decimal, cents % 100)
+}
+
+/// Número com até duas casas e o separador decimal do idioma.
+pub fn format_number(value: f64, language: &str) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match language {
+        "en" => text.to_string(),
+        _ => text.replace('.', ","),
+    }
+}
+
+/// Valor de uma info conforme o tipo da definição, ex: "4.5" -> "4,5 g", "true" -> "Sim".
+pub fn format_attribute(attribute: &AttributeDefinition, value: &str, language: &str) -> String {
+    match attribute.kind {
+        AttributeType::Number => parse_stored_number(value)
+            .map(|number| format_number(number, language))
+            .unwrap_or_else(|| value.to_string()),
+        AttributeType::Unit => match (parse_stored_number(value), attribute.unit.as_deref()) {
+            (Some(number), Some(unit)) if !unit.is_empty() => format!("{} {}", format_number(number, language), unit),
+            (Some(number), _) => format_number(number, language),
+            _ => value.to_string(),
+        },
+        AttributeType::Boolean => match value {
+            "true" => label("yes", language).to_string(),
+            "false" => label("no", language).to_string(),
+            _ => value.to_string(),
+        },
+        AttributeType::Text | AttributeType::Enum => value.to_string(),
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::catalog::builder::CatalogItem;
+
+/// Termo do índice remissivo e as páginas onde ele aparece.
+pub struct IndexEntry {
+    pub term: String,
+    pub pages: Vec<usize>,
+}
+
+/// Bloco do índice: os códigos ou os valores de uma info (ex: "Metal").
+pub struct IndexGroup {
+    pub title: String,
+    pub entries: Vec<IndexEntry>,
+}
+
+// Chave em minúsculas para ordenar sem diferenciar maiúsculas.
+type Terms = BTreeMap<(String, String), BTreeSet<usize>>;
+
+/// Junta, durante a paginação, os códigos (de itens e variantes) e os valores
+/// das infos escolhidas com as páginas onde foram desenhados.
+pub struct IndexBuilder {
+    info_names: Vec<String>,
+    codes: Terms,
+    infos: Vec<InfoTerms>, // um por nome em `info_names`
+}
+
+// Valores de uma info e o título do bloco: o nome da info no idioma do
+// catálogo, quando alguma aparece.
+#[derive(Default)]
+struct InfoTerms {
+    title: Option<String>,
+    terms: Terms,
+}
+
+impl IndexBuilder {
+    pub fn new(info_names: &[String]) -> Self {
+        let info_names: Vec<String> = info_names
+            .iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let infos = info_names.iter().map(|_| InfoTerms::default()).collect();
+        Self { info_names, codes: Terms::new(), infos }
+    }
+
+    pub fn add_item(&mut self, entry: &CatalogItem, page: usize) {
+        add_term(&mut self.codes, &entry.item.code, page);
+        for variant in &entry.variants {
+            add_term(&mut self.codes, &variant.code, page);
+        }
+
+        // Compara com o nome cadastrado (antes da tradução), sem diferenciar
+        // maiúsculas, inclusive acentuadas.
+        for (info, name) in entry.infos.iter().zip(&entry.info_names) {
+            let name = name.trim().to_lowercase();
+            let position = self.info_names.iter().position(|wanted| wanted.to_lowercase() == name);
+            if let Some(position) = position {
+                let group = &mut self.infos[position];
+                group.title.get_or_insert_with(|| info.name.trim().to_string());
+                add_term(&mut group.terms, &info.details, page);
+            }
+        }
+    }
+
+    /// Grupos na ordem de impressão; grupos vazios ficam de fora.
+    pub fn groups(self, codes_title: &str) -> Vec<IndexGroup> {
+        let infos = self.info_names.into_iter().zip(self.infos).map(|(name, info)| (info.title.unwrap_or(name), info.terms));
+        std::iter::once((codes_title.to_string(), self.codes))
+            .chain(infos)
+            .filter(|(_, terms)| !terms.is_empty())
+            .map(|(title, terms)| IndexGroup {
+                title,
+                entries: terms
+                    .into_iter()
+                    .map(|((_, term), pages)| IndexEntry { term, pages: pages.into_iter().collect() })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+fn add_term(terms: &mut Terms, term: &str, page: usize) {
+    let term = term.trim();
+    if term.is_empty() {
+        return;
+    }
+    terms.entry((term.to_lowercase(), term.to_string())).or_default().insert(page);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::build_catalog;
+    use crate::catalog::options::CatalogOptions;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{info_repository, item_repository, section_repository, translation_repository};
+
+    #[test]
+    fn infos_are_matched_by_their_untranslated_name() {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap();
+        info_repository::insert_info(&conn, "materia", "AN-1", "MATÉRIA", "Ouro").unwrap();
+        info_repository::insert_info(&conn, "material", "AN-1", "Material", "Prata").unwrap();
+        translation_repository::set_translation(&conn, "info", "materia", "name", "en", "Substance").unwrap();
+        translation_repository::set_translation(&conn, "info", "materia", "details", "en", "Gold").unwrap();
+
+        let options = CatalogOptions { language: Some("en".into()), ..Default::default() };
+        let catalog = build_catalog(&conn, &options).unwrap();
+        let mut index = IndexBuilder::new(&["matéria".to_string(), "Cor".to_string()]);
+        index.add_item(&catalog.sections[0].items[0], 3);
+
+        let groups = index.groups("Codes");
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].title, "Substance");
+        let terms: Vec<(&str, &[usize])> = groups[1].entries.iter().map(|entry| (entry.term.as_str(), entry.pages.as_slice())).collect();
+        assert_eq!(terms, vec![("Gold", [3].as_slice())]);
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::catalog::options::{CatalogOptions, LabelOptions, LabelSheet, LabelSheetFormat};
+use crate::catalog::print::LowResolutionImage;
+use crate::catalog::screen::ScreenReport;
+use crate::catalog::render::{render_pages, ImageFilter};
+use crate::catalog::sheet;
+use crate::catalog::{build_catalog, expand_file_name, output_path, today, Catalog, DEFAULT_FILE_NAME, LABELS_FILE_NAME};
+use crate::db::label_preset_repository;
+use crate::i18n;
+use crate::settings::settings_repository::Settings;
+
+const SPLIT_FILE_NAME: &str = "{section}_{date}.pdf";
+
+/// Etapa atual da geração.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Loading,   // lendo seções, itens e infos do banco
+    Rendering, // desenhando as páginas
+    Saving,    // gravando o PDF
+}
+
+/// Progresso enviado à janela. Seções e itens contam a partir de 1.
+#[derive(Clone, Debug, Serialize)]
+pub struct Progress {
+    pub phase: Phase,
+    pub section: usize,
+    pub section_count: usize,
+    pub item: usize,
+    pub item_count: usize,
+}
+
+/// Recebe o progresso da geração e diz se ela deve parar.
+pub trait Monitor {
+    fn report(&self, progress: &Progress);
+    fn is_cancelled(&self) -> bool;
+}
+
+/// Repassa o progresso no máximo a cada `interval`. Mudanças de etapa e o
+/// último item sempre passam, para a barra não parar antes do fim.
+pub struct Throttled<M: Monitor> {
+    inner: M,
+    interval: Duration,
+    last: Mutex<Option<(Instant, Phase)>>,
+}
+
+impl<M: Monitor> Throttled<M> {
+    pub fn new(inner: M, interval: Duration) -> Self {
+        Throttled { inner, interval, last: Mutex::new(None) }
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
+
+impl<M: Monitor> Monitor for Throttled<M> {
+    fn report(&self, progress: &Progress) {
+        let now = Instant::now();
+        let mut last = self.last.lock().unwrap();
+        let due = match *last {
+            Some((at, phase)) => {
+                phase != progress.phase
+                    || progress.item == progress.item_count
+                    || now.duration_since(at) >= self.interval
+            }
+            None => true,
+        };
+        if due {
+            *last = Some((now, progress.phase));
+            self.inner.report(progress);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+}
+
+/// Geração sem acompanhamento (comandos síncronos, prévia).
+pub struct Silent;
+
+impl Monitor for Silent {
+    fn report(&self, _progress: &Progress) {}
+
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Erro de uma geração, com um código para o frontend decidir o que mostrar.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum JobError {
+    Cancelled,
+    Database(String),
+    Render(String),
+    Io(String),
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobError::Cancelled => write!(f, "{}", i18n::text("generation_cancelled")),
+            JobError::Database(message) | JobError::Render(message) | JobError::Io(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+/// Evento final de uma geração em segundo plano: arquivos gravados ou erro.
+#[derive(Clone, Debug, Serialize)]
+pub struct JobFinished {
+    pub job_id: String,
+    pub files: Vec<GeneratedFile>,
+    pub error: Option<JobError>,
+    pub low_resolution: Vec<LowResolutionImage>,
+}
+
+/// PDF gravado; no modo dividido, `section` é a seção de primeiro nível do arquivo.
+#[derive(Clone, Debug, Serialize)]
+pub struct GeneratedFile {
+    pub path: String,
+    pub section: Option<String>,
+    /// Só no perfil de tela: tamanho final e qualidade das imagens.
+    pub screen: Option<ScreenReport>,
+}
+
+/// Arquivos gravados (o completo primeiro) e os avisos de imagem do perfil de gráfica.
+pub struct GeneratedCatalog {
+    pub files: Vec<GeneratedFile>,
+    pub low_resolution: Vec<LowResolutionImage>,
+}
+
+/// Progresso de uma parte do catálogo dividido, somado ao das partes já geradas.
+struct PartMonitor<'a> {
+    inner: &'a dyn Monitor,
+    sections_done: usize,
+    items_done: usize,
+    section_count: usize,
+    item_count: usize,
+}
+
+impl Monitor for PartMonitor<'_> {
+    fn report(&self, progress: &Progress) {
+        self.inner.report(&Progress {
+            phase: progress.phase,
+            section: self.sections_done + progress.section,
+            section_count: self.section_count,
+            item: self.items_done + progress.item,
+            item_count: self.item_count,
+        });
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+}
+
+/// Monta, desenha e salva o catálogo (ou, no modo dividido, um arquivo por
+/// seção). O banco só fica travado enquanto os dados são lidos.
+pub fn generate(
+    db: &Mutex<Connection>,
+    settings: &Settings,
+    options: &CatalogOptions,
+    preset_name: &str,
+    monitor: &dyn Monitor,
+) -> Result<GeneratedCatalog, JobError> {
+    monitor.report(&Progress { phase: Phase::Loading, section: 0, section_count: 0, item: 0, item_count: 0 });
+    let (catalog, today) = {
+        let conn = db.lock().unwrap();
+        let catalog = build_catalog(&conn, options).map_err(|e| JobError::Database(e.to_string()))?;
+        (catalog, today(&conn).map_err(|e| JobError::Database(e.to_string()))?)
+    };
+    check_cancelled(monitor)?;
+
+    let file_name = |pattern: &str, section: &str| {
+        expand_file_name(pattern, section, preset_name, &today, options.language(), DEFAULT_FILE_NAME)
+    };
+    let main_file_name = options.file_name.as_deref().map(|pattern| file_name(pattern, ""));
+    // Seção, catálogo e nome do arquivo de cada PDF.
+    let parts = match &options.split {
+        None => vec![(None, catalog, main_file_name)],
+        Some(split) => {
+            let pattern = split.file_name.as_deref().filter(|pattern| !pattern.trim().is_empty()).unwrap_or(SPLIT_FILE_NAME);
+            let sections = catalog.split_by_section();
+            let mut parts = Vec::new();
+            if split.combined {
+                parts.push((None, catalog, main_file_name));
+            }
+            for part in sections {
+                let name = part.sections[0].section.name.clone();
+                let part_file_name = file_name(pattern, &name);
+                parts.push((Some(name), part, Some(part_file_name)));
+            }
+            parts
+        }
+    };
+
+    let mut part_monitor = PartMonitor {
+        inner: monitor,
+        sections_done: 0,
+        items_done: 0,
+        section_count: parts.iter().map(|(_, part, _)| part.sections.len()).sum(),
+        item_count: parts.iter().map(|(_, part, _)| item_count(part)).sum(),
+    };
+    let mut files = Vec::new();
+    let mut low_resolution: Vec<LowResolutionImage> = Vec::new();
+    let mut used_paths = HashSet::new();
+    for (section, part, part_file_name) in parts {
+        let rendered = render_pages(&part, settings, options, &ImageFilter::All, &part_monitor).map_err(|message| {
+            if monitor.is_cancelled() {
+                JobError::Cancelled
+            } else {
+                JobError::Render(message)
+            }
+        })?;
+
+        part_monitor.sections_done += part.sections.len();
+        part_monitor.items_done += item_count(&part);
+        monitor.report(&Progress {
+            phase: Phase::Saving,
+            section: part_monitor.sections_done,
+            section_count: part_monitor.section_count,
+            item: part_monitor.items_done,
+            item_count: part_monitor.item_count,
+        });
+        // Cancelado depois da última página: nada é gravado.
+        check_cancelled(monitor)?;
+        let path = unique_path(output_path(settings, part_file_name.as_deref()), &mut used_paths);
+        save(&path, &rendered.bytes)?;
+
+        // O catálogo completo vem primeiro, então as páginas dos avisos são as dele.
+        for image in rendered.low_resolution {
+            if !low_resolution.iter().any(|seen| seen.item_id == image.item_id) {
+                low_resolution.push(image);
+            }
+        }
+        files.push(GeneratedFile { path: path.display().to_string(), section, screen: rendered.screen });
+    }
+
+    Ok(GeneratedCatalog { files, low_resolution })
+}
+
+/// Monta e salva a folha de etiquetas; devolve o caminho do PDF.
+pub fn generate_labels(db: &Mutex<Connection>, settings: &Settings, options: &LabelOptions) -> Result<String, JobError> {
+    let (catalog, sheet, today) = {
+        let conn = db.lock().unwrap();
+        let catalog_options = CatalogOptions {
+            language: options.language.clone(),
+            selection: Some(options.selection.clone()),
+            ..Default::default()
+        };
+        let catalog = build_catalog(&conn, &catalog_options).map_err(|e| JobError::Database(e.to_string()))?;
+        let sheet = label_sheet(&conn, &options.sheet)?;
+        (catalog, sheet, today(&conn).map_err(|e| JobError::Database(e.to_string()))?)
+    };
+
+    let labels = sheet::labels(&catalog, settings, options);
+    if labels.is_empty() {
+        return Err(JobError::Render(i18n::text("labels_no_items").to_string()));
+    }
+    let bytes = sheet::render_labels(&labels, &sheet, settings, options).map_err(JobError::Render)?;
+
+    let pattern = options.file_name.as_deref().filter(|pattern| !pattern.trim().is_empty()).unwrap_or(LABELS_FILE_NAME);
+    let file_name = expand_file_name(pattern, "", "", &today, options.language(), LABELS_FILE_NAME);
+    let path = output_path(settings, Some(&file_name));
+    save(&path, &bytes)?;
+    Ok(path.display().to_string())
+}
+
+/// Medidas da folha escolhida; as dos presets vêm do banco.
+fn label_sheet(conn: &Connection, format: &LabelSheetFormat) -> Result<LabelSheet, JobError> {
+    match format {
+        LabelSheetFormat::Standard { model } => Ok(model.sheet()),
+        LabelSheetFormat::Custom(sheet) => Ok(sheet.clone()),
+        LabelSheetFormat::Preset { id } => label_preset_repository::get_label_preset(conn, id)
+            .map_err(|e| JobError::Database(e.to_string()))?
+            .map(|preset| preset.sheet)
+            .ok_or_else(|| JobError::Database(i18n::text("label_preset_not_found").to_string())),
+    }
+}
+
+fn check_cancelled(monitor: &dyn Monitor) -> Result<(), JobError> {
+    if monitor.is_cancelled() {
+        Err(JobError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+fn item_count(catalog: &Catalog) -> usize {
+    catalog.sections.iter().map(|section| section.items.len()).sum()
+}
+
+/// Duas seções com o mesmo nome não podem gravar no mesmo arquivo.
+fn unique_path(path: PathBuf, used: &mut HashSet<PathBuf>) -> PathBuf {
+    let mut candidate = path.clone();
+    let mut copy = 1;
+    while !used.insert(candidate.clone()) {
+        copy += 1;
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        candidate = path.with_file_name(format!("{}-{}", stem, copy));
+        if let Some(extension) = path.extension() {
+            candidate.set_extension(extension);
+        }
+    }
+    candidate
+}
+
+fn save(path: &Path, bytes: &[u8]) -> Result<(), JobError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| JobError::Io(i18n::message("create_folder_failed", &[&e])))?;
+    }
+    fs::write(path, bytes).map_err(|e| JobError::Io(i18n::message("save_catalog_failed", &[&e])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use crate::db::connection::open_in_memory;
+    use crate::db::{item_repository, section_repository};
+
+    #[derive(Default)]
+    struct Recorder {
+        reports: RefCell<Vec<Progress>>,
+        cancel_after: Option<usize>,
+        checks: Cell<usize>,
+    }
+
+    impl Monitor for Recorder {
+        fn report(&self, progress: &Progress) {
+            self.reports.borrow_mut().push(progress.clone());
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.checks.set(self.checks.get() + 1);
+            self.cancel_after.is_some_and(|after| self.checks.get() > after)
+        }
+    }
+
+    impl Monitor for &Recorder {
+        fn report(&self, progress: &Progress) {
+            (*self).report(progress)
+        }
+
+        fn is_cancelled(&self) -> bool {
+            (*self).is_cancelled()
+        }
+    }
+
+    fn progress(phase: Phase, item: usize) -> Progress {
+        Progress { phase, section: 1, section_count: 1, item, item_count: 10 }
+    }
+
+    #[test]
+    fn throttled_monitor_skips_frequent_reports_but_keeps_phase_changes_and_the_end() {
+        let recorder = Recorder::default();
+        let monitor = Throttled::new(&recorder, Duration::from_secs(60));
+        monitor.report(&progress(Phase::Loading, 0));
+        for item in 1..=10 {
+            monitor.report(&progress(Phase::Rendering, item));
+        }
+        monitor.report(&progress(Phase::Saving, 10));
+
+        let items: Vec<(Phase, usize)> = recorder.reports.borrow().iter().map(|p| (p.phase, p.item)).collect();
+        assert_eq!(items, vec![(Phase::Loading, 0), (Phase::Rendering, 1), (Phase::Rendering, 10), (Phase::Saving, 10)]);
+    }
+
+    #[test]
+    fn cancelling_during_loading_stops_before_rendering() {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap();
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section.id, String::new()).unwrap();
+
+        let recorder = Recorder { cancel_after: Some(0), ..Default::default() };
+        let db = Mutex::new(conn);
+        let result = generate(&db, &Settings::default(), &CatalogOptions::default(), "", &recorder);
+
+        assert!(matches!(result, Err(JobError::Cancelled)));
+        assert!(recorder.reports.borrow().iter().all(|p| p.phase == Phase::Loading));
+    }
+}
@@ -0,0 +1,46 @@
+/// Textos fixos impressos no catálogo, por idioma ("pt", "en", "es").
+pub fn label(key: &str, language: &str) -> &'static str {
+    match (key, language) {
+        ("new_arrival", "en") => "New",
+        ("new_arrival", "es") => "Novedad",
+        ("new_arrival", _) => "Novidade",
+        ("code", "en") => "Code",
+        ("code", _) => "Código",
+        ("price", "en") => "Price",
+        ("price", "es") => "Precio",
+        ("price", _) => "Preço",
+        ("on_request", "en") => "On request",
+        ("on_request", "es") => "A consultar",
+        ("on_request", _) => "Sob consulta",
+        ("price_from", "en") => "From",
+        ("price_from", "es") => "Desde",
+        ("price_from", _) => "A partir de",
+        ("catalog", "en") => "Catalog",
+        ("catalog", _) => "Catálogo",
+        ("labels", "en") => "Labels",
+        ("labels", _) => "Etiquetas",
+        ("catalog_subject", "en") => "Product catalog",
+        ("catalog_subject", "es") => "Catálogo de productos",
+        ("catalog_subject", _) => "Catálogo de produtos",
+        ("contents", "en") => "Contents",
+        ("contents", "es") => "Índice",
+        ("contents", _) => "Sumário",
+        ("index", "en") => "Index",
+        ("index", "es") => "Índice alfabético",
+        ("index", _) => "Índice",
+        ("page", "en") => "Page",
+        ("page", "es") => "Página",
+        ("page", _) => "Página",
+        ("whatsapp_interest", "en") => "I'm interested in item {code}",
+        ("whatsapp_interest", "es") => "Me interesa el artículo {code}",
+        ("whatsapp_interest", _) => "Tenho interesse no item {code}",
+        ("codes", "en") => "Codes",
+        ("codes", _) => "Códigos",
+        ("yes", "en") => "Yes",
+        ("yes", "es") => "Sí",
+        ("yes", _) => "Sim",
+        ("no", "pt") => "Não",
+        ("no", _) => "No",
+        _ => "",
+    }
+}
@@ -0,0 +1,645 @@
+use std::collections::{HashMap, HashSet};
+
+use printpdf::lopdf::xref::XrefEntry;
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId};
+
+use crate::catalog::metadata::BINARY_COMMENT;
+
+/// Grava o documento linearizado ("visualização rápida na web"): a primeira
+/// página e tudo o que ela usa vêm logo no começo, com uma tabela de
+/// referências só para eles, e o leitor mostra a página antes de baixar o resto.
+///
+/// Ordem do arquivo: dicionário de linearização, tabela da primeira página,
+/// catálogo (com marcadores e camadas), tabelas de dicas, primeira página,
+/// demais páginas, objetos usados por mais de uma página e o resto.
+pub fn linearize(mut doc: Document) -> Result<Vec<u8>, String> {
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    if pages.is_empty() {
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).map_err(|e| e.to_string())?;
+        return Ok(bytes);
+    }
+    let root = doc.trailer.get(b"Root").and_then(Object::as_reference).map_err(|e| e.to_string())?;
+    let pages_root = doc.catalog().and_then(|catalog| catalog.get(b"Pages")).and_then(Object::as_reference).map_err(|e| e.to_string())?;
+    let page_set: HashSet<ObjectId> = pages.iter().copied().collect();
+
+    // Catálogo e o que ele usa, menos a árvore de páginas.
+    let mut stop = page_set.clone();
+    stop.insert(pages_root);
+    let document_level = reachable(&doc, root, &stop);
+    let mut placed: HashSet<ObjectId> = document_level.iter().copied().collect();
+
+    let first_page: Vec<ObjectId> = reachable(&doc, pages[0], &page_set).into_iter().filter(|id| !placed.contains(id)).collect();
+    placed.extend(&first_page);
+
+    // Nas outras páginas, o que só uma página usa fica junto dela; o resto vai
+    // para a seção de objetos compartilhados.
+    let page_objects: Vec<Vec<ObjectId>> = pages[1..].iter().map(|&page| reachable(&doc, page, &page_set)).collect();
+    let mut users: HashMap<ObjectId, usize> = HashMap::new();
+    for id in page_objects.iter().flatten().filter(|id| !placed.contains(id)) {
+        *users.entry(*id).or_default() += 1;
+    }
+    let private: Vec<Vec<ObjectId>> = page_objects
+        .iter()
+        .map(|objects| objects.iter().copied().filter(|id| users.get(id) == Some(&1)).collect())
+        .collect();
+    let mut shared = Vec::new();
+    for id in page_objects.iter().flatten() {
+        if users.get(id).is_some_and(|&count| count > 1) && placed.insert(*id) {
+            shared.push(*id);
+        }
+    }
+    placed.extend(private.iter().flatten());
+    let other: Vec<ObjectId> = doc.objects.keys().copied().filter(|id| !placed.contains(id)).collect();
+
+    // A tabela principal (no fim) tem os números baixos; a da primeira página,
+    // os altos, na ordem em que aparecem no arquivo.
+    let main: Vec<ObjectId> = private.iter().flatten().chain(&shared).chain(&other).copied().collect();
+    let main_count = main.len() as u32 + 1;
+    let linearization_id = main_count;
+    let hint_id = linearization_id + 1 + document_level.len() as u32;
+    let size = hint_id + 1 + first_page.len() as u32;
+
+    let mut numbers: HashMap<ObjectId, u32> = HashMap::new();
+    for (index, id) in main.iter().enumerate() {
+        numbers.insert(*id, index as u32 + 1);
+    }
+    for (index, id) in document_level.iter().enumerate() {
+        numbers.insert(*id, linearization_id + 1 + index as u32);
+    }
+    for (index, id) in first_page.iter().enumerate() {
+        numbers.insert(*id, hint_id + 1 + index as u32);
+    }
+    let new_id = |id: &ObjectId| numbers[id];
+    let contents: Vec<Vec<ObjectId>> = pages.iter().map(|page| page_contents(&doc, *page)).collect();
+
+    let chunks = write_objects(&mut doc, &numbers, size)?;
+    let length_of = |ids: &[ObjectId]| ids.iter().map(|id| chunks[&new_id(id)].len()).sum::<usize>();
+
+    let header = [format!("%PDF-{}\n", doc.version).as_bytes(), BINARY_COMMENT].concat();
+    let trailer = trailer_entries(&doc.trailer);
+
+    // Os campos numéricos têm largura fixa, então o tamanho dessas partes não
+    // depende dos valores preenchidos depois.
+    let linearization_length = linearization_dict(linearization_id, &Linearization::default()).len();
+    let first_xref_length = first_page_xref(linearization_id, size, &trailer, &[], 0).len();
+
+    // Os endereços das tabelas de dicas contam como se elas não existissem.
+    let document_end = header.len() + linearization_length + first_xref_length + length_of(&document_level);
+    let first_page_end = document_end + length_of(&first_page);
+    let private_end = first_page_end + private.iter().map(|ids| length_of(ids)).sum::<usize>();
+
+    let chunk_length = |id: &ObjectId| chunks[&new_id(id)].len();
+    let (content_offset, content_length) = content_range(&contents[0], &first_page, chunk_length);
+    let mut page_entries = vec![PageEntry {
+        objects: first_page.len(),
+        length: length_of(&first_page),
+        shared: Vec::new(),
+        content_offset,
+        content_length,
+    }];
+    let shared_index: HashMap<ObjectId, usize> =
+        first_page.iter().chain(&shared).enumerate().map(|(index, id)| (*id, index)).collect();
+    for ((contents, objects), private) in contents[1..].iter().zip(&page_objects).zip(&private) {
+        let (content_offset, content_length) = content_range(contents, private, chunk_length);
+        page_entries.push(PageEntry {
+            objects: private.len(),
+            length: length_of(private),
+            shared: objects.iter().filter_map(|id| shared_index.get(id).copied()).collect(),
+            content_offset,
+            content_length,
+        });
+    }
+    let group_lengths: Vec<usize> = first_page.iter().chain(&shared).map(|id| chunks[&new_id(id)].len()).collect();
+    let shared_start = shared.first().map(|id| (new_id(id), private_end));
+    let hint = hint_stream(hint_id, &page_entries, document_end, &group_lengths, first_page.len(), shared_start);
+
+    // Endereços finais.
+    let first_xref_offset = header.len() + linearization_length;
+    let mut offset = first_xref_offset + first_xref_length;
+    let mut first_offsets = vec![header.len()];
+    for id in &document_level {
+        first_offsets.push(offset);
+        offset += chunks[&new_id(id)].len();
+    }
+    let hint_offset = offset;
+    first_offsets.push(hint_offset);
+    offset += hint.len();
+    for id in &first_page {
+        first_offsets.push(offset);
+        offset += chunks[&new_id(id)].len();
+    }
+    let first_section_end = offset;
+    let mut main_offsets = Vec::new();
+    for id in &main {
+        main_offsets.push(offset);
+        offset += chunks[&new_id(id)].len();
+    }
+    let main_xref = main_xref(main_count, &main_offsets, first_xref_offset);
+    let main_xref_offset = offset;
+    let total = main_xref_offset + main_xref.len();
+
+    let linearization = Linearization {
+        length: total,
+        hint_offset,
+        hint_length: hint.len(),
+        first_page: new_id(&first_page[0]),
+        first_page_end: first_section_end,
+        page_count: pages.len(),
+        main_xref_entries: main_xref_offset + format!("xref\n0 {}\n", main_count).len(),
+    };
+
+    let mut bytes = Vec::with_capacity(total);
+    bytes.extend_from_slice(&header);
+    bytes.extend_from_slice(&linearization_dict(linearization_id, &linearization));
+    bytes.extend_from_slice(&first_page_xref(linearization_id, size, &trailer, &first_offsets, main_xref_offset));
+    for id in &document_level {
+        bytes.extend_from_slice(&chunks[&new_id(id)]);
+    }
+    bytes.extend_from_slice(&hint);
+    for id in first_page.iter().chain(&main) {
+        bytes.extend_from_slice(&chunks[&new_id(id)]);
+    }
+    bytes.extend_from_slice(&main_xref);
+    Ok(bytes)
+}
+
+fn page_contents(doc: &Document, page: ObjectId) -> Vec<ObjectId> {
+    let mut contents = Vec::new();
+    if let Ok(value) = doc.get_dictionary(page).and_then(|page| page.get(b"Contents")) {
+        collect_references(value, &mut contents);
+    }
+    contents
+}
+
+/// Onde ficam os fluxos de conteúdo da página dentro da parte dela no arquivo:
+/// distância do primeiro objeto da página e tamanho do primeiro ao último
+/// fluxo. Sem o conteúdo na parte da página, vale a página inteira.
+fn content_range(contents: &[ObjectId], objects: &[ObjectId], length: impl Fn(&ObjectId) -> usize) -> (usize, usize) {
+    let positions: Vec<usize> = contents.iter().filter_map(|id| objects.iter().position(|object| object == id)).collect();
+    match (positions.iter().min(), positions.iter().max()) {
+        (Some(&first), Some(&last)) => (
+            objects[..first].iter().map(&length).sum(),
+            objects[first..=last].iter().map(&length).sum(),
+        ),
+        _ => (0, objects.iter().map(&length).sum()),
+    }
+}
+
+/// Objetos alcançáveis a partir de `start`, na ordem em que aparecem, sem
+/// subir pelo `/Parent` e sem entrar nas páginas de `stop`.
+fn reachable(doc: &Document, start: ObjectId, stop: &HashSet<ObjectId>) -> Vec<ObjectId> {
+    let mut found = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending = vec![start];
+    while let Some(id) = pending.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let Ok(object) = doc.get_object(id) else {
+            continue;
+        };
+        found.push(id);
+        let mut references = Vec::new();
+        collect_references(object, &mut references);
+        pending.extend(references.into_iter().rev().filter(|id| !stop.contains(id)));
+    }
+    found
+}
+
+fn collect_references(object: &Object, references: &mut Vec<ObjectId>) {
+    match object {
+        Object::Reference(id) => references.push(*id),
+        Object::Array(items) => items.iter().for_each(|item| collect_references(item, references)),
+        Object::Dictionary(dict) => collect_dict_references(dict, references),
+        Object::Stream(stream) => collect_dict_references(&stream.dict, references),
+        _ => {}
+    }
+}
+
+fn collect_dict_references(dict: &Dictionary, references: &mut Vec<ObjectId>) {
+    for (key, value) in dict.iter() {
+        if key != b"Parent" {
+            collect_references(value, references);
+        }
+    }
+}
+
+fn renumber(object: &mut Object, numbers: &HashMap<ObjectId, u32>) {
+    match object {
+        Object::Reference(id) => match numbers.get(id) {
+            Some(&number) => *id = (number, 0),
+            None => *object = Object::Null, // referência a objeto que não existe
+        },
+        Object::Array(items) => items.iter_mut().for_each(|item| renumber(item, numbers)),
+        Object::Dictionary(dict) => dict.iter_mut().for_each(|(_, value)| renumber(value, numbers)),
+        Object::Stream(stream) => stream.dict.iter_mut().for_each(|(_, value)| renumber(value, numbers)),
+        _ => {}
+    }
+}
+
+/// Renumera o documento e devolve cada objeto já escrito ("N 0 obj ... endobj"),
+/// pelo número novo. O lopdf não expõe o escritor de objetos, então o
+/// documento é salvo inteiro e recortado pelos endereços da tabela.
+fn write_objects(doc: &mut Document, numbers: &HashMap<ObjectId, u32>, size: u32) -> Result<HashMap<u32, Vec<u8>>, String> {
+    let objects = std::mem::take(&mut doc.objects);
+    doc.objects = objects
+        .into_iter()
+        .map(|(id, mut object)| {
+            renumber(&mut object, numbers);
+            ((numbers[&id], 0), object)
+        })
+        .collect();
+    for (_, value) in doc.trailer.iter_mut() {
+        renumber(value, numbers);
+    }
+    doc.max_id = size - 1;
+
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).map_err(|e| e.to_string())?;
+    let saved = Document::load_mem(&bytes).map_err(|e| e.to_string())?;
+
+    let mut offsets: Vec<(usize, u32)> = saved
+        .reference_table
+        .entries
+        .iter()
+        .filter_map(|(&id, entry)| match entry {
+            XrefEntry::Normal { offset, .. } => Some((*offset as usize, id)),
+            _ => None,
+        })
+        .collect();
+    offsets.sort_unstable();
+    let mut chunks = HashMap::new();
+    for (index, &(start, id)) in offsets.iter().enumerate() {
+        let end = offsets.get(index + 1).map_or(saved.xref_start, |&(next, _)| next);
+        chunks.insert(id, bytes[start..end].to_vec());
+    }
+    Ok(chunks)
+}
+
+fn trailer_entries(trailer: &Dictionary) -> String {
+    let mut text = String::new();
+    for key in ["Root", "Info"] {
+        if let Ok(Object::Reference((id, generation))) = trailer.get(key.as_bytes()) {
+            text.push_str(&format!("/{} {} {} R", key, id, generation));
+        }
+    }
+    if let Ok(Object::Array(ids)) = trailer.get(b"ID") {
+        text.push_str("/ID[");
+        for id in ids {
+            if let Object::String(bytes, _) = id {
+                let hex: String = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+                text.push_str(&format!("<{}>", hex));
+            }
+        }
+        text.push(']');
+    }
+    text
+}
+
+#[derive(Default)]
+struct Linearization {
+    length: usize,
+    hint_offset: usize,
+    hint_length: usize,
+    first_page: u32,
+    first_page_end: usize,
+    page_count: usize,
+    main_xref_entries: usize,
+}
+
+fn linearization_dict(id: u32, values: &Linearization) -> Vec<u8> {
+    format!(
+        "{} 0 obj\n<</Linearized 1/L {:010}/H [{:010} {:010}]/O {:010}/E {:010}/N {:010}/T {:010}>>\nendobj\n",
+        id,
+        values.length,
+        values.hint_offset,
+        values.hint_length,
+        values.first_page,
+        values.first_page_end,
+        values.page_count,
+        values.main_xref_entries
+    )
+    .into_bytes()
+}
+
+fn first_page_xref(first_id: u32, size: u32, trailer: &str, offsets: &[usize], main_xref_offset: usize) -> Vec<u8> {
+    let count = size - first_id;
+    let mut text = format!("xref\n{} {}\n", first_id, count);
+    for index in 0..count as usize {
+        text.push_str(&format!("{:010} 00000 n \n", offsets.get(index).copied().unwrap_or_default()));
+    }
+    text.push_str(&format!(
+        "trailer\n<</Size {}{}/Prev {:010}>>\nstartxref\n0\n%%EOF\n",
+        size, trailer, main_xref_offset
+    ));
+    text.into_bytes()
+}
+
+fn main_xref(count: u32, offsets: &[usize], first_xref_offset: usize) -> Vec<u8> {
+    let mut text = format!("xref\n0 {}\n0000000000 65535 f \n", count);
+    for offset in offsets {
+        text.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    text.push_str(&format!("trailer\n<</Size {}>>\nstartxref\n{}\n%%EOF\n", count, first_xref_offset));
+    text.into_bytes()
+}
+
+/// Objetos da página (na primeira, todos os da seção dela), tamanho em bytes,
+/// índices, na tabela de compartilhados, dos objetos que ela usa de fora e
+/// posição e tamanho dos fluxos de conteúdo.
+struct PageEntry {
+    objects: usize,
+    length: usize,
+    shared: Vec<usize>,
+    content_offset: usize,
+    content_length: usize,
+}
+
+/// Tabelas de dicas de páginas e de objetos compartilhados (anexo F da
+/// especificação), com cada objeto compartilhado num grupo próprio.
+fn hint_stream(
+    id: u32,
+    pages: &[PageEntry],
+    first_page_offset: usize,
+    group_lengths: &[usize],
+    first_page_groups: usize,
+    shared_start: Option<(u32, usize)>,
+) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+
+    let least_objects = pages.iter().map(|page| page.objects).min().unwrap_or_default();
+    let most_objects = pages.iter().map(|page| page.objects).max().unwrap_or_default();
+    let least_length = pages.iter().map(|page| page.length).min().unwrap_or_default();
+    let most_length = pages.iter().map(|page| page.length).max().unwrap_or_default();
+    let most_shared = pages.iter().map(|page| page.shared.len()).max().unwrap_or_default();
+    let objects_bits = bit_count(most_objects - least_objects);
+    let length_bits = bit_count(most_length - least_length);
+    let shared_bits = bit_count(most_shared);
+    let identifier_bits = bit_count(group_lengths.len().saturating_sub(1));
+    let least_offset = pages.iter().map(|page| page.content_offset).min().unwrap_or_default();
+    let most_offset = pages.iter().map(|page| page.content_offset).max().unwrap_or_default();
+    let least_content = pages.iter().map(|page| page.content_length).min().unwrap_or_default();
+    let most_content = pages.iter().map(|page| page.content_length).max().unwrap_or_default();
+    let offset_bits = bit_count(most_offset - least_offset);
+    let content_bits = bit_count(most_content - least_content);
+
+    bits.write(least_objects, 32);
+    bits.write(first_page_offset, 32);
+    bits.write(objects_bits as usize, 16);
+    bits.write(least_length, 32);
+    bits.write(length_bits as usize, 16);
+    bits.write(least_offset, 32);
+    bits.write(offset_bits as usize, 16);
+    bits.write(least_content, 32);
+    bits.write(content_bits as usize, 16);
+    bits.write(shared_bits as usize, 16);
+    bits.write(identifier_bits as usize, 16);
+    bits.write(0, 16); // sem frações de objetos compartilhados
+    bits.write(1, 16);
+
+    for page in pages {
+        bits.write(page.objects - least_objects, objects_bits);
+    }
+    bits.align();
+    for page in pages {
+        bits.write(page.length - least_length, length_bits);
+    }
+    bits.align();
+    for page in pages {
+        bits.write(page.shared.len(), shared_bits);
+    }
+    bits.align();
+    for page in pages {
+        for &index in &page.shared {
+            bits.write(index, identifier_bits);
+        }
+    }
+    bits.align();
+    for page in pages {
+        bits.write(page.content_offset - least_offset, offset_bits);
+    }
+    bits.align();
+    for page in pages {
+        bits.write(page.content_length - least_content, content_bits);
+    }
+    bits.align();
+
+    let shared_table = bits.bytes.len();
+    let least_group = group_lengths.iter().copied().min().unwrap_or_default();
+    let most_group = group_lengths.iter().copied().max().unwrap_or_default();
+    let group_bits = bit_count(most_group - least_group);
+    let (first_shared_id, first_shared_offset) = shared_start.unwrap_or_default();
+
+    bits.write(first_shared_id as usize, 32);
+    bits.write(first_shared_offset, 32);
+    bits.write(first_page_groups, 32);
+    bits.write(group_lengths.len(), 32);
+    bits.write(0, 16); // um objeto por grupo
+    bits.write(least_group, 32);
+    bits.write(group_bits as usize, 16);
+    for &length in group_lengths {
+        bits.write(length - least_group, group_bits);
+    }
+    bits.align();
+    for _ in group_lengths {
+        bits.write(0, 1); // sem assinatura MD5
+    }
+    bits.align();
+
+    let mut object = format!("{} 0 obj\n<</Length {}/S {}>>\nstream\n", id, bits.bytes.len(), shared_table).into_bytes();
+    object.extend_from_slice(&bits.bytes);
+    object.extend_from_slice(b"\nendstream\nendobj\n");
+    object
+}
+
+fn bit_count(value: usize) -> u32 {
+    usize::BITS - value.leading_zeros()
+}
+
+/// Escreve inteiros com a quantidade de bits pedida, do bit mais alto para o
+/// mais baixo, como nas tabelas de dicas.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    used: u32, // bits já usados no último byte
+}
+
+impl BitWriter {
+    fn write(&mut self, value: usize, bits: u32) {
+        for bit in (0..bits).rev() {
+            if self.used == 0 {
+                self.bytes.push(0);
+            }
+            if (value >> bit) & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> self.used;
+            }
+            self.used = (self.used + 1) % 8;
+        }
+    }
+
+    fn align(&mut self) {
+        self.used = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::image_crate::{Rgb, RgbImage};
+
+    use crate::catalog::build_catalog;
+    use crate::catalog::options::{CatalogOptions, OutputProfile, ScreenOptions};
+    use crate::catalog::render::render_catalog;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{item_repository, section_repository};
+    use crate::settings::settings_repository::Settings;
+
+    /// Catálogo de tela com `items` itens, cada um com uma foto diferente.
+    fn screen_catalog(items: usize) -> Vec<u8> {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        for number in 0..items {
+            let path = std::env::temp_dir().join(format!("linearize-{}.png", number));
+            RgbImage::from_fn(64, 64, |x, y| Rgb([(x * 4) as u8, (y * 4) as u8, (number * 20) as u8])).save(&path).unwrap();
+            let code = format!("AN-{:02}", number);
+            item_repository::insert_item(&conn, code, "Anel".into(), section_id.clone(), path.display().to_string()).unwrap();
+        }
+        let options = CatalogOptions { profile: OutputProfile::Screen(ScreenOptions::default()), ..Default::default() };
+        render_catalog(&build_catalog(&conn, &options).unwrap(), &Settings::default(), &options).unwrap()
+    }
+
+    fn number_after(bytes: &[u8], key: &str) -> usize {
+        let text = String::from_utf8_lossy(&bytes[..400]);
+        let start = text.find(key).unwrap() + key.len();
+        text[start..].trim_start_matches([' ', '[']).split(|c: char| !c.is_ascii_digit()).next().unwrap().parse().unwrap()
+    }
+
+    fn starts_object(bytes: &[u8], offset: usize) -> bool {
+        let text = String::from_utf8_lossy(&bytes[offset..(offset + 20).min(bytes.len())]).to_string();
+        text.split_whitespace().take(3).collect::<Vec<_>>()[1..] == ["0", "obj"]
+    }
+
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        bit: usize,
+    }
+
+    impl BitReader<'_> {
+        fn read(&mut self, bits: usize) -> usize {
+            let mut value = 0;
+            for _ in 0..bits {
+                let byte = self.bytes[self.bit / 8];
+                value = (value << 1) | ((byte >> (7 - self.bit % 8)) & 1) as usize;
+                self.bit += 1;
+            }
+            value
+        }
+
+        fn align(&mut self) {
+            self.bit = self.bit.div_ceil(8) * 8;
+        }
+
+        // Um valor por página, como nas tabelas de dicas de páginas.
+        fn read_pages(&mut self, pages: usize, bits: usize) -> Vec<usize> {
+            let values = (0..pages).map(|_| self.read(bits)).collect();
+            self.align();
+            values
+        }
+    }
+
+    #[test]
+    fn linearized_catalog_loads_back_with_every_page_and_image() {
+        let bytes = screen_catalog(12);
+        let doc = Document::load_mem(&bytes).unwrap();
+        let pages = doc.get_pages();
+        assert!(pages.len() >= 3);
+
+        let images = doc
+            .objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image".as_slice()))
+            .count();
+        assert_eq!(images, 12);
+        for page in pages.values() {
+            assert!(!doc.get_page_content(*page).unwrap().is_empty());
+        }
+        // Todo endereço das tabelas aponta para o começo de um objeto.
+        for entry in doc.reference_table.entries.values() {
+            if let XrefEntry::Normal { offset, .. } = entry {
+                assert!(starts_object(&bytes, *offset as usize));
+            }
+        }
+    }
+
+    #[test]
+    fn linearization_dictionary_matches_the_file() {
+        let bytes = screen_catalog(12);
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert!(String::from_utf8_lossy(&bytes[..100]).contains("/Linearized 1"));
+
+        assert_eq!(number_after(&bytes, "/L "), bytes.len());
+        assert_eq!(number_after(&bytes, "/N "), doc.get_pages().len());
+        assert_eq!(number_after(&bytes, "/O ") as u32, doc.get_pages()[&1].0);
+        let first_page_end = number_after(&bytes, "/E ");
+        assert!(starts_object(&bytes, first_page_end));
+        assert!(bytes[number_after(&bytes, "/T ")..].starts_with(b"0000000000 65535 f"));
+
+        // Tabela de dicas: o primeiro objeto da primeira página mais o tamanho
+        // dela (sem contar a própria tabela) termina em /E.
+        let hint_offset = number_after(&bytes, "/H ");
+        assert!(starts_object(&bytes, hint_offset));
+        let hint_length = String::from_utf8_lossy(&bytes[..400]).split("/H [").nth(1).unwrap()
+            .split([' ', ']']).nth(1).unwrap().parse::<usize>().unwrap();
+        let stream = &bytes[hint_offset..hint_offset + hint_length];
+        let data = stream.windows(7).position(|window| window == b"stream\n").unwrap() + 7;
+        let mut hints = BitReader { bytes: &stream[data..], bit: 0 };
+        let _least_objects = hints.read(32);
+        let first_page_offset = hints.read(32);
+        let objects_bits = hints.read(16);
+        let least_length = hints.read(32);
+        let length_bits = hints.read(16);
+        let (_least_offset, offset_bits) = (hints.read(32), hints.read(16));
+        let (least_content, content_bits) = (hints.read(32), hints.read(16));
+        let (shared_bits, identifier_bits) = (hints.read(16), hints.read(16));
+        hints.read(16 * 2);
+        let page_count = doc.get_pages().len();
+        hints.read_pages(page_count, objects_bits);
+        let first_length = least_length + hints.read_pages(page_count, length_bits)[0];
+        let shared: usize = hints.read_pages(page_count, shared_bits).iter().sum();
+        hints.read(shared * identifier_bits);
+        hints.align();
+        hints.read_pages(page_count, offset_bits);
+        let first_content = least_content + hints.read_pages(page_count, content_bits)[0];
+        assert_eq!(first_page_offset + hint_length + first_length, first_page_end);
+
+        // O conteúdo da primeira página vai do objeto do fluxo até o próximo objeto.
+        let mut offsets: Vec<usize> = doc
+            .reference_table
+            .entries
+            .values()
+            .filter_map(|entry| match entry {
+                XrefEntry::Normal { offset, .. } => Some(*offset as usize),
+                _ => None,
+            })
+            .collect();
+        offsets.sort_unstable();
+        let first_page = doc.get_pages()[&1];
+        let contents = doc.get_dictionary(first_page).unwrap().get(b"Contents").unwrap().as_reference().unwrap();
+        let XrefEntry::Normal { offset, .. } = doc.reference_table.entries[&contents.0] else {
+            panic!("conteúdo sem endereço");
+        };
+        let next = offsets.iter().copied().find(|next| *next > offset as usize).unwrap();
+        assert_eq!(first_content, next - offset as usize);
+    }
+
+    #[test]
+    fn single_page_documents_are_linearized_too() {
+        let bytes = screen_catalog(0);
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert_eq!(number_after(&bytes, "/N "), doc.get_pages().len());
+        assert_eq!(number_after(&bytes, "/L "), bytes.len());
+    }
+}
@@ -0,0 +1,416 @@
+use printpdf::lopdf::xref::XrefType;
+use printpdf::lopdf::{Dictionary, Document, Object, Stream, StringFormat};
+use printpdf::{CustomPdfConformance, OffsetDateTime, PdfConformance};
+
+use crate::catalog::navigation::text_string;
+
+const PRODUCER: &str = "BrilliantPDF";
+// Comentário com bytes altos logo depois do cabeçalho: avisa que o arquivo é binário.
+pub const BINARY_COMMENT: &[u8] = b"%\xe2\xe3\xcf\xd3\n";
+// Cada entrada da tabela xref: "0000000123 00000 n \n".
+const XREF_ENTRY: usize = 20;
+
+/// Metadados gravados no dicionário Info do PDF (e no XMP, no PDF/A).
+pub struct DocumentMetadata {
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+    pub keywords: Vec<String>,
+    pub created: OffsetDateTime,
+}
+
+/// Conformidade dos documentos do printpdf. A padrão grava um XMP de PDF/X-3
+/// e o perfil FOGRA39 como OutputIntent, que não valem para o que geramos.
+pub fn conformance() -> PdfConformance {
+    PdfConformance::Custom(CustomPdfConformance {
+        requires_xmp_metadata: false,
+        requires_icc_profile: false,
+        ..Default::default()
+    })
+}
+
+/// O printpdf grava o Info sem codificação (acentos viram lixo); aqui ele é
+/// reescrito em UTF-16. XMP e OutputIntents deixados pelo printpdf saem; com
+/// `pdf_a`, o documento ganha os dele, e o mais que o PDF/A-2b exige além
+/// das fontes embutidas.
+pub fn apply(doc: &mut Document, metadata: &DocumentMetadata, pdf_a: bool) -> Result<(), String> {
+    let keywords = metadata.keywords.join(", ");
+    let date = pdf_date(&metadata.created);
+
+    let mut info = Dictionary::new();
+    info.set("Title", text_string(&metadata.title));
+    info.set("Author", text_string(&metadata.author));
+    info.set("Subject", text_string(&metadata.subject));
+    info.set("Keywords", text_string(&keywords));
+    info.set("Creator", text_string(PRODUCER));
+    info.set("Producer", text_string(PRODUCER));
+    info.set("CreationDate", Object::String(date.clone().into_bytes(), StringFormat::Literal));
+    info.set("ModDate", Object::String(date.into_bytes(), StringFormat::Literal));
+    if let Ok(Object::Reference(old)) = doc.trailer.get(b"Info") {
+        let old = *old;
+        doc.objects.remove(&old);
+    }
+    let info = doc.add_object(Object::Dictionary(info));
+    doc.trailer.set("Info", Object::Reference(info));
+
+    strip_intents(doc)?;
+    if pdf_a {
+        archive(doc, metadata, &keywords)?;
+    }
+    Ok(())
+}
+
+/// Grava o documento. O PDF/A pede o comentário binário logo depois do
+/// cabeçalho, que o lopdf não escreve; ele é inserido depois, e os
+/// deslocamentos da tabela xref são corrigidos.
+pub fn save(doc: &mut Document, pdf_a: bool) -> Result<Vec<u8>, String> {
+    doc.reference_table.cross_reference_type = XrefType::CrossReferenceTable;
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).map_err(|e| e.to_string())?;
+    if pdf_a {
+        insert_binary_comment(&mut bytes)?;
+    }
+    Ok(bytes)
+}
+
+fn insert_binary_comment(bytes: &mut Vec<u8>) -> Result<(), String> {
+    let invalid = || "xref".to_string();
+    let shift = BINARY_COMMENT.len();
+    let header = bytes.iter().position(|byte| *byte == b'\n').ok_or_else(invalid)? + 1;
+
+    let marker = b"startxref\n";
+    let start = bytes.windows(marker.len()).rposition(|window| window == marker).ok_or_else(invalid)? + marker.len();
+    let end = start + bytes[start..].iter().position(|byte| *byte == b'\n').ok_or_else(invalid)?;
+    let xref: usize = std::str::from_utf8(&bytes[start..end]).ok().and_then(|n| n.parse().ok()).ok_or_else(invalid)?;
+    bytes.splice(start..end, (xref + shift).to_string().into_bytes());
+
+    // Seções "<primeiro id> <quantidade>" até o "trailer".
+    let mut position = xref + b"xref\n".len();
+    while !bytes[position..].starts_with(b"trailer") {
+        let line_end = position + bytes[position..].iter().position(|byte| *byte == b'\n').ok_or_else(invalid)?;
+        let count: usize = std::str::from_utf8(&bytes[position..line_end])
+            .ok()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|count| count.parse().ok())
+            .ok_or_else(invalid)?;
+        position = line_end + 1;
+        for _ in 0..count {
+            let entry = &mut bytes[position..position + XREF_ENTRY];
+            if entry[17] == b'n' {
+                let offset: usize = std::str::from_utf8(&entry[..10]).ok().and_then(|n| n.parse().ok()).ok_or_else(invalid)?;
+                entry[..10].copy_from_slice(format!("{:010}", offset + shift).as_bytes());
+            }
+            position += XREF_ENTRY;
+        }
+    }
+
+    bytes.splice(header..header, BINARY_COMMENT.iter().copied());
+    Ok(())
+}
+
+// Remove o XMP e os OutputIntents do catálogo, com os objetos ligados a eles.
+fn strip_intents(doc: &mut Document) -> Result<(), String> {
+    let catalog = doc.catalog_mut().map_err(|e| e.to_string())?;
+    let mut unused = Vec::new();
+    if let Some(Object::Reference(xmp)) = catalog.remove(b"Metadata") {
+        unused.push(xmp);
+    }
+    if let Some(Object::Array(intents)) = catalog.remove(b"OutputIntents") {
+        for intent in intents {
+            if let Ok(Object::Reference(profile)) = intent.as_dict().and_then(|intent| intent.get(b"DestinationOutputProfile")) {
+                unused.push(*profile);
+            }
+        }
+    }
+    for id in unused {
+        doc.objects.remove(&id);
+    }
+    Ok(())
+}
+
+fn archive(doc: &mut Document, metadata: &DocumentMetadata, keywords: &str) -> Result<(), String> {
+    doc.version = "1.7".to_string();
+
+    let mut xmp = Stream::new(Dictionary::new(), xmp_packet(metadata, keywords).into_bytes());
+    xmp.dict.set("Type", Object::Name(b"Metadata".to_vec()));
+    xmp.dict.set("Subtype", Object::Name(b"XML".to_vec()));
+    xmp.allows_compression = false; // o XMP precisa ficar legível sem descompactar
+    let xmp = doc.add_object(xmp);
+
+    let mut profile = Stream::new(Dictionary::new(), srgb_profile());
+    profile.dict.set("N", Object::Integer(3));
+    let profile = doc.add_object(profile);
+
+    let mut intent = Dictionary::new();
+    intent.set("Type", Object::Name(b"OutputIntent".to_vec()));
+    intent.set("S", Object::Name(b"GTS_PDFA1".to_vec()));
+    intent.set("OutputConditionIdentifier", Object::string_literal("sRGB IEC61966-2.1"));
+    intent.set("RegistryName", Object::string_literal("http://www.color.org"));
+    intent.set("Info", Object::string_literal("sRGB IEC61966-2.1"));
+    intent.set("DestinationOutputProfile", Object::Reference(profile));
+
+    let catalog = doc.catalog_mut().map_err(|e| e.to_string())?;
+    catalog.set("Metadata", Object::Reference(xmp));
+    catalog.set("OutputIntents", Object::Array(vec![Object::Dictionary(intent)]));
+    // Toda configuração de camadas precisa de nome no PDF/A-2.
+    if let Ok(Object::Dictionary(properties)) = catalog.get_mut(b"OCProperties") {
+        if let Ok(Object::Dictionary(config)) = properties.get_mut(b"D") {
+            config.set("Name", Object::string_literal("Layers"));
+        }
+    }
+    Ok(())
+}
+
+// D:20240131143000+00'00'
+fn pdf_date(date: &OffsetDateTime) -> String {
+    format!(
+        "D:{:04}{:02}{:02}{:02}{:02}{:02}+00'00'",
+        date.year(),
+        u8::from(date.month()),
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second()
+    )
+}
+
+// 2024-01-31T14:30:00+00:00, o mesmo instante do Info.
+fn xmp_date(date: &OffsetDateTime) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+        date.year(),
+        u8::from(date.month()),
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second()
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// XMP com os mesmos valores do Info, como o PDF/A exige.
+fn xmp_packet(metadata: &DocumentMetadata, keywords: &str) -> String {
+    let date = xmp_date(&metadata.created);
+    format!(
+        r#"<?xpacket begin="{bom}" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+<rdf:Description rdf:about=""
+ xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/"
+ xmlns:dc="http://purl.org/dc/elements/1.1/"
+ xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+ xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+<pdfaid:part>2</pdfaid:part>
+<pdfaid:conformance>B</pdfaid:conformance>
+<dc:format>application/pdf</dc:format>
+<dc:title><rdf:Alt><rdf:li xml:lang="x-default">{title}</rdf:li></rdf:Alt></dc:title>
+<dc:creator><rdf:Seq><rdf:li>{author}</rdf:li></rdf:Seq></dc:creator>
+<dc:description><rdf:Alt><rdf:li xml:lang="x-default">{subject}</rdf:li></rdf:Alt></dc:description>
+<pdf:Keywords>{keywords}</pdf:Keywords>
+<pdf:Producer>{producer}</pdf:Producer>
+<xmp:CreatorTool>{producer}</xmp:CreatorTool>
+<xmp:CreateDate>{date}</xmp:CreateDate>
+<xmp:ModifyDate>{date}</xmp:ModifyDate>
+<xmp:MetadataDate>{date}</xmp:MetadataDate>
+</rdf:Description>
+</rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#,
+        bom = '\u{feff}',
+        title = escape_xml(&metadata.title),
+        author = escape_xml(&metadata.author),
+        subject = escape_xml(&metadata.subject),
+        keywords = escape_xml(keywords),
+        producer = PRODUCER,
+        date = date,
+    )
+}
+
+/// Perfil ICC v2 do sRGB (monitor RGB, PCS XYZ): primárias adaptadas ao D50
+/// e a curva de transferência do sRGB em 1024 pontos.
+fn srgb_profile() -> Vec<u8> {
+    fn s15_fixed16(value: f64) -> [u8; 4] {
+        ((value * 65536.0).round() as i32).to_be_bytes()
+    }
+    fn xyz(x: f64, y: f64, z: f64) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for value in [x, y, z] {
+            tag.extend_from_slice(&s15_fixed16(value));
+        }
+        tag
+    }
+
+    let mut description = b"desc\0\0\0\0".to_vec();
+    let name = b"sRGB IEC61966-2.1\0";
+    description.extend_from_slice(&(name.len() as u32).to_be_bytes());
+    description.extend_from_slice(name);
+    description.extend_from_slice(&[0; 8]); // sem texto Unicode
+    description.extend_from_slice(&[0; 3 + 67]); // sem texto ScriptCode
+
+    let mut copyright = b"text\0\0\0\0".to_vec();
+    copyright.extend_from_slice(b"No copyright, use freely\0");
+
+    let mut curve = b"curv\0\0\0\0".to_vec();
+    let points = 1024;
+    curve.extend_from_slice(&(points as u32).to_be_bytes());
+    for index in 0..points {
+        let value = index as f64 / (points - 1) as f64;
+        let linear = if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) };
+        curve.extend_from_slice(&((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+
+    let tags: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"desc", description),
+        (b"cprt", copyright),
+        (b"wtpt", xyz(0.9505, 1.0, 1.0891)),
+        (b"rXYZ", xyz(0.4361, 0.2225, 0.0139)),
+        (b"gXYZ", xyz(0.3851, 0.7169, 0.0971)),
+        (b"bXYZ", xyz(0.1431, 0.0606, 0.7141)),
+        (b"rTRC", curve),
+    ];
+    // gTRC e bTRC apontam para a mesma curva do rTRC.
+    let entry_count = tags.len() + 2;
+
+    let mut table = Vec::new();
+    let mut data = Vec::new();
+    let mut offset = 128 + 4 + entry_count * 12;
+    let mut curve_entry = (0, 0);
+    for (signature, tag) in &tags {
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+        table.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+        if *signature == b"rTRC" {
+            curve_entry = (offset, tag.len());
+        }
+        data.extend_from_slice(tag);
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+        offset = 128 + 4 + entry_count * 12 + data.len();
+    }
+    for signature in [b"gTRC", b"bTRC"] {
+        table.extend_from_slice(signature);
+        table.extend_from_slice(&(curve_entry.0 as u32).to_be_bytes());
+        table.extend_from_slice(&(curve_entry.1 as u32).to_be_bytes());
+    }
+
+    let size = 128 + 4 + table.len() + data.len();
+    let mut profile = Vec::with_capacity(size);
+    profile.extend_from_slice(&(size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]); // CMM
+    profile.extend_from_slice(&[2, 0x10, 0, 0]); // versão 2.1
+    profile.extend_from_slice(b"mntrRGB XYZ ");
+    for part in [2024u16, 1, 1, 0, 0, 0] {
+        profile.extend_from_slice(&part.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 24]); // plataforma, flags, fabricante, modelo, atributos
+    profile.extend_from_slice(&[0; 4]); // intenção: perceptual
+    profile.extend_from_slice(&s15_fixed16(0.9642));
+    profile.extend_from_slice(&s15_fixed16(1.0));
+    profile.extend_from_slice(&s15_fixed16(0.8249));
+    profile.resize(128, 0);
+    profile.extend_from_slice(&(entry_count as u32).to_be_bytes());
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::catalog::build_catalog;
+    use crate::catalog::options::{CatalogOptions, OutputProfile, PrintOptions};
+    use crate::catalog::render::render_catalog;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{item_repository, section_repository};
+    use crate::settings::settings_repository::Settings;
+
+    fn u32_at(bytes: &[u8], offset: usize) -> usize {
+        u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+    }
+
+    fn render(options: &CatalogOptions) -> Result<Vec<u8>, String> {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap();
+        render_catalog(&build_catalog(&conn, options).unwrap(), &Settings::default(), options)
+    }
+
+    fn contains(bytes: &[u8], text: &[u8]) -> bool {
+        bytes.windows(text.len()).any(|window| window == text)
+    }
+
+    #[test]
+    fn srgb_profile_is_a_well_formed_icc_v2_profile() {
+        let profile = srgb_profile();
+        assert_eq!(u32_at(&profile, 0), profile.len());
+        assert_eq!(&profile[8..10], &[2, 0x10]);
+        assert_eq!(&profile[12..24], b"mntrRGB XYZ ");
+        assert_eq!(&profile[36..40], b"acsp");
+
+        let count = u32_at(&profile, 128);
+        assert_eq!(count, 9);
+        let mut tags = HashMap::new();
+        for entry in 0..count {
+            let at = 132 + entry * 12;
+            let (offset, size) = (u32_at(&profile, at + 4), u32_at(&profile, at + 8));
+            assert!(offset.is_multiple_of(4) && offset + size <= profile.len());
+            tags.insert(&profile[at..at + 4], (offset, size));
+        }
+        assert_eq!(tags[b"rTRC".as_slice()], tags[b"gTRC".as_slice()]);
+        assert_eq!(tags[b"rTRC".as_slice()], tags[b"bTRC".as_slice()]);
+
+        // Curva de 1024 pontos do preto (0) ao branco (65535).
+        let (curve, size) = tags[b"rTRC".as_slice()];
+        assert_eq!(&profile[curve..curve + 4], b"curv");
+        assert_eq!(u32_at(&profile, curve + 8), 1024);
+        assert_eq!(size, 12 + 1024 * 2);
+        assert_eq!(&profile[curve + 12..curve + 14], &[0, 0]);
+        assert_eq!(&profile[curve + size - 2..curve + size], &[0xff, 0xff]);
+
+        let (white, _) = tags[b"wtpt".as_slice()];
+        assert_eq!(&profile[white..white + 4], b"XYZ ");
+        assert_eq!(u32_at(&profile, white + 12), 65536); // Y = 1,0
+    }
+
+    #[test]
+    fn pdf_a_has_binary_header_and_only_its_own_intents() {
+        let bytes = render(&CatalogOptions { pdf_a: true, ..Default::default() }).unwrap();
+        assert!(bytes.starts_with(&[b"%PDF-1.7\n".as_slice(), BINARY_COMMENT].concat()));
+        assert!(!contains(&bytes, b"GTS_PDFX") && !contains(&bytes, b"FOGRA39"));
+
+        let doc = Document::load_mem(&bytes).unwrap();
+        let catalog = doc.catalog().unwrap();
+        let intents = catalog.get(b"OutputIntents").and_then(Object::as_array).unwrap();
+        assert_eq!(intents.len(), 1);
+        let intent = intents[0].as_dict().unwrap();
+        assert_eq!(intent.get(b"S").and_then(Object::as_name).unwrap(), b"GTS_PDFA1");
+        let xmp = doc.get_object(catalog.get(b"Metadata").and_then(Object::as_reference).unwrap()).and_then(Object::as_stream).unwrap();
+        assert!(contains(&xmp.content, b"<pdfaid:part>2</pdfaid:part>"));
+    }
+
+    #[test]
+    fn regular_pdfs_have_no_xmp_or_output_intents() {
+        for profile in [OutputProfile::Standard, OutputProfile::Print(PrintOptions { cmyk: false, ..Default::default() })] {
+            let bytes = render(&CatalogOptions { profile, ..Default::default() }).unwrap();
+            assert!(!contains(&bytes, b"GTS_PDFX") && !contains(&bytes, b"FOGRA39"));
+            let doc = Document::load_mem(&bytes).unwrap();
+            let catalog = doc.catalog().unwrap();
+            assert!(catalog.get(b"Metadata").is_err() && catalog.get(b"OutputIntents").is_err());
+        }
+    }
+
+    #[test]
+    fn pdf_a_rejects_cmyk_print_output() {
+        let options = CatalogOptions { pdf_a: true, profile: OutputProfile::Print(PrintOptions::default()), ..Default::default() };
+        assert!(render(&options).is_err());
+        let rgb = OutputProfile::Print(PrintOptions { cmyk: false, ..Default::default() });
+        assert!(render(&CatalogOptions { pdf_a: true, profile: rgb, ..Default::default() }).is_ok());
+    }
+}
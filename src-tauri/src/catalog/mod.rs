@@ -0,0 +1,106 @@
+pub mod barcode;
+pub mod builder;
+pub mod fonts;
+pub mod format;
+pub mod index;
+pub mod job;
+pub mod labels;
+pub mod linearize;
+pub mod metadata;
+pub mod navigation;
+pub mod options;
+pub mod palette;
+pub mod preview;
+pub mod print;
+pub mod qr;
+pub mod render;
+pub mod screen;
+pub mod sheet;
+
+use std::path::PathBuf;
+use rusqlite::Connection;
+use crate::settings::settings_repository::Settings;
+
+pub use builder::{build_catalog, Catalog, CatalogItem, CatalogSection};
+pub use options::CatalogOptions;
+
+const DEFAULT_FILE_NAME: &str = "catalogo.pdf";
+pub const LABELS_FILE_NAME: &str = "etiquetas.pdf";
+
+/// Caminho final do PDF: `Settings.save_path` ou, se vazio, a pasta de documentos.
+pub fn output_path(settings: &Settings, file_name: Option<&str>) -> PathBuf {
+    let mut path = if settings.save_path.trim().is_empty() {
+        dirs::document_dir().unwrap_or_else(|| PathBuf::from("."))
+    } else {
+        PathBuf::from(&settings.save_path)
+    };
+
+    let file_name = file_name.filter(|name| !name.trim().is_empty()).unwrap_or(DEFAULT_FILE_NAME);
+    path.push(file_name);
+    if path.extension().is_none() {
+        path.set_extension("pdf");
+    }
+    path
+}
+
+/// Data local no formato "YYYY-MM-DD", para o nome do arquivo.
+pub fn today(conn: &Connection) -> rusqlite::Result<String> {
+    conn.query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))
+}
+
+/// Troca {section}, {preset}, {date} e {language} no nome do arquivo. Barras e
+/// caracteres que o Windows não aceita viram hífen, e pontos nas pontas caem,
+/// para o nome não apontar para outra pasta. Se não sobrar nome (ex: seção
+/// vazia em "{section}.pdf"), usa `default`.
+pub fn expand_file_name(pattern: &str, section: &str, preset: &str, date: &str, language: &str, default: &str) -> String {
+    let expanded = pattern
+        .replace("{section}", section.trim())
+        .replace("{preset}", preset)
+        .replace("{date}", date)
+        .replace("{language}", language);
+    let name: String = expanded
+        .chars()
+        .map(|c| if c.is_control() || "<>:\"|?*/\\".contains(c) { '-' } else { c })
+        .collect();
+
+    let is_edge = |c: char| c == '.' || c.is_whitespace();
+    let name = name.trim();
+    let stem = match name.len().checked_sub(4) {
+        Some(split) if name.is_char_boundary(split) && name[split..].eq_ignore_ascii_case(".pdf") => &name[..split],
+        _ => name,
+    };
+    if stem.trim_matches(is_edge).is_empty() {
+        return default.to_string();
+    }
+    name.trim_matches(is_edge).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(pattern: &str, section: &str) -> String {
+        expand_file_name(pattern, section, "Atacado", "2025-03-01", "pt", DEFAULT_FILE_NAME)
+    }
+
+    #[test]
+    fn placeholders_are_replaced() {
+        assert_eq!(expand("{preset}-{date}-{language}.pdf", ""), "Atacado-2025-03-01-pt.pdf");
+        assert_eq!(expand("{section}_{date}.pdf", " Anéis "), "Anéis_2025-03-01.pdf");
+    }
+
+    #[test]
+    fn names_cannot_leave_the_output_folder() {
+        assert_eq!(expand("{section}.pdf", "../../etc/passwd"), "-..-etc-passwd.pdf");
+        assert_eq!(expand("{section}", ".."), DEFAULT_FILE_NAME);
+        assert_eq!(expand("..\\{section}.pdf", "Anéis"), "-Anéis.pdf");
+        assert_eq!(expand("{section}.pdf", "Ouro: 18k?"), "Ouro- 18k-.pdf");
+    }
+
+    #[test]
+    fn empty_expansions_use_the_default_name() {
+        assert_eq!(expand("{section}.pdf", ""), DEFAULT_FILE_NAME);
+        assert_eq!(expand("{section}", "   "), DEFAULT_FILE_NAME);
+        assert_eq!(expand(" . ", "Anéis"), DEFAULT_FILE_NAME);
+    }
+}
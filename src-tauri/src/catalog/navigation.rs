@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, StringFormat};
+
+use crate::i18n;
+
+const MM_TO_PT: f32 = 72.0 / 25.4;
+
+/// Entrada do marcador (outline) do PDF. `item` marca entradas de itens,
+/// que não aparecem no sumário impresso.
+#[derive(Clone, Debug)]
+pub struct OutlineEntry {
+    pub depth: usize,
+    pub title: String,
+    pub page: usize, // 1 = primeira página
+    pub item: bool,
+}
+
+/// Área clicável (em mm) de uma página que leva a outra página ou a um endereço.
+#[derive(Clone, Debug)]
+pub struct PageLink {
+    pub page: usize,
+    pub rect: (f32, f32, f32, f32), // x1, y1, x2, y2
+    pub target: LinkTarget,
+}
+
+#[derive(Clone, Debug)]
+pub enum LinkTarget {
+    Page(usize),
+    Uri(String), // site ou WhatsApp, nos QR codes
+}
+
+/// O printpdf só gera marcadores planos (um por página) e links externos sem
+/// a flag de impressão; aqui o PDF salvo, já reaberto, ganha os links e
+/// marcadores aninhados.
+pub fn apply(doc: &mut Document, outline: &[OutlineEntry], links: &[PageLink]) -> Result<(), String> {
+    let pages = doc.get_pages();
+
+    for link in links {
+        add_link(doc, &pages, link)?;
+    }
+    if !outline.is_empty() {
+        add_outline(doc, &pages, outline)?;
+    }
+    Ok(())
+}
+
+fn page_id(pages: &BTreeMap<u32, ObjectId>, page: usize) -> Result<ObjectId, String> {
+    pages
+        .get(&(page as u32))
+        .copied()
+        .ok_or_else(|| i18n::message("pdf_page_missing", &[&page]))
+}
+
+fn destination(page: ObjectId) -> Object {
+    Object::Array(vec![Object::Reference(page), Object::Name(b"Fit".to_vec())])
+}
+
+/// Texto em UTF-16BE com BOM, para acentos nos títulos dos marcadores.
+pub fn text_string(text: &str) -> Object {
+    let mut bytes = vec![0xFE, 0xFF];
+    for unit in text.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+fn add_link(doc: &mut Document, pages: &BTreeMap<u32, ObjectId>, link: &PageLink) -> Result<(), String> {
+    let page = page_id(pages, link.page)?;
+    let (x1, y1, x2, y2) = link.rect;
+
+    let mut annotation = Dictionary::new();
+    annotation.set("Type", Object::Name(b"Annot".to_vec()));
+    annotation.set("Subtype", Object::Name(b"Link".to_vec()));
+    annotation.set(
+        "Rect",
+        Object::Array(vec![
+            (x1 * MM_TO_PT).into(),
+            (y1 * MM_TO_PT).into(),
+            (x2 * MM_TO_PT).into(),
+            (y2 * MM_TO_PT).into(),
+        ]),
+    );
+    annotation.set("Border", Object::Array(vec![0.into(), 0.into(), 0.into()]));
+    annotation.set("F", Object::Integer(4)); // Print, exigido pelo PDF/A
+    match &link.target {
+        LinkTarget::Page(target) => annotation.set("Dest", destination(page_id(pages, *target)?)),
+        LinkTarget::Uri(uri) => {
+            let mut action = Dictionary::new();
+            action.set("S", Object::Name(b"URI".to_vec()));
+            action.set("URI", Object::string_literal(uri.as_str()));
+            annotation.set("A", Object::Dictionary(action));
+        }
+    }
+    let annotation = doc.add_object(Object::Dictionary(annotation));
+
+    // /Annots pode ser um array direto ou uma referência para um array.
+    let annots_ref = match doc.get_object(page).and_then(Object::as_dict).map_err(|e| e.to_string())?.get(b"Annots") {
+        Ok(Object::Reference(id)) => Some(*id),
+        _ => None,
+    };
+    if let Some(id) = annots_ref {
+        if let Ok(Object::Array(annots)) = doc.get_object_mut(id) {
+            annots.push(Object::Reference(annotation));
+            return Ok(());
+        }
+    }
+
+    let page = doc.get_object_mut(page).and_then(Object::as_dict_mut).map_err(|e| e.to_string())?;
+    match page.get_mut(b"Annots") {
+        Ok(Object::Array(annots)) => annots.push(Object::Reference(annotation)),
+        _ => page.set("Annots", Object::Array(vec![Object::Reference(annotation)])),
+    }
+    Ok(())
+}
+
+struct Node {
+    entry: usize,
+    children: Vec<usize>,
+}
+
+fn add_outline(doc: &mut Document, pages: &BTreeMap<u32, ObjectId>, outline: &[OutlineEntry]) -> Result<(), String> {
+    // Monta a árvore a partir da profundidade de cada entrada.
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut roots: Vec<usize> = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+    for (index, entry) in outline.iter().enumerate() {
+        while stack.last().is_some_and(|&top| outline[nodes[top].entry].depth >= entry.depth) {
+            stack.pop();
+        }
+        let node = nodes.len();
+        nodes.push(Node { entry: index, children: Vec::new() });
+        match stack.last() {
+            Some(&parent) => nodes[parent].children.push(node),
+            None => roots.push(node),
+        }
+        stack.push(node);
+    }
+
+    let root_id = doc.new_object_id();
+    let ids: Vec<ObjectId> = nodes.iter().map(|_| doc.new_object_id()).collect();
+
+    write_siblings(doc, pages, outline, &nodes, &ids, &roots, root_id)?;
+
+    let mut root = Dictionary::new();
+    root.set("Type", Object::Name(b"Outlines".to_vec()));
+    root.set("First", Object::Reference(ids[roots[0]]));
+    root.set("Last", Object::Reference(ids[*roots.last().unwrap()]));
+    root.set("Count", Object::Integer(roots.len() as i64));
+    doc.objects.insert(root_id, Object::Dictionary(root));
+
+    let catalog = doc.catalog_mut().map_err(|e| e.to_string())?;
+    catalog.set("Outlines", Object::Reference(root_id));
+    catalog.set("PageMode", Object::Name(b"UseOutlines".to_vec()));
+    Ok(())
+}
+
+// Marcadores com filhos começam fechados (Count negativo) para a lista não ficar enorme.
+fn write_siblings(
+    doc: &mut Document,
+    pages: &BTreeMap<u32, ObjectId>,
+    outline: &[OutlineEntry],
+    nodes: &[Node],
+    ids: &[ObjectId],
+    siblings: &[usize],
+    parent: ObjectId,
+) -> Result<(), String> {
+    for (position, &node) in siblings.iter().enumerate() {
+        let entry = &outline[nodes[node].entry];
+        let children = &nodes[node].children;
+
+        let mut item = Dictionary::new();
+        item.set("Title", text_string(&entry.title));
+        item.set("Parent", Object::Reference(parent));
+        item.set("Dest", destination(page_id(pages, entry.page)?));
+        if position > 0 {
+            item.set("Prev", Object::Reference(ids[siblings[position - 1]]));
+        }
+        if let Some(&next) = siblings.get(position + 1) {
+            item.set("Next", Object::Reference(ids[next]));
+        }
+        if let (Some(&first), Some(&last)) = (children.first(), children.last()) {
+            item.set("First", Object::Reference(ids[first]));
+            item.set("Last", Object::Reference(ids[last]));
+            item.set("Count", Object::Integer(-(children.len() as i64)));
+        }
+        doc.objects.insert(ids[node], Object::Dictionary(item));
+
+        write_siblings(doc, pages, outline, nodes, ids, children, ids[node])?;
+    }
+    Ok(())
+}
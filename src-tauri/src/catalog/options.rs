@@ -0,0 +1,478 @@
+use serde::{Serialize, Deserialize};
+
+use crate::i18n;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatalogOptions {
+    /// Aceita {preset}, {date} e {language}, ex: "{preset}-{date}.pdf".
+    pub file_name: Option<String>,
+    pub language: Option<String>, // "pt", "en" ou "es"; sem valor, o idioma do app
+    /// Paleta do catálogo; sem valor, usa a das configurações.
+    pub palette: Option<String>,
+    /// Famílias de fonte; sem valor, usam as das configurações.
+    pub heading_font: Option<String>,
+    pub body_font: Option<String>,
+    pub price_mode: PriceMode,
+    /// Itens criados nos últimos N dias recebem o selo de novidade.
+    pub new_arrival_days: Option<u32>,
+    /// Gera o catálogo só com os itens desta tag, como se fosse uma seção.
+    pub tag_id: Option<String>,
+    /// Sumário com as seções e subseções logo depois da capa.
+    pub table_of_contents: bool,
+    /// Página divisória (capa, nome e descrição) antes de cada seção de primeiro nível.
+    pub section_dividers: bool,
+    /// Layout padrão dos itens ("list" ou "grid"); seções podem sobrescrever.
+    pub layout: Option<String>,
+    /// Além das seções, cria um marcador do PDF para cada item.
+    pub bookmark_items: bool,
+    /// Posição do número da página; `None` não numera.
+    pub page_numbers: Option<Placement>,
+    /// Textos de cabeçalho e rodapé; aceitam {company}, {section}, {page} e {pages}.
+    pub header: Option<String>,
+    pub header_placement: Option<Placement>, // padrão: top_left
+    pub footer: Option<String>,
+    pub footer_placement: Option<Placement>, // padrão: bottom_left
+    /// Índice no fim do catálogo com cada código e a página onde aparece.
+    pub code_index: bool,
+    /// Infos cujos valores também entram no índice, pelo nome cadastrado (ex: ["Metal"]).
+    pub index_infos: Vec<String>,
+    /// Omite as infos sem valor (ex: campos do template ainda não preenchidos).
+    pub hide_empty_infos: bool,
+    /// Gera o catálogo só com parte dos itens.
+    pub selection: Option<CatalogSelection>,
+    /// Metadados do PDF. Sem valor, o título é "<empresa> - Catálogo" e as
+    /// palavras-chave são as seções de primeiro nível.
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Vec<String>,
+    /// Gera PDF/A-2b, para arquivamento: só fontes embutidas, perfil de cor
+    /// sRGB e metadados XMP. Ignorado no perfil de tela.
+    pub pdf_a: bool,
+    /// Tamanho final da página (depois do corte).
+    pub page_size: PageSize,
+    /// Perfil de saída: padrão, para gráfica ou para tela (WhatsApp, e-mail).
+    pub profile: OutputProfile,
+    /// Gera um PDF por seção de primeiro nível em vez de um arquivo só.
+    pub split: Option<SplitOptions>,
+    /// QR code em cada item, levando ao site ou a uma conversa no WhatsApp.
+    pub qr_code: Option<QrTarget>,
+    /// Código de barras do código do item, embaixo do texto. Itens cujo código
+    /// não serve para o formato escolhido saem sem código de barras.
+    pub barcode: Option<BarcodeFormat>,
+}
+
+/// Formato do código de barras. O automático usa EAN-13 em códigos de 13
+/// dígitos com o verificador certo e Code 128 no resto.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarcodeFormat {
+    #[default]
+    Auto,
+    Code128,
+    Ean13,
+}
+
+/// Para onde o QR code do item leva. Sem site (ou telefone) nas
+/// configurações, os itens saem sem QR code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QrTarget {
+    /// Página do produto; aceita {website_url} e {code}. Padrão "{website_url}/p/{code}".
+    Website { url: Option<String> },
+    /// Conversa com `Settings.phone_number` já com a mensagem; aceita {code}.
+    /// Padrão "Tenho interesse no item {code}", no idioma do catálogo.
+    Whatsapp { message: Option<String> },
+}
+
+/// Um PDF para cada seção de primeiro nível, com as subseções dela.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SplitOptions {
+    /// Aceita {section}, {preset}, {date} e {language}; padrão "{section}_{date}.pdf".
+    pub file_name: Option<String>,
+    /// Também gera o catálogo completo, com o `file_name` das opções.
+    pub combined: bool,
+}
+
+/// Subconjunto de itens do catálogo. Seções e itens escolhidos se somam;
+/// os demais campos são filtros aplicados em cima deles. Seções sem nenhum
+/// item selecionado saem do catálogo.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatalogSelection {
+    /// Seções escolhidas, com as subseções; vazio = todas.
+    pub section_ids: Vec<String>,
+    pub item_ids: Vec<String>,
+    /// Só itens com esta tag (sem mudar o agrupamento por seção).
+    pub tag_id: Option<String>,
+    /// Busca no código, na descrição, nas infos e nos códigos das variantes.
+    pub text: Option<String>,
+    pub with_image_only: bool,
+    /// Faixa de preço das variantes; itens sem variante com preço ficam de fora.
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+}
+
+impl CatalogOptions {
+    /// Idioma dos rótulos e dos números: "en-US" ou "EN" viram "en". Sem
+    /// valor, ou num idioma sem suporte, o idioma do app.
+    pub fn language(&self) -> &'static str {
+        self.language.as_deref().and_then(i18n::normalize).unwrap_or_else(i18n::locale)
+    }
+
+    /// Idioma das traduções do conteúdo, com a região (ex: "es-AR"), que
+    /// cai no idioma base quando a região não tem tradução.
+    pub fn content_locale(&self) -> &str {
+        match self.language.as_deref() {
+            Some(language) if i18n::normalize(language).is_some() => language,
+            _ => i18n::locale(),
+        }
+    }
+
+    /// O perfil de tela tira os metadados, então não combina com PDF/A.
+    pub fn is_pdf_a(&self) -> bool {
+        self.pdf_a && self.profile.screen().is_none()
+    }
+}
+
+/// Como os preços das variantes aparecem no catálogo.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceMode {
+    #[default]
+    Show,
+    Hide,      // sem coluna de preço
+    OnRequest, // "Sob consulta" no lugar do valor
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    List, // imagem à esquerda, um item por linha
+    Grid, // dois itens por linha
+}
+
+impl Layout {
+    pub fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("grid") => Layout::Grid,
+            _ => Layout::List,
+        }
+    }
+}
+
+/// Posição de textos repetidos em todas as páginas (número, cabeçalho, rodapé).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Placement {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl Placement {
+    pub fn is_top(self) -> bool {
+        matches!(self, Placement::TopLeft | Placement::TopCenter | Placement::TopRight)
+    }
+}
+
+/// Menor página personalizada do catálogo, em mm.
+pub const MIN_CATALOG_WIDTH: f32 = 120.0;
+pub const MIN_CATALOG_HEIGHT: f32 = 150.0;
+
+/// Tamanho da página em mm; no catálogo, `Custom` respeita `MIN_CATALOG_WIDTH` e `MIN_CATALOG_HEIGHT`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageSize {
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Custom { width: f32, height: f32 },
+}
+
+impl PageSize {
+    /// Largura e altura em mm; tamanhos personalizados menores que 50 mm sobem para 50.
+    pub fn dimensions(self) -> (f32, f32) {
+        match self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::A5 => (148.0, 210.0),
+            PageSize::Letter => (215.9, 279.4),
+            PageSize::Custom { width, height } => (width.max(50.0), height.max(50.0)),
+        }
+    }
+
+    /// Dimensões para o catálogo. Abaixo do mínimo a coluna de texto do layout
+    /// em lista (ao lado da foto e do QR code) some e um item não cabe na página.
+    pub fn catalog_dimensions(self) -> (f32, f32) {
+        let (width, height) = self.dimensions();
+        (width.max(MIN_CATALOG_WIDTH), height.max(MIN_CATALOG_HEIGHT))
+    }
+}
+
+/// Para onde vai o PDF. O padrão serve para tela e impressão no escritório.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutputProfile {
+    #[default]
+    Standard,
+    Print(PrintOptions),
+    Screen(ScreenOptions),
+}
+
+impl OutputProfile {
+    pub fn print(&self) -> Option<&PrintOptions> {
+        match self {
+            OutputProfile::Print(print) => Some(print),
+            _ => None,
+        }
+    }
+
+    pub fn screen(&self) -> Option<&ScreenOptions> {
+        match self {
+            OutputProfile::Screen(screen) => Some(screen),
+            _ => None,
+        }
+    }
+}
+
+/// Arquivo para gráfica: sangria, marcas de corte, cores em CMYK e aviso de
+/// imagens com resolução baixa.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrintOptions {
+    /// Sangria em mm: fundos de página inteira passam da linha de corte.
+    pub bleed: f32,
+    pub crop_marks: bool,
+    /// Converte cores e imagens para CMYK.
+    pub cmyk: bool,
+    /// Imagens de itens abaixo desta resolução (no tamanho impresso) geram aviso.
+    pub min_dpi: u32,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self { bleed: 3.0, crop_marks: true, cmyk: true, min_dpi: 300 }
+    }
+}
+
+/// Arquivo pequeno para mandar por WhatsApp: imagens reduzidas em JPEG, sem
+/// metadados, fluxos compactados e PDF linearizado (abre antes de baixar tudo).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenOptions {
+    /// Resolução das imagens no tamanho em que aparecem na página.
+    pub image_dpi: u32,
+    /// Qualidade do JPEG das imagens, de 1 a 100.
+    pub jpeg_quality: u8,
+    /// Tamanho máximo desejado, em KB. Se o PDF passar disso, as imagens são
+    /// refeitas com a qualidade e a resolução estimadas para caber (no mínimo,
+    /// as menores de `screen::reduce`).
+    pub target_size_kb: Option<u32>,
+}
+
+impl Default for ScreenOptions {
+    fn default() -> Self {
+        Self { image_dpi: 110, jpeg_quality: 75, target_size_kb: None }
+    }
+}
+
+/// Folha de etiquetas para expositores: código, descrição curta, preço e
+/// código de barras ou QR code de cada item, em folhas de etiquetas prontas.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LabelOptions {
+    /// Aceita {date} e {language}; padrão "etiquetas.pdf".
+    pub file_name: Option<String>,
+    pub language: Option<String>,
+    /// Itens das etiquetas, com os mesmos filtros do catálogo.
+    pub selection: CatalogSelection,
+    pub sheet: LabelSheetFormat,
+    /// Etiquetas de cada item.
+    pub copies: u32,
+    /// Etiquetas já usadas no começo da primeira folha, para aproveitar folhas pela metade.
+    pub skip: u32,
+    pub price_mode: PriceMode,
+    pub barcode: Option<BarcodeFormat>,
+    pub qr_code: Option<QrTarget>,
+    /// Contorno fino em cada etiqueta, para conferir o alinhamento em papel comum.
+    pub outline: bool,
+    pub body_font: Option<String>,
+}
+
+impl Default for LabelOptions {
+    fn default() -> Self {
+        Self {
+            file_name: None,
+            language: None,
+            selection: CatalogSelection::default(),
+            sheet: LabelSheetFormat::default(),
+            copies: 1,
+            skip: 0,
+            price_mode: PriceMode::Show,
+            barcode: None,
+            qr_code: None,
+            outline: false,
+            body_font: None,
+        }
+    }
+}
+
+impl LabelOptions {
+    /// Como em `CatalogOptions::language`.
+    pub fn language(&self) -> &'static str {
+        self.language.as_deref().and_then(i18n::normalize).unwrap_or_else(i18n::locale)
+    }
+}
+
+/// Folha das etiquetas: um modelo comum de papelaria, medidas salvas
+/// (`label_presets`) ou medidas avulsas.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LabelSheetFormat {
+    Standard { model: LabelModel },
+    Preset { id: String },
+    Custom(LabelSheet),
+}
+
+impl Default for LabelSheetFormat {
+    fn default() -> Self {
+        LabelSheetFormat::Standard { model: LabelModel::default() }
+    }
+}
+
+/// Folhas de etiquetas vendidas prontas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelModel {
+    /// A4, 3 × 8 etiquetas de 63,5 × 33,9 mm (Avery L7159 e equivalentes).
+    #[default]
+    A4x24,
+    /// A4, 3 × 7 etiquetas de 63,5 × 38,1 mm (Pimaco A4356, Avery L7160).
+    A4x21,
+    /// A4, 5 × 13 etiquetas de 38,1 × 21,2 mm (Pimaco A4251, Avery L7651).
+    A4x65,
+    /// A4, 3 × 11 etiquetas de 63,5 × 25,4 mm (Pimaco A4256, Avery L7157).
+    A4x33,
+    /// A4, 3 × 6 etiquetas de 63,5 × 46,5 mm (Pimaco A4260).
+    A4x18,
+    /// Carta, 3 × 10 etiquetas de 66,7 × 25,4 mm (Pimaco 6180, Avery 5160).
+    Letterx30,
+}
+
+impl LabelModel {
+    pub const ALL: [LabelModel; 6] = [
+        LabelModel::A4x24,
+        LabelModel::A4x21,
+        LabelModel::A4x65,
+        LabelModel::A4x33,
+        LabelModel::A4x18,
+        LabelModel::Letterx30,
+    ];
+
+    pub fn sheet(self) -> LabelSheet {
+        let sheet = |page_size, columns, rows, label_width, label_height, margin_left, margin_top, gap_x| LabelSheet {
+            page_size,
+            columns,
+            rows,
+            label_width,
+            label_height,
+            margin_left,
+            margin_top,
+            gap_x,
+            gap_y: 0.0,
+        };
+        match self {
+            LabelModel::A4x24 => sheet(PageSize::A4, 3, 8, 63.5, 33.9, 7.2, 12.9, 2.5),
+            LabelModel::A4x21 => sheet(PageSize::A4, 3, 7, 63.5, 38.1, 7.2, 15.15, 2.5),
+            LabelModel::A4x65 => sheet(PageSize::A4, 5, 13, 38.1, 21.2, 4.75, 10.7, 2.5),
+            LabelModel::A4x33 => sheet(PageSize::A4, 3, 11, 63.5, 25.4, 7.2, 8.8, 2.5),
+            LabelModel::A4x18 => sheet(PageSize::A4, 3, 6, 63.5, 46.5, 7.2, 9.0, 2.5),
+            LabelModel::Letterx30 => sheet(PageSize::Letter, 3, 10, 66.7, 25.4, 4.8, 12.7, 3.15),
+        }
+    }
+}
+
+/// Medidas de uma folha de etiquetas, em mm. As margens contam da borda da
+/// folha até a primeira etiqueta; os espaços, entre uma etiqueta e a próxima.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LabelSheet {
+    pub page_size: PageSize,
+    pub columns: u32,
+    pub rows: u32,
+    pub label_width: f32,
+    pub label_height: f32,
+    pub margin_left: f32,
+    pub margin_top: f32,
+    #[serde(default)]
+    pub gap_x: f32,
+    #[serde(default)]
+    pub gap_y: f32,
+}
+
+impl LabelSheet {
+    /// Confere se há etiquetas e se todas cabem na folha.
+    pub fn validate(&self) -> Result<(), String> {
+        let sizes = [self.label_width, self.label_height];
+        let offsets = [self.margin_left, self.margin_top, self.gap_x, self.gap_y];
+        if self.columns == 0 || self.rows == 0 || sizes.iter().any(|size| *size <= 0.0) || offsets.iter().any(|offset| *offset < 0.0) {
+            return Err(i18n::text("label_sheet_empty").to_string());
+        }
+
+        let (page_width, page_height) = self.page_size.dimensions();
+        let width = self.margin_left + self.columns as f32 * self.label_width + (self.columns - 1) as f32 * self.gap_x;
+        let height = self.margin_top + self.rows as f32 * self.label_height + (self.rows - 1) as f32 * self.gap_y;
+        // Meio milímetro de folga para medidas arredondadas do fabricante.
+        if width > page_width + 0.5 || height > page_height + 0.5 {
+            let size = format!("{} × {}", page_width, page_height);
+            return Err(i18n::message("label_sheet_too_large", &[&size]));
+        }
+        Ok(())
+    }
+
+    pub fn labels_per_page(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_are_normalized_like_the_app_locale() {
+        let options = |language: &str| CatalogOptions { language: Some(language.into()), ..Default::default() };
+        assert_eq!(options("EN").language(), "en");
+        assert_eq!(options("en-US").language(), "en");
+        assert_eq!(options("es_AR").language(), "es");
+        assert_eq!(options("es_AR").content_locale(), "es_AR");
+        // Sem suporte: rótulos e conteúdo no idioma do app.
+        assert_eq!(options("fr").language(), i18n::locale());
+        assert_eq!(options("fr").content_locale(), i18n::locale());
+        let labels = LabelOptions { language: Some("EN-gb".into()), ..Default::default() };
+        assert_eq!(labels.language(), "en");
+    }
+
+    #[test]
+    fn standard_label_models_fit_their_sheets() {
+        for model in LabelModel::ALL {
+            assert!(model.sheet().validate().is_ok(), "{:?}", model);
+        }
+        assert_eq!(LabelModel::A4x33.sheet().labels_per_page(), 33);
+    }
+
+    #[test]
+    fn label_sheets_need_labels_that_fit_the_page() {
+        let sheet = LabelModel::A4x24.sheet();
+        assert!(LabelSheet { columns: 0, ..sheet.clone() }.validate().is_err());
+        assert!(LabelSheet { label_height: 0.0, ..sheet.clone() }.validate().is_err());
+        assert!(LabelSheet { margin_left: -1.0, ..sheet.clone() }.validate().is_err());
+        assert!(LabelSheet { columns: 4, ..sheet.clone() }.validate().is_err());
+        assert!(LabelSheet { rows: 9, ..sheet.clone() }.validate().is_err());
+        // Arredondamentos de até meio milímetro são aceitos.
+        assert!(LabelSheet { margin_top: sheet.margin_top * 2.0 + 0.4, ..sheet }.validate().is_ok());
+    }
+}
@@ -0,0 +1,48 @@
+use printpdf::{Color, Rgb};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Palette {
+    pub primary: (f32, f32, f32),
+    pub accent: (f32, f32, f32),
+    pub text: (f32, f32, f32),
+    pub muted: (f32, f32, f32),
+}
+
+impl Palette {
+    /// Paleta a partir do nome salvo em `Settings.pallet`; nomes desconhecidos usam a padrão.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "gold" => Self {
+                primary: (0.55, 0.42, 0.16),
+                accent: (0.80, 0.64, 0.27),
+                ..Self::default()
+            },
+            "silver" => Self {
+                primary: (0.35, 0.38, 0.42),
+                accent: (0.62, 0.65, 0.70),
+                ..Self::default()
+            },
+            "rose" => Self {
+                primary: (0.62, 0.33, 0.38),
+                accent: (0.85, 0.55, 0.58),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    pub fn color(rgb: (f32, f32, f32)) -> Color {
+        Color::Rgb(Rgb::new(rgb.0, rgb.1, rgb.2, None))
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            primary: (0.13, 0.13, 0.18),
+            accent: (0.76, 0.18, 0.33),
+            text: (0.10, 0.10, 0.10),
+            muted: (0.45, 0.45, 0.45),
+        }
+    }
+}
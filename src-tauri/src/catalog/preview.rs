@@ -0,0 +1,185 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use pdfium_render::prelude::{PdfRenderConfig, Pdfium, PdfiumLibraryBindings};
+use printpdf::image_crate::ImageOutputFormat;
+use rusqlite::Connection;
+
+use crate::catalog::builder::Catalog;
+use crate::catalog::job::Silent;
+use crate::catalog::options::CatalogOptions;
+use crate::catalog::render::{render_pages, ImageFilter};
+use crate::i18n;
+use crate::settings::settings_repository::Settings;
+
+const PREVIEW_WIDTH: i32 = 1240; // px, ~150 dpi em A4
+
+/// Página pedida na prévia: pelo número ou pelo item que aparece nela.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PreviewTarget {
+    Page(usize), // 1 = capa
+    Item(String),
+}
+
+/// Desenha uma página do catálogo em PNG. O PDF é gerado inteiro (uma vez)
+/// para a paginação ficar igual à final, mas só com as imagens da página pedida.
+pub fn preview_page(
+    catalog: &Catalog,
+    settings: &Settings,
+    options: &CatalogOptions,
+    target: &PreviewTarget,
+) -> Result<Vec<u8>, String> {
+    let images = match target {
+        PreviewTarget::Page(page) => ImageFilter::Page(*page),
+        PreviewTarget::Item(item_id) => ImageFilter::ItemPage(item_id.clone()),
+    };
+    let rendered = render_pages(catalog, settings, options, &images, &Silent)?;
+    let page = match target {
+        PreviewTarget::Page(page) => *page,
+        PreviewTarget::Item(item_id) => *rendered.item_pages.get(item_id).ok_or(i18n::text("item_not_in_catalog"))?,
+    };
+    if page == 0 || page > rendered.page_count {
+        return Err(i18n::message("page_out_of_range", &[&page, &rendered.page_count]));
+    }
+    rasterize(&rendered.bytes, page)
+}
+
+fn rasterize(pdf: &[u8], page: usize) -> Result<Vec<u8>, String> {
+    let pdfium = Pdfium::new(bind_pdfium()?);
+    let document = pdfium.load_pdf_from_byte_slice(pdf, None).map_err(|e| e.to_string())?;
+    let page = document.pages().get((page - 1) as u16).map_err(|e| e.to_string())?;
+    let bitmap = page
+        .render_with_config(&PdfRenderConfig::new().set_target_width(PREVIEW_WIDTH))
+        .map_err(|e| e.to_string())?;
+
+    let mut png = Cursor::new(Vec::new());
+    bitmap
+        .as_image()
+        .write_to(&mut png, ImageOutputFormat::Png)
+        .map_err(|e| i18n::message("png_failed", &[&e]))?;
+    Ok(png.into_inner())
+}
+
+// O instalador leva a biblioteca do PDFium (`bundle.resources` em
+// tauri.conf.json) para a pasta do executável; sem ela, tenta a do sistema.
+fn bind_pdfium() -> Result<Box<dyn PdfiumLibraryBindings>, String> {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(PathBuf::from))
+        .unwrap_or_default();
+    Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(&exe_dir))
+        .or_else(|_| Pdfium::bind_to_system_library())
+        .map_err(|e| i18n::message("pdfium_not_found", &[&e]))
+}
+
+/// Versão dos dados usados na prévia: alterações no banco (por esta conexão),
+/// os arquivos de imagem, as configurações da empresa e o idioma do app
+/// (padrão dos catálogos).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Revision {
+    changes: i64,
+    images: u64,
+    settings: String,
+    locale: &'static str,
+}
+
+pub fn current_revision(conn: &Connection, settings: &Settings) -> rusqlite::Result<Revision> {
+    Ok(Revision {
+        // Toda escrita do app passa por esta conexão, então o contador muda a
+        // cada seção, item ou info criado, alterado ou apagado.
+        changes: conn.query_row("SELECT total_changes()", [], |row| row.get(0))?,
+        images: image_fingerprint(conn, settings)?,
+        settings: serde_json::to_string(settings).unwrap_or_default(),
+        locale: i18n::locale(),
+    })
+}
+
+// Trocar a foto no disco mantendo o caminho não passa pelo banco, então entram
+// na versão o tamanho e a data de modificação de cada imagem usada.
+fn image_fingerprint(conn: &Connection, settings: &Settings) -> rusqlite::Result<u64> {
+    let mut stmt = conn.prepare(
+        "SELECT image_path FROM items WHERE image_path != ''
+         UNION SELECT image_path FROM variants WHERE image_path != ''
+         UNION SELECT cover_image FROM sections WHERE cover_image != ''
+         ORDER BY 1",
+    )?;
+    let paths = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut hasher = DefaultHasher::new();
+    for path in paths.chain(std::iter::once(Ok(settings.image_path.clone()))) {
+        let path = path?;
+        path.hash(&mut hasher);
+        if let Ok(metadata) = fs::metadata(&path) {
+            metadata.len().hash(&mut hasher);
+            metadata.modified().ok().hash(&mut hasher);
+        }
+    }
+    Ok(hasher.finish())
+}
+
+/// PNGs já gerados por preset e página; tudo é descartado quando os dados mudam.
+#[derive(Default)]
+pub struct PreviewCache {
+    revision: Option<Revision>,
+    pages: HashMap<(String, PreviewTarget), Vec<u8>>,
+}
+
+impl PreviewCache {
+    pub fn get(&mut self, revision: &Revision, preset: &str, target: &PreviewTarget) -> Option<Vec<u8>> {
+        if self.revision.as_ref() != Some(revision) {
+            self.revision = Some(revision.clone());
+            self.pages.clear();
+        }
+        self.pages.get(&(preset.to_string(), target.clone())).cloned()
+    }
+
+    pub fn insert(&mut self, revision: &Revision, preset: &str, target: PreviewTarget, png: Vec<u8>) {
+        if self.revision.as_ref() == Some(revision) {
+            self.pages.insert((preset.to_string(), target), png);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{item_repository, section_repository};
+
+    #[test]
+    fn replacing_an_image_file_changes_the_revision() {
+        let path = std::env::temp_dir().join("preview-revision-image.png");
+        fs::write(&path, b"first").unwrap();
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        let image_path = path.display().to_string();
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id, image_path).unwrap();
+
+        let settings = Settings::default();
+        let before = current_revision(&conn, &settings).unwrap();
+        assert_eq!(current_revision(&conn, &settings).unwrap(), before);
+
+        fs::write(&path, b"second version").unwrap();
+        assert_ne!(current_revision(&conn, &settings).unwrap(), before);
+    }
+
+    #[test]
+    fn the_cache_is_cleared_when_the_revision_changes() {
+        let conn = open_in_memory();
+        let settings = Settings::default();
+        let revision = current_revision(&conn, &settings).unwrap();
+        let mut cache = PreviewCache::default();
+        assert_eq!(cache.get(&revision, "Atacado", &PreviewTarget::Page(1)), None);
+        cache.insert(&revision, "Atacado", PreviewTarget::Page(1), vec![1, 2, 3]);
+        assert_eq!(cache.get(&revision, "Atacado", &PreviewTarget::Page(1)), Some(vec![1, 2, 3]));
+
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let changed = current_revision(&conn, &settings).unwrap();
+        assert_eq!(cache.get(&changed, "Atacado", &PreviewTarget::Page(1)), None);
+    }
+}
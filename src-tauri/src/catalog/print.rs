@@ -0,0 +1,152 @@
+use printpdf::image_crate::RgbImage;
+use printpdf::lopdf::{Document, Object};
+use printpdf::{
+    Cmyk, Color, ColorBits, ColorSpace, CurTransMat, ImageXObject, Line, Mm, PdfLayerReference, Point, Pt, Px, Rgb,
+};
+use serde::Serialize;
+
+use crate::catalog::options::CatalogOptions;
+
+const MARK_LENGTH: f32 = 5.0;
+const MARK_MIN_GAP: f32 = 3.0; // marcas nunca encostam no corte, mesmo sem sangria
+
+/// Imagem de item que vai sair borrada na gráfica.
+#[derive(Clone, Debug, Serialize)]
+pub struct LowResolutionImage {
+    pub item_id: String,
+    pub item_code: String,
+    pub image_path: String,
+    pub page: usize,
+    pub dpi: u32,
+}
+
+/// Tamanho final da página, sangria e a distância da borda do PDF até a linha
+/// de corte (sangria mais o espaço das marcas). Tudo em mm.
+#[derive(Clone, Copy, Debug)]
+pub struct PageGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub bleed: f32,
+    pub offset: f32,
+    pub crop_marks: bool,
+}
+
+impl PageGeometry {
+    pub fn new(options: &CatalogOptions) -> Self {
+        let (width, height) = options.page_size.catalog_dimensions();
+        let (bleed, crop_marks) = options
+            .profile
+            .print()
+            .map(|print| (print.bleed.max(0.0), print.crop_marks))
+            .unwrap_or((0.0, false));
+        let offset = if crop_marks { mark_gap(bleed) + MARK_LENGTH + 1.0 } else { bleed };
+        Self { width, height, bleed, offset, crop_marks }
+    }
+
+    /// Tamanho da página do PDF, com sangria e marcas.
+    pub fn media_size(&self) -> (f32, f32) {
+        (self.width + 2.0 * self.offset, self.height + 2.0 * self.offset)
+    }
+
+    /// Move a origem da camada para o canto da linha de corte; o resto do
+    /// catálogo desenha como se a página tivesse só o tamanho final.
+    pub fn place(&self, layer: &PdfLayerReference) {
+        if self.offset > 0.0 {
+            layer.set_ctm(CurTransMat::Translate(Mm(self.offset).into(), Mm(self.offset).into()));
+        }
+    }
+}
+
+fn mark_gap(bleed: f32) -> f32 {
+    bleed.max(MARK_MIN_GAP)
+}
+
+/// Cor da paleta convertida para CMYK (sem perfil de cor, conversão simples).
+pub fn cmyk_color(rgb: (f32, f32, f32)) -> Color {
+    let (c, m, y, k) = rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+    Color::Cmyk(Cmyk::new(c, m, y, k, None))
+}
+
+fn rgb_to_cmyk(r: f32, g: f32, b: f32) -> (f32, f32, f32, f32) {
+    let k = 1.0 - r.max(g).max(b);
+    if k >= 1.0 {
+        return (0.0, 0.0, 0.0, 1.0);
+    }
+    ((1.0 - r - k) / (1.0 - k), (1.0 - g - k) / (1.0 - k), (1.0 - b - k) / (1.0 - k), k)
+}
+
+/// Imagem RGB convertida para CMYK, pronta para o printpdf.
+pub fn cmyk_image(image: &RgbImage) -> ImageXObject {
+    let mut data = Vec::with_capacity(image.as_raw().len() / 3 * 4);
+    for pixel in image.pixels() {
+        let [r, g, b] = pixel.0.map(|channel| channel as f32 / 255.0);
+        let (c, m, y, k) = rgb_to_cmyk(r, g, b);
+        data.extend([c, m, y, k].map(|channel| (channel * 255.0).round() as u8));
+    }
+
+    ImageXObject {
+        width: Px(image.width() as usize),
+        height: Px(image.height() as usize),
+        color_space: ColorSpace::Cmyk,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: true,
+        image_data: data,
+        image_filter: None,
+        smask: None,
+        clipping_bbox: None,
+    }
+}
+
+/// Marcas de corte nos quatro cantos, fora da sangria, na cor de registro
+/// (sai em todas as chapas).
+pub fn crop_marks(layer: &PdfLayerReference, geometry: &PageGeometry, cmyk: bool) {
+    let color = if cmyk {
+        Color::Cmyk(Cmyk::new(1.0, 1.0, 1.0, 1.0, None))
+    } else {
+        Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None))
+    };
+    layer.set_outline_color(color);
+    layer.set_outline_thickness(0.25);
+
+    let gap = mark_gap(geometry.bleed);
+    let far = gap + MARK_LENGTH;
+    for x in [0.0, geometry.width] {
+        for y in [0.0, geometry.height] {
+            // Para fora da página: -1 nos cantos de baixo/esquerda, +1 nos de cima/direita.
+            let dx = if x == 0.0 { -1.0 } else { 1.0 };
+            let dy = if y == 0.0 { -1.0 } else { 1.0 };
+            layer.add_line(line((x + dx * gap, y), (x + dx * far, y)));
+            layer.add_line(line((x, y + dy * gap), (x, y + dy * far)));
+        }
+    }
+}
+
+fn line(from: (f32, f32), to: (f32, f32)) -> Line {
+    Line {
+        points: vec![(Point::new(Mm(from.0), Mm(from.1)), false), (Point::new(Mm(to.0), Mm(to.1)), false)],
+        is_closed: false,
+    }
+}
+
+/// Grava TrimBox e BleedBox em todas as páginas, para a gráfica saber onde cortar.
+pub fn set_page_boxes(doc: &mut Document, geometry: &PageGeometry) -> Result<(), String> {
+    let trim = geometry.offset;
+    let bleed = geometry.offset - geometry.bleed;
+    let box_object = |margin: f32, extra: f32| {
+        Object::Array(
+            [margin, margin, trim + geometry.width + extra, trim + geometry.height + extra]
+                .into_iter()
+                .map(|value| Pt::from(Mm(value)).0.into())
+                .collect(),
+        )
+    };
+    let trim_box = box_object(trim, 0.0);
+    let bleed_box = box_object(bleed, geometry.bleed);
+
+    for page in doc.get_pages().into_values() {
+        let page = doc.get_object_mut(page).and_then(Object::as_dict_mut).map_err(|e| e.to_string())?;
+        page.set("TrimBox", trim_box.clone());
+        page.set("BleedBox", bleed_box.clone());
+    }
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use qrcode::{Color, QrCode};
+
+use crate::catalog::labels::label;
+use crate::catalog::options::QrTarget;
+use crate::settings::settings_repository::Settings;
+
+const DEFAULT_PRODUCT_URL: &str = "{website_url}/p/{code}";
+
+/// QR code pronto para desenhar: quantos módulos tem cada lado e, por linha
+/// (de cima para baixo), os trechos escuros como (coluna inicial, coluna final).
+pub struct QrModules {
+    pub width: usize,
+    pub rows: Vec<Vec<(usize, usize)>>,
+}
+
+/// Monta o endereço do QR code de cada item com o site e o telefone das configurações.
+pub struct QrLinks {
+    target: QrTarget,
+    website_url: String,
+    phone_number: String,
+}
+
+impl QrLinks {
+    pub fn new(target: &QrTarget, settings: &Settings) -> Self {
+        Self {
+            target: target.clone(),
+            website_url: settings.website_url.clone(),
+            phone_number: settings.phone_number.clone(),
+        }
+    }
+
+    /// Endereço do item, ou `None` se faltar site ou telefone.
+    pub fn url(&self, code: &str, language: &str) -> Option<String> {
+        match &self.target {
+            QrTarget::Website { url } => {
+                let template = url.as_deref().filter(|url| !url.trim().is_empty()).unwrap_or(DEFAULT_PRODUCT_URL);
+                let website = self.website_url.trim().trim_end_matches('/');
+                if template.contains("{website_url}") && website.is_empty() {
+                    return None;
+                }
+                let website = if website.contains("://") { website.to_string() } else { format!("https://{}", website) };
+                Some(template.replace("{website_url}", &website).replace("{code}", &encode_component(code)))
+            }
+            QrTarget::Whatsapp { message } => {
+                let phone = whatsapp_number(&self.phone_number)?;
+                let message = message
+                    .as_deref()
+                    .filter(|message| !message.trim().is_empty())
+                    .unwrap_or_else(|| label("whatsapp_interest", language))
+                    .replace("{code}", code);
+                Some(format!("https://wa.me/{}?text={}", phone, encode_component(&message)))
+            }
+        }
+    }
+}
+
+/// Só os dígitos, com o código do país. Números com DDD e sem o país
+/// (10 ou 11 dígitos, sem "+") são tratados como brasileiros.
+fn whatsapp_number(phone: &str) -> Option<String> {
+    let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    let local = !phone.trim_start().starts_with('+') && (10..=11).contains(&digits.len());
+    Some(if local { format!("55{}", digits) } else { digits })
+}
+
+/// Codifica o texto para entrar na URL (percent-encoding do UTF-8).
+fn encode_component(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Gera o QR code; `None` se o texto não couber em nenhuma versão.
+pub fn modules(data: &str) -> Option<QrModules> {
+    let code = QrCode::new(data.as_bytes()).ok()?;
+    let width = code.width();
+    let colors = code.to_colors();
+
+    let rows = colors
+        .chunks(width)
+        .map(|row| {
+            let mut runs = Vec::new();
+            let mut start = None;
+            for (column, color) in row.iter().chain([&Color::Light]).enumerate() {
+                match (color, start) {
+                    (Color::Dark, None) => start = Some(column),
+                    (Color::Light, Some(first)) => {
+                        runs.push((first, column));
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            runs
+        })
+        .collect();
+    Some(QrModules { width, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(website_url: &str, phone_number: &str) -> Settings {
+        Settings { website_url: website_url.into(), phone_number: phone_number.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn modules_match_the_encoded_qr_code() {
+        let data = "https://loja.com.br/p/AN-1";
+        let qr = modules(data).unwrap();
+        let code = QrCode::new(data.as_bytes()).unwrap();
+        assert_eq!(qr.width, code.width());
+        assert_eq!(qr.rows.len(), qr.width);
+
+        let dark: Vec<bool> = code.to_colors().iter().map(|color| *color == Color::Dark).collect();
+        for (row, runs) in qr.rows.iter().enumerate() {
+            let mut drawn = vec![false; qr.width];
+            for &(start, end) in runs {
+                assert!(start < end && end <= qr.width);
+                drawn[start..end].iter_mut().for_each(|module| *module = true);
+            }
+            assert_eq!(drawn, dark[row * qr.width..(row + 1) * qr.width], "linha {}", row);
+        }
+        // Padrões de posição: 7 módulos escuros nos cantos de cima.
+        assert_eq!(qr.rows[0].first(), Some(&(0, 7)));
+        assert_eq!(qr.rows[0].last(), Some(&(qr.width - 7, qr.width)));
+    }
+
+    #[test]
+    fn data_too_long_for_any_version_has_no_qr_code() {
+        assert!(modules(&"A".repeat(8000)).is_none());
+    }
+
+    #[test]
+    fn website_links_use_the_settings_site_and_encode_the_code() {
+        let target = QrTarget::Website { url: None };
+        let links = QrLinks::new(&target, &settings("loja.com.br/", ""));
+        assert_eq!(links.url("AN 1/2", "pt").as_deref(), Some("https://loja.com.br/p/AN%201%2F2"));
+        assert_eq!(QrLinks::new(&target, &settings("", "")).url("AN-1", "pt"), None);
+
+        let custom = QrTarget::Website { url: Some("https://example.com/?ref={code}".into()) };
+        assert_eq!(QrLinks::new(&custom, &settings("", "")).url("AN-1", "pt").as_deref(), Some("https://example.com/?ref=AN-1"));
+    }
+
+    #[test]
+    fn whatsapp_links_add_the_brazilian_country_code_to_local_numbers() {
+        let target = QrTarget::Whatsapp { message: Some("Quero o {code}".into()) };
+        let link = QrLinks::new(&target, &settings("", "(11) 98765-4321")).url("AN-1", "pt").unwrap();
+        assert_eq!(link, "https://wa.me/5511987654321?text=Quero%20o%20AN-1");
+        let foreign = QrLinks::new(&target, &settings("", "+1 555 123 4567")).url("AN-1", "pt").unwrap();
+        assert!(foreign.starts_with("https://wa.me/15551234567?"));
+        assert_eq!(QrLinks::new(&target, &settings("", "")).url("AN-1", "pt"), None);
+    }
+}
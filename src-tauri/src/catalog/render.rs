@@ -0,0 +1,1336 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use printpdf::image_crate::{self, DynamicImage, GenericImageView};
+use printpdf::{
+    lopdf, Color, Image, ImageTransform, IndirectFontRef, Mm, OffsetDateTime, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Rect,
+};
+
+use crate::catalog::barcode::{self, Barcode};
+use crate::catalog::builder::{Catalog, CatalogItem, CatalogSection};
+use crate::catalog::fonts::{self, FontData};
+use crate::catalog::format::format_price;
+use crate::catalog::index::{IndexBuilder, IndexGroup};
+use crate::catalog::job::{Monitor, Phase, Progress, Silent};
+use crate::catalog::labels::label;
+use crate::catalog::metadata::{self, DocumentMetadata};
+use crate::catalog::navigation::{self, LinkTarget, OutlineEntry, PageLink};
+use crate::catalog::options::{BarcodeFormat, CatalogOptions, Layout, OutputProfile, Placement, PrintOptions, PriceMode, ScreenOptions};
+use crate::catalog::palette::Palette;
+use crate::catalog::print::{self, LowResolutionImage, PageGeometry};
+use crate::catalog::qr::{self, QrLinks};
+use crate::catalog::screen::{self, ScreenReport};
+use crate::i18n;
+use crate::settings::settings_repository::Settings;
+
+const MARGIN: f32 = 15.0;
+const IMAGE_BOX: f32 = 45.0;
+const ITEM_GAP: f32 = 8.0;
+const IMAGE_DPI: f32 = 300.0;
+const TABLE_ROW: f32 = 4.5;
+const VARIANT_IMAGE_ROW: f32 = 9.0; // linha da tabela com miniatura da variante
+const GRID_IMAGE: f32 = 60.0;
+const GRID_GAP: f32 = 8.0;
+const TOC_LINE: f32 = 7.0;
+const TOC_INDENT: f32 = 6.0;
+const MARGIN_TEXT: f32 = 8.0; // distância do cabeçalho/rodapé à borda
+const INDEX_LINE: f32 = 4.5;
+const QR_SIZE: f32 = 20.0;
+const QR_GAP: f32 = 4.0; // zona de silêncio do QR code até o texto
+const BARCODE_HEIGHT: f32 = 10.0;
+const BARCODE_BLOCK: f32 = BARCODE_HEIGHT + 7.0; // barras, texto embaixo e espaço
+const BARCODE_MODULE: f32 = 0.33; // largura máxima de um módulo, em mm
+const BARCODE_MAX_WIDTH: f32 = 50.0;
+
+pub const PT_TO_MM: f32 = 0.3528;
+
+/// Corpo (regular e negrito) e títulos; `char_width` é a largura média de um
+/// caractere do corpo em relação ao tamanho da fonte.
+struct Fonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    heading: IndirectFontRef,
+    char_width: f32,
+}
+
+/// Página já criada: camada para desenhar depois e seção de primeiro nível
+/// em andamento, usadas no cabeçalho/rodapé.
+struct PageInfo {
+    layer: PdfLayerReference,
+    section: Option<String>,
+    decorated: bool, // capa e divisórias ficam sem cabeçalho, rodapé e número
+}
+
+/// Quais imagens desenhar. Na prévia só importam as da página mostrada; a
+/// paginação é a mesma em qualquer caso.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImageFilter {
+    All,
+    Page(usize), // 0 = nenhuma
+    /// A página onde o item cair, descoberta na própria geração: as imagens
+    /// ficam guardadas e só as daquela página são desenhadas no fim.
+    ItemPage(String),
+}
+
+// Imagem guardada com `ImageFilter::ItemPage` até se saber a página do item.
+struct DeferredImage {
+    page_number: usize,
+    layer: PdfLayerReference,
+    path: PathBuf,
+    x: f32,
+    y: f32,
+    box_width: f32,
+    box_height: f32,
+}
+
+/// Estado de paginação: página atual e posição vertical (em mm a partir da base).
+struct Renderer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    page_number: usize,
+    y: f32,
+    fonts: Fonts,
+    palette: Palette,
+    language: String,
+    pages: Vec<PageInfo>,
+    section: Option<String>,
+    outline: Vec<OutlineEntry>,
+    links: Vec<PageLink>,
+    index: Option<IndexBuilder>,
+    price_mode: PriceMode,
+    image_filter: ImageFilter,
+    deferred_images: Vec<DeferredImage>,
+    item_pages: HashMap<String, usize>,
+    progress: Progress,
+    geometry: PageGeometry,
+    print: Option<PrintOptions>,
+    low_resolution: Vec<LowResolutionImage>,
+    screen: Option<ScreenOptions>,
+    qr_links: Option<QrLinks>,
+    barcode: Option<BarcodeFormat>,
+}
+
+/// PDF gerado e onde cada item foi parar, usado pela prévia.
+pub struct RenderedCatalog {
+    pub bytes: Vec<u8>,
+    pub page_count: usize,
+    pub item_pages: HashMap<String, usize>, // id do item -> página
+    /// Só no perfil de gráfica: imagens de itens abaixo de `min_dpi`.
+    pub low_resolution: Vec<LowResolutionImage>,
+    /// Só no perfil de tela: tamanho final e qualidade das imagens.
+    pub screen: Option<ScreenReport>,
+    /// Só no perfil de tela: bytes das imagens, para estimar outra qualidade.
+    image_bytes: usize,
+}
+
+/// Repassa só o cancelamento: a segunda passada do perfil de tela não faz a
+/// barra de progresso voltar ao começo.
+struct Unreported<'a>(&'a dyn Monitor);
+
+impl Monitor for Unreported<'_> {
+    fn report(&self, _progress: &Progress) {}
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+/// Gera o PDF do catálogo e devolve os bytes do arquivo.
+pub fn render_catalog(catalog: &Catalog, settings: &Settings, options: &CatalogOptions) -> Result<Vec<u8>, String> {
+    render_pages(catalog, settings, options, &ImageFilter::All, &Silent).map(|rendered| rendered.bytes)
+}
+
+/// Com `images` diferente de `All`, as imagens (a parte lenta) só são
+/// desenhadas numa página. O progresso vai para `monitor` a cada item, e a
+/// geração para com erro se ele pedir o cancelamento.
+pub fn render_pages(
+    catalog: &Catalog,
+    settings: &Settings,
+    options: &CatalogOptions,
+    images: &ImageFilter,
+    monitor: &dyn Monitor,
+) -> Result<RenderedCatalog, String> {
+    // O PDF/A-2b usa o perfil sRGB como OutputIntent; cores CMYK sem perfil não passam.
+    if options.is_pdf_a() && options.profile.print().is_some_and(|print| print.cmyk) {
+        return Err(i18n::text("pdf_a_cmyk").to_string());
+    }
+    let mut rendered = render_document(catalog, settings, options, images, monitor)?;
+    let Some(mut current) = options.profile.screen().cloned() else {
+        return Ok(rendered);
+    };
+
+    // No perfil de tela, passou do tamanho pedido: a qualidade das imagens é
+    // estimada pelos bytes delas e o catálogo é refeito uma vez só.
+    if *images == ImageFilter::All {
+        let next = screen::fit(rendered.bytes.len(), rendered.image_bytes, &current);
+        if next != current {
+            let options = CatalogOptions { profile: OutputProfile::Screen(next.clone()), ..options.clone() };
+            rendered = render_document(catalog, settings, &options, images, &Unreported(monitor))?;
+            current = next;
+        }
+    }
+    rendered.screen = Some(ScreenReport::new(rendered.bytes.len(), &current));
+    Ok(rendered)
+}
+
+fn render_document(
+    catalog: &Catalog,
+    settings: &Settings,
+    options: &CatalogOptions,
+    images: &ImageFilter,
+    monitor: &dyn Monitor,
+) -> Result<RenderedCatalog, String> {
+    let mut renderer = Renderer::new(settings, options)?;
+    renderer.image_filter = images.clone();
+    renderer.progress.section_count = catalog.sections.len();
+    renderer.progress.item_count = catalog.sections.iter().map(|section| section.items.len()).sum();
+
+    renderer.cover(settings);
+    // O sumário precisa das páginas das seções, então as páginas dele são
+    // reservadas agora e preenchidas no fim.
+    let toc_pages = if options.table_of_contents {
+        let entries = catalog.sections.len() + usize::from(options.code_index);
+        renderer.reserve_pages(toc_page_count(entries, renderer.geometry.height))
+    } else {
+        Vec::new()
+    };
+
+    if options.code_index {
+        renderer.index = Some(IndexBuilder::new(&options.index_infos));
+    }
+    for section in &catalog.sections {
+        renderer.progress.section += 1;
+        monitor.report(&renderer.progress);
+        renderer.section(section, options, monitor);
+        if monitor.is_cancelled() {
+            return Err(i18n::text("generation_cancelled").to_string());
+        }
+    }
+    if let Some(index) = renderer.index.take() {
+        renderer.back_index(index.groups(label("codes", options.language())));
+    }
+    renderer.table_of_contents(&toc_pages);
+    renderer.decorate(settings, options);
+    renderer.draw_deferred_images();
+
+    let page_count = renderer.pages.len();
+    let Renderer { doc, outline, mut links, item_pages, geometry, low_resolution, .. } = renderer;
+    let bytes = doc.save_to_bytes().map_err(|e| e.to_string())?;
+
+    // Links, marcadores e metadados que o printpdf não sabe gravar. Os links
+    // foram medidos a partir do corte; no PDF contam a partir da borda.
+    for link in &mut links {
+        let (x1, y1, x2, y2) = link.rect;
+        let offset = geometry.offset;
+        link.rect = (x1 + offset, y1 + offset, x2 + offset, y2 + offset);
+    }
+    let mut pdf = lopdf::Document::load_mem(&bytes).map_err(|e| e.to_string())?;
+    navigation::apply(&mut pdf, &outline, &links)?;
+    if options.profile.print().is_some() {
+        print::set_page_boxes(&mut pdf, &geometry)?;
+    }
+    let mut image_bytes = 0;
+    let bytes = if options.profile.screen().is_some() {
+        image_bytes = screen::image_bytes(&pdf);
+        screen::optimize(pdf)?
+    } else {
+        metadata::apply(&mut pdf, &document_metadata(catalog, settings, options), options.is_pdf_a())?;
+        metadata::save(&mut pdf, options.is_pdf_a())?
+    };
+
+    Ok(RenderedCatalog { bytes, page_count, item_pages, low_resolution, screen: None, image_bytes })
+}
+
+fn document_metadata(catalog: &Catalog, settings: &Settings, options: &CatalogOptions) -> DocumentMetadata {
+    let language = options.language();
+    let non_empty = |text: &Option<String>| text.as_deref().map(str::trim).filter(|text| !text.is_empty()).map(str::to_string);
+    let keywords = if options.keywords.is_empty() {
+        catalog
+            .sections
+            .iter()
+            .filter(|section| section.depth == 0)
+            .map(|section| section.section.name.clone())
+            .collect()
+    } else {
+        options.keywords.clone()
+    };
+
+    DocumentMetadata {
+        title: non_empty(&options.title).unwrap_or_else(|| format!("{} - {}", settings.name, label("catalog", language))),
+        author: settings.name.clone(),
+        subject: non_empty(&options.subject).unwrap_or_else(|| label("catalog_subject", language).to_string()),
+        keywords,
+        created: OffsetDateTime::now_utc(),
+    }
+}
+
+impl Renderer {
+    fn new(settings: &Settings, options: &CatalogOptions) -> Result<Self, String> {
+        let geometry = PageGeometry::new(options);
+        let (media_width, media_height) = geometry.media_size();
+        let (doc, page, layer) = PdfDocument::new(&settings.name, Mm(media_width), Mm(media_height), "Layer 1");
+        let doc = doc.with_conformance(metadata::conformance());
+        let fonts = load_fonts(&doc, settings, options)?;
+        let layer = doc.get_page(page).get_layer(layer);
+        geometry.place(&layer);
+        // A primeira página é a capa.
+        let pages = vec![PageInfo { layer: layer.clone(), section: None, decorated: false }];
+
+        Ok(Self {
+            doc,
+            layer,
+            page_number: 1,
+            y: geometry.height - MARGIN,
+            fonts,
+            palette: Palette::from_name(options.palette.as_deref().unwrap_or(&settings.pallet)),
+            language: options.language().to_string(),
+            pages,
+            section: None,
+            outline: Vec::new(),
+            links: Vec::new(),
+            index: None,
+            price_mode: options.price_mode,
+            image_filter: ImageFilter::All,
+            deferred_images: Vec::new(),
+            item_pages: HashMap::new(),
+            progress: Progress { phase: Phase::Rendering, section: 0, section_count: 0, item: 0, item_count: 0 },
+            geometry,
+            print: options.profile.print().cloned(),
+            low_resolution: Vec::new(),
+            screen: options.profile.screen().cloned(),
+            qr_links: options.qr_code.as_ref().map(|target| QrLinks::new(target, settings)),
+            barcode: options.barcode,
+        })
+    }
+
+    fn new_page(&mut self) {
+        let (media_width, media_height) = self.geometry.media_size();
+        let (page, layer) = self.doc.add_page(Mm(media_width), Mm(media_height), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.geometry.place(&self.layer);
+        self.page_number += 1;
+        self.y = self.geometry.height - MARGIN;
+        self.pages.push(PageInfo {
+            layer: self.layer.clone(),
+            section: self.section.clone(),
+            decorated: true,
+        });
+    }
+
+    /// Adiciona `count` páginas em branco e devolve os números delas para desenhar depois.
+    fn reserve_pages(&mut self, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                self.new_page();
+                self.page_number
+            })
+            .collect()
+    }
+
+    /// Quebra a página se não houver `height` mm livres.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    /// Como `ensure_space`, mas um bloco maior que a página só precisa de
+    /// `min_height` livres: o resto continua nas páginas seguintes.
+    fn ensure_space_for(&mut self, height: f32, min_height: f32) {
+        if height <= self.geometry.height - 2.0 * MARGIN {
+            self.ensure_space(height);
+        } else {
+            self.ensure_space(min_height);
+        }
+    }
+
+    /// Volta para uma página já criada (a segunda coluna da grade começa
+    /// na mesma página que a primeira).
+    fn go_to_page(&mut self, page_number: usize) {
+        self.page_number = page_number;
+        self.layer = self.pages[page_number - 1].layer.clone();
+    }
+
+    /// Segue para a próxima página, criando-a se ainda não existir.
+    fn next_page(&mut self) {
+        if self.page_number < self.pages.len() {
+            self.go_to_page(self.page_number + 1);
+            self.y = self.geometry.height - MARGIN;
+        } else {
+            self.new_page();
+        }
+    }
+
+    fn cover(&mut self, settings: &Settings) {
+        self.fill_page(self.palette.primary);
+        self.layer.set_fill_color(self.color((1.0, 1.0, 1.0)));
+        self.layer.use_text(&settings.name, 32.0, Mm(MARGIN), Mm(self.geometry.height / 2.0), &self.fonts.heading);
+
+        let contacts: Vec<&str> = [&settings.website_url, &settings.phone_number, &settings.email]
+            .into_iter()
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+            .collect();
+        let mut y = self.geometry.height / 2.0 - 12.0;
+        for contact in contacts {
+            self.layer.use_text(contact, 11.0, Mm(MARGIN), Mm(y), &self.fonts.regular);
+            y -= 6.0;
+        }
+    }
+
+    /// Seções de primeiro nível abrem página nova (ou uma página divisória);
+    /// subseções seguem na mesma página com título menor.
+    fn section(&mut self, section: &CatalogSection, options: &CatalogOptions, monitor: &dyn Monitor) {
+        let base_palette = self.palette;
+        if let Some(name) = section.section.palette.as_deref() {
+            self.palette = Palette::from_name(name);
+        }
+
+        let size = heading_size(section.depth);
+        let divider = section.depth == 0 && options.section_dividers;
+        if section.depth == 0 {
+            self.section = Some(section.section.name.clone());
+            self.new_page();
+        } else {
+            self.ensure_space(size * PT_TO_MM + IMAGE_BOX + 2.0 * ITEM_GAP);
+        }
+
+        self.outline.push(OutlineEntry {
+            depth: section.depth,
+            title: section.section.name.clone(),
+            page: self.page_number,
+            item: false,
+        });
+
+        if divider {
+            self.divider_page(section);
+            if let Some(page) = self.pages.last_mut() {
+                page.decorated = false;
+            }
+            self.new_page();
+        } else {
+            self.heading(section, size);
+        }
+
+        let layout = Layout::parse(section.section.layout.as_deref().or(options.layout.as_deref()));
+        let bookmark_depth = options.bookmark_items.then_some(section.depth + 1);
+        self.items(&section.items, layout, bookmark_depth, monitor);
+        self.palette = base_palette;
+    }
+
+    fn heading(&mut self, section: &CatalogSection, size: f32) {
+        self.layer.set_fill_color(self.color(self.palette.primary));
+        self.layer.use_text(&section.section.name, size, Mm(MARGIN), Mm(self.y - size * PT_TO_MM), &self.fonts.heading);
+        self.y -= size * PT_TO_MM + 4.0;
+        let rule = if section.depth == 0 { 0.8 } else { 0.3 };
+        self.fill_rect(MARGIN, self.y, self.geometry.width - MARGIN, self.y + rule, self.palette.accent);
+        self.y -= 4.0;
+
+        if let Some(description) = section.section.description.as_deref().filter(|text| !text.is_empty()) {
+            self.layer.set_fill_color(self.color(self.palette.muted));
+            for line in wrap(description, self.geometry.width - 2.0 * MARGIN, 10.0, self.fonts.char_width) {
+                self.y -= 4.5;
+                self.layer.use_text(line, 10.0, Mm(MARGIN), Mm(self.y), &self.fonts.regular);
+            }
+        }
+        self.y -= ITEM_GAP;
+    }
+
+    /// Página de abertura da seção: capa (se houver), nome e descrição sobre a cor da paleta.
+    fn divider_page(&mut self, section: &CatalogSection) {
+        self.fill_page(self.palette.primary);
+
+        let mut y = self.geometry.height / 2.0;
+        if let Some(path) = section.section.cover_image.as_deref().filter(|path| !path.is_empty()) {
+            let cover_height = self.geometry.height / 2.0 - MARGIN;
+            self.image(Path::new(path), MARGIN, self.geometry.height / 2.0, self.geometry.width - 2.0 * MARGIN, cover_height);
+            y -= 10.0;
+        }
+
+        self.fill_rect(MARGIN, y - 2.0, MARGIN + 30.0, y - 1.2, self.palette.accent);
+        y -= 14.0;
+        self.layer.set_fill_color(self.color((1.0, 1.0, 1.0)));
+        self.layer.use_text(&section.section.name, 30.0, Mm(MARGIN), Mm(y), &self.fonts.heading);
+
+        if let Some(description) = section.section.description.as_deref().filter(|text| !text.is_empty()) {
+            y -= 6.0;
+            for line in wrap(description, self.geometry.width - 2.0 * MARGIN, 12.0, self.fonts.char_width) {
+                y -= 6.0;
+                self.layer.use_text(line, 12.0, Mm(MARGIN), Mm(y), &self.fonts.regular);
+            }
+        }
+    }
+
+    /// Cada linha do sumário vira um link para a página da seção.
+    fn table_of_contents(&mut self, pages: &[usize]) {
+        let per_page = toc_entries_per_page(self.geometry.height);
+        let sections: Vec<&OutlineEntry> = self.outline.iter().filter(|entry| !entry.item).collect();
+        for (index, &page_number) in pages.iter().enumerate() {
+            let layer = &self.pages[page_number - 1].layer;
+            let mut y = self.geometry.height - MARGIN;
+            if index == 0 {
+                layer.set_fill_color(self.color(self.palette.primary));
+                layer.use_text(label("contents", &self.language), 22.0, Mm(MARGIN), Mm(y - 8.0), &self.fonts.heading);
+                y -= 12.0;
+            }
+            y -= TOC_LINE;
+
+            for entry in sections.iter().skip(index * per_page).take(per_page) {
+                let (font, size) = if entry.depth == 0 {
+                    (&self.fonts.bold, 12.0)
+                } else {
+                    (&self.fonts.regular, 10.0)
+                };
+                let page = entry.page.to_string();
+
+                layer.set_fill_color(self.color(self.palette.text));
+                layer.use_text(&entry.title, size, Mm(MARGIN + entry.depth as f32 * TOC_INDENT), Mm(y), font);
+                layer.use_text(&page, size, Mm(self.geometry.width - MARGIN - text_len(&page, size, self.fonts.char_width)), Mm(y), font);
+                self.links.push(PageLink {
+                    page: page_number,
+                    rect: (MARGIN, y - 2.0, self.geometry.width - MARGIN, y + TOC_LINE - 2.0),
+                    target: LinkTarget::Page(entry.page),
+                });
+                y -= TOC_LINE;
+            }
+        }
+    }
+
+    /// Índice remissivo em duas colunas: termo à esquerda, páginas à direita.
+    /// Termos com uma página só viram link para ela.
+    fn back_index(&mut self, groups: Vec<IndexGroup>) {
+        self.section = None;
+        self.new_page();
+        self.outline.push(OutlineEntry {
+            depth: 0,
+            title: label("index", &self.language).to_string(),
+            page: self.page_number,
+            item: false,
+        });
+
+        self.layer.set_fill_color(self.color(self.palette.primary));
+        self.layer.use_text(label("index", &self.language), 22.0, Mm(MARGIN), Mm(self.y - 8.0), &self.fonts.heading);
+        self.y -= 16.0;
+
+        let width = (self.geometry.width - 2.0 * MARGIN - GRID_GAP) / 2.0;
+        let mut top = self.y;
+        let mut column = 0;
+        for group in &groups {
+            for (row, entry) in group.entries.iter().enumerate() {
+                // O título do grupo não fica sozinho no fim da coluna.
+                let needed = if row == 0 { 2.0 * INDEX_LINE + 3.0 } else { INDEX_LINE };
+                if self.y - needed < MARGIN {
+                    column += 1;
+                    if column == 2 {
+                        column = 0;
+                        self.new_page();
+                        top = self.y;
+                    }
+                    self.y = top;
+                }
+                let x = MARGIN + column as f32 * (width + GRID_GAP);
+
+                if row == 0 {
+                    self.y -= INDEX_LINE;
+                    self.layer.set_fill_color(self.color(self.palette.primary));
+                    self.layer.use_text(&group.title, 11.0, Mm(x), Mm(self.y), &self.fonts.bold);
+                    self.y -= 3.0;
+                }
+
+                self.y -= INDEX_LINE;
+                let pages = entry.pages.iter().map(usize::to_string).collect::<Vec<_>>().join(", ");
+                let pages_width = text_len(&pages, 9.0, self.fonts.char_width);
+                let term_chars = ((width - pages_width - 3.0) / (9.0 * self.fonts.char_width * PT_TO_MM)).max(1.0) as usize;
+                let term: String = entry.term.chars().take(term_chars).collect();
+
+                self.layer.set_fill_color(self.color(self.palette.text));
+                self.layer.use_text(&term, 9.0, Mm(x), Mm(self.y), &self.fonts.regular);
+                self.layer.set_fill_color(self.color(self.palette.muted));
+                self.layer.use_text(&pages, 9.0, Mm(x + width - pages_width), Mm(self.y), &self.fonts.regular);
+
+                if let [page] = entry.pages[..] {
+                    self.links.push(PageLink {
+                        page: self.page_number,
+                        rect: (x, self.y - 1.2, x + width, self.y + INDEX_LINE - 1.2),
+                        target: LinkTarget::Page(page),
+                    });
+                }
+            }
+            self.y -= 2.0;
+        }
+    }
+
+    /// Número da página, cabeçalho, rodapé e marcas de corte; desenhados no fim,
+    /// quando o total de páginas é conhecido.
+    fn decorate(&self, settings: &Settings, options: &CatalogOptions) {
+        let total = self.pages.len().to_string();
+        let header = options.header.as_deref().filter(|text| !text.is_empty());
+        let footer = options.footer.as_deref().filter(|text| !text.is_empty());
+
+        if self.geometry.crop_marks {
+            for page in &self.pages {
+                print::crop_marks(&page.layer, &self.geometry, self.cmyk());
+            }
+        }
+        for (index, page) in self.pages.iter().enumerate().filter(|(_, page)| page.decorated) {
+            let number = (index + 1).to_string();
+            let fill = |template: &str| {
+                template
+                    .replace("{company}", &settings.name)
+                    .replace("{section}", page.section.as_deref().unwrap_or(""))
+                    .replace("{page}", &number)
+                    .replace("{pages}", &total)
+            };
+
+            page.layer.set_fill_color(self.color(self.palette.muted));
+            if let Some(placement) = options.page_numbers {
+                let text = format!("{} {}", label("page", &self.language), number);
+                self.page_text(&page.layer, &text, placement);
+            }
+            if let Some(text) = header {
+                self.page_text(&page.layer, &fill(text), options.header_placement.unwrap_or(Placement::TopLeft));
+            }
+            if let Some(text) = footer {
+                self.page_text(&page.layer, &fill(text), options.footer_placement.unwrap_or(Placement::BottomLeft));
+            }
+        }
+    }
+
+    fn page_text(&self, layer: &PdfLayerReference, text: &str, placement: Placement) {
+        let size = 8.0;
+        let width = text_len(text, size, self.fonts.char_width);
+        let x = match placement {
+            Placement::TopLeft | Placement::BottomLeft => MARGIN,
+            Placement::TopCenter | Placement::BottomCenter => (self.geometry.width - width) / 2.0,
+            Placement::TopRight | Placement::BottomRight => self.geometry.width - MARGIN - width,
+        };
+        let y = if placement.is_top() { self.geometry.height - MARGIN_TEXT } else { MARGIN_TEXT - size * PT_TO_MM };
+        layer.use_text(text, size, Mm(x), Mm(y), &self.fonts.regular);
+    }
+
+    fn items(&mut self, items: &[CatalogItem], layout: Layout, bookmark_depth: Option<usize>, monitor: &dyn Monitor) {
+        let per_row = match layout {
+            Layout::List => 1,
+            Layout::Grid => 2,
+        };
+        for row in items.chunks(per_row) {
+            if monitor.is_cancelled() {
+                return;
+            }
+            match layout {
+                Layout::List => self.list_item(&row[0], bookmark_depth),
+                Layout::Grid => self.grid_row(row, bookmark_depth),
+            }
+            self.progress.item += row.len();
+            monitor.report(&self.progress);
+        }
+    }
+
+    /// Registra a página do item no índice e, se os itens tiverem marcadores, no outline.
+    fn mark_item(&mut self, entry: &CatalogItem, depth: Option<usize>) {
+        self.item_pages.insert(entry.item.id.clone(), self.page_number);
+        if let Some(index) = &mut self.index {
+            index.add_item(entry, self.page_number);
+        }
+        if let Some(depth) = depth {
+            self.outline.push(OutlineEntry {
+                depth,
+                title: entry.item.code.clone(),
+                page: self.page_number,
+                item: true,
+            });
+        }
+    }
+
+    /// Layout em lista: imagem à esquerda, texto à direita, variantes embaixo.
+    fn list_item(&mut self, entry: &CatalogItem, bookmark_depth: Option<usize>) {
+        let qr_url = self.qr_url(entry);
+        let qr_space = if qr_url.is_some() { QR_SIZE + QR_GAP } else { 0.0 };
+        let item_barcode = self.item_barcode(entry);
+        let barcode_height = if item_barcode.is_some() { BARCODE_BLOCK } else { 0.0 };
+        let text_x = MARGIN + IMAGE_BOX + 6.0;
+        let text_width = self.geometry.width - MARGIN - text_x - qr_space;
+        let text_height = self.item_text_height(entry, text_width) + barcode_height;
+        let block = text_height.max(IMAGE_BOX);
+
+        self.ensure_space_for(block + variant_table_height(entry), block + variant_table_min_height(entry));
+        self.mark_item(entry, bookmark_depth);
+        let top = self.y;
+
+        if let Some(path) = entry.item.image_path.as_deref().filter(|path| !path.is_empty()) {
+            self.check_resolution(entry, path, IMAGE_BOX, IMAGE_BOX);
+            self.image(Path::new(path), MARGIN, top - IMAGE_BOX, IMAGE_BOX, IMAGE_BOX);
+        }
+        let mut y = self.item_text(entry, text_x, top, text_width);
+        if let Some(item_barcode) = &item_barcode {
+            y = self.barcode(item_barcode, text_x, y, text_width);
+        }
+        if let Some(url) = &qr_url {
+            self.qr_code(url, self.geometry.width - MARGIN - QR_SIZE, top - QR_SIZE);
+        }
+
+        let bottom = if entry.variants.is_empty() {
+            top - block
+        } else {
+            let table_top = (top - IMAGE_BOX).min(y) - 2.0;
+            self.variant_table(entry, MARGIN, table_top, self.geometry.width - 2.0 * MARGIN) - 2.0
+        };
+
+        self.y = bottom - ITEM_GAP;
+    }
+
+    /// Layout em grade: dois itens por linha, imagem em cima e texto embaixo
+    /// (com o QR code à direita do texto).
+    fn grid_row(&mut self, row: &[CatalogItem], bookmark_depth: Option<usize>) {
+        let width = (self.geometry.width - 2.0 * MARGIN - GRID_GAP) / 2.0;
+        let qr_urls: Vec<Option<String>> = row.iter().map(|entry| self.qr_url(entry)).collect();
+        let barcodes: Vec<Option<Barcode>> = row.iter().map(|entry| self.item_barcode(entry)).collect();
+        let text_width = |qr_url: &Option<String>| grid_text_width(width, qr_url.is_some());
+        let blocks: Vec<f32> = row
+            .iter()
+            .zip(&qr_urls)
+            .zip(&barcodes)
+            .map(|((entry, qr_url), item_barcode)| {
+                let barcode_height = if item_barcode.is_some() { BARCODE_BLOCK } else { 0.0 };
+                let text_height = self.item_text_height(entry, text_width(qr_url)) + barcode_height;
+                let qr_height = if qr_url.is_some() { QR_SIZE } else { 0.0 };
+                GRID_IMAGE + 3.0 + text_height.max(qr_height)
+            })
+            .collect();
+        let height = row.iter().zip(&blocks).map(|(entry, block)| block + variant_table_height(entry)).fold(0.0, f32::max);
+        let min_height = row.iter().zip(&blocks).map(|(entry, block)| block + variant_table_min_height(entry)).fold(0.0, f32::max);
+
+        self.ensure_space_for(height, min_height);
+        row.iter().for_each(|entry| self.mark_item(entry, bookmark_depth));
+        let top = self.y;
+        let first_page = self.page_number;
+        // Onde a linha termina: a coluna que foi mais longe (página, depois y).
+        let mut end = (first_page, top - blocks.iter().copied().fold(0.0, f32::max));
+
+        for (column, ((entry, qr_url), item_barcode)) in row.iter().zip(&qr_urls).zip(&barcodes).enumerate() {
+            self.go_to_page(first_page);
+            let x = MARGIN + column as f32 * (width + GRID_GAP);
+            if let Some(path) = entry.item.image_path.as_deref().filter(|path| !path.is_empty()) {
+                self.check_resolution(entry, path, width, GRID_IMAGE);
+                self.image(Path::new(path), x, top - GRID_IMAGE, width, GRID_IMAGE);
+            }
+            let text_top = top - GRID_IMAGE - 3.0;
+            let mut y = self.item_text(entry, x, text_top, text_width(qr_url));
+            if let Some(item_barcode) = item_barcode {
+                y = self.barcode(item_barcode, x, y, text_width(qr_url));
+            }
+            if let Some(url) = qr_url {
+                self.qr_code(url, x + width - QR_SIZE, text_top - QR_SIZE);
+                y = y.min(text_top - QR_SIZE);
+            }
+            if !entry.variants.is_empty() {
+                let bottom = self.variant_table(entry, x, y - 2.0, width) - 2.0;
+                if self.page_number > end.0 || (self.page_number == end.0 && bottom < end.1) {
+                    end = (self.page_number, bottom);
+                }
+            }
+        }
+
+        self.go_to_page(end.0);
+        self.y = end.1 - ITEM_GAP;
+    }
+
+    /// Código, selo, descrição e especificações a partir de `top`; devolve o y final.
+    fn item_text(&self, entry: &CatalogItem, x: f32, top: f32, width: f32) -> f32 {
+        let mut y = top - 5.0;
+        self.layer.set_fill_color(self.color(self.palette.text));
+        self.layer.use_text(&entry.item.code, 13.0, Mm(x), Mm(y), &self.fonts.bold);
+        if let Some(badge) = &entry.badge {
+            let badge_x = x + text_len(&entry.item.code, 13.0, self.fonts.char_width) + 4.0;
+            self.badge(badge, badge_x, y);
+        }
+        y -= 6.5;
+
+        self.layer.set_fill_color(self.color(self.palette.text));
+        for line in wrap(&entry.item.description, width, 10.0, self.fonts.char_width) {
+            self.layer.use_text(line, 10.0, Mm(x), Mm(y), &self.fonts.regular);
+            y -= 4.5;
+        }
+
+        y -= 1.5;
+        let value_x = x + spec_label_width(entry, self.fonts.char_width);
+        for info in &entry.infos {
+            self.layer.set_fill_color(self.color(self.palette.muted));
+            self.layer.use_text(&info.name, 9.0, Mm(x), Mm(y), &self.fonts.bold);
+            self.layer.set_fill_color(self.color(self.palette.text));
+            self.layer.use_text(&info.details, 9.0, Mm(value_x), Mm(y), &self.fonts.regular);
+            y -= 4.2;
+        }
+        if let Some(price) = self.price_line(entry) {
+            self.layer.set_fill_color(self.color(self.palette.text));
+            self.layer.use_text(price, 9.0, Mm(x), Mm(y), &self.fonts.bold);
+            y -= 4.2;
+        }
+        y
+    }
+
+    fn item_text_height(&self, entry: &CatalogItem, width: f32) -> f32 {
+        let price_line = if self.price_line(entry).is_some() { 4.2 } else { 0.0 };
+        item_text_height(entry, width, self.fonts.char_width) + price_line
+    }
+
+    /// "Sob consulta" para itens sem tabela de variantes; nos outros o aviso
+    /// vai na coluna de preço.
+    fn price_line(&self, entry: &CatalogItem) -> Option<String> {
+        (self.price_mode == PriceMode::OnRequest && entry.variants.is_empty())
+            .then(|| label("on_request", &self.language).to_string())
+    }
+
+    /// Tabela compacta das variantes: miniatura (se alguma variante tiver
+    /// imagem), código, um valor por eixo e preço. Quando não cabe, continua
+    /// na página seguinte com o cabeçalho repetido. Devolve o y do fim da tabela.
+    fn variant_table(&mut self, entry: &CatalogItem, x: f32, top: f32, width: f32) -> f32 {
+        let show_price = match self.price_mode {
+            PriceMode::Hide => false,
+            PriceMode::OnRequest => true,
+            PriceMode::Show => entry.variants.iter().any(|variant| variant.price.is_some()),
+        };
+        let row_height = variant_row_height(entry);
+        let thumbnail = if row_height > TABLE_ROW { row_height } else { 0.0 };
+
+        let mut header = vec![label("code", &self.language).to_string()];
+        header.extend(entry.variant_axes.iter().map(|axis| axis.name.clone()));
+        if show_price {
+            header.push(label("price", &self.language).to_string());
+        }
+        let text_x = x + thumbnail;
+        let column = (width - thumbnail) / header.len() as f32;
+
+        let mut bottom = self.variant_header(&header, x, text_x, top, width, column);
+        for (row, variant) in entry.variants.iter().enumerate() {
+            if bottom - row_height < MARGIN {
+                self.next_page();
+                bottom = self.variant_header(&header, x, text_x, self.geometry.height - MARGIN, width, column);
+            }
+            let row_top = bottom;
+            bottom -= row_height;
+            if row % 2 == 1 {
+                self.fill_rect(x, bottom, x + width, row_top, (0.94, 0.94, 0.94));
+            }
+            if let Some(path) = variant.image_path.as_deref().filter(|path| !path.is_empty()) {
+                self.image(Path::new(path), x + 0.5, bottom + 0.5, thumbnail - 1.0, row_height - 1.0);
+            }
+            let y = bottom + (row_height - TABLE_ROW) / 2.0 + 1.5;
+
+            let mut cells = vec![variant.code.clone()];
+            cells.extend(entry.variant_axes.iter().map(|axis| {
+                variant.options.get(&axis.name).cloned().unwrap_or_default()
+            }));
+            if show_price {
+                let price = match self.price_mode {
+                    PriceMode::OnRequest => Some(label("on_request", &self.language).to_string()),
+                    _ => variant.price.map(|price| format_price(price, &self.language)),
+                };
+                cells.push(price.unwrap_or_default());
+            }
+
+            self.layer.set_fill_color(self.color(self.palette.text));
+            for (index, cell) in cells.iter().enumerate() {
+                self.layer.use_text(cell, 8.0, Mm(text_x + 1.5 + index as f32 * column), Mm(y), &self.fonts.regular);
+            }
+        }
+        bottom
+    }
+
+    /// Faixa de títulos da tabela de variantes; devolve o y de baixo da faixa.
+    fn variant_header(&self, header: &[String], x: f32, text_x: f32, top: f32, width: f32, column: f32) -> f32 {
+        let bottom = top - TABLE_ROW - 1.5;
+        self.fill_rect(x, bottom, x + width, bottom + TABLE_ROW, self.palette.primary);
+        self.layer.set_fill_color(self.color((1.0, 1.0, 1.0)));
+        for (index, title) in header.iter().enumerate() {
+            self.layer.use_text(title, 8.0, Mm(text_x + 1.5 + index as f32 * column), Mm(bottom + 1.5), &self.fonts.bold);
+        }
+        bottom
+    }
+
+    fn badge(&self, label: &str, x: f32, baseline: f32) {
+        let width = text_len(label, 8.0, self.fonts.char_width) + 4.0;
+        self.fill_rect(x, baseline - 1.5, x + width, baseline + 3.5, self.palette.accent);
+        self.layer.set_fill_color(self.color((1.0, 1.0, 1.0)));
+        self.layer.use_text(label, 8.0, Mm(x + 2.0), Mm(baseline), &self.fonts.bold);
+    }
+
+    /// Desenha a imagem centralizada na caixa, conforme o filtro de imagens.
+    fn image(&mut self, path: &Path, x: f32, y: f32, box_width: f32, box_height: f32) {
+        match &self.image_filter {
+            ImageFilter::All => self.draw_image(path, x, y, box_width, box_height),
+            ImageFilter::Page(page) if *page == self.page_number => self.draw_image(path, x, y, box_width, box_height),
+            ImageFilter::Page(_) => {}
+            ImageFilter::ItemPage(_) => self.deferred_images.push(DeferredImage {
+                page_number: self.page_number,
+                layer: self.layer.clone(),
+                path: path.to_path_buf(),
+                x,
+                y,
+                box_width,
+                box_height,
+            }),
+        }
+    }
+
+    /// Com `ImageFilter::ItemPage`, desenha as imagens guardadas da página do item.
+    fn draw_deferred_images(&mut self) {
+        let ImageFilter::ItemPage(item_id) = &self.image_filter else {
+            return;
+        };
+        let Some(&page) = self.item_pages.get(item_id) else {
+            return;
+        };
+        let current = self.layer.clone();
+        for image in std::mem::take(&mut self.deferred_images).into_iter().filter(|image| image.page_number == page) {
+            self.layer = image.layer;
+            self.draw_image(&image.path, image.x, image.y, image.box_width, image.box_height);
+        }
+        self.layer = current;
+    }
+
+    /// Desenha a imagem centralizada na caixa; imagens ilegíveis são ignoradas.
+    fn draw_image(&self, path: &Path, x: f32, y: f32, box_width: f32, box_height: f32) {
+        let Ok(image) = image_crate::open(path) else {
+            eprintln!("{}", i18n::message("image_not_found", &[&path.display()]));
+            return;
+        };
+        // printpdf não trata canal alfa; achata para RGB.
+        let mut image = image.to_rgb8();
+        if let Some(screen) = &self.screen {
+            image = screen::downsample(image, box_width, box_height, screen.image_dpi);
+        }
+        let (width_px, height_px) = image.dimensions();
+
+        let native_width = width_px as f32 * 25.4 / IMAGE_DPI;
+        let native_height = height_px as f32 * 25.4 / IMAGE_DPI;
+        let scale = (box_width / native_width).min(box_height / native_height);
+        let width = native_width * scale;
+        let height = native_height * scale;
+
+        let jpeg = self.screen.as_ref().and_then(|screen| screen::jpeg_image(&image, screen.jpeg_quality));
+        let image = if self.cmyk() {
+            Image::from(print::cmyk_image(&image))
+        } else if let Some(jpeg) = jpeg {
+            Image::from(jpeg)
+        } else {
+            Image::from_dynamic_image(&DynamicImage::ImageRgb8(image))
+        };
+        image.add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(x + (box_width - width) / 2.0)),
+                translate_y: Some(Mm(y + (box_height - height) / 2.0)),
+                scale_x: Some(scale),
+                scale_y: Some(scale),
+                dpi: Some(IMAGE_DPI),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Endereço do QR code do item, se o catálogo tiver QR codes.
+    fn qr_url(&self, entry: &CatalogItem) -> Option<String> {
+        self.qr_links.as_ref()?.url(&entry.item.code, &self.language)
+    }
+
+    /// QR code com o canto inferior esquerdo em (x, y), também clicável na tela.
+    fn qr_code(&mut self, url: &str, x: f32, y: f32) {
+        let Some(modules) = qr::modules(url) else {
+            return;
+        };
+        let module = QR_SIZE / modules.width as f32;
+        for (row, runs) in modules.rows.iter().enumerate() {
+            let top = y + QR_SIZE - row as f32 * module;
+            for &(start, end) in runs {
+                self.fill_rect(x + start as f32 * module, top - module, x + end as f32 * module, top, (0.0, 0.0, 0.0));
+            }
+        }
+        self.links.push(PageLink {
+            page: self.page_number,
+            rect: (x, y, x + QR_SIZE, y + QR_SIZE),
+            target: LinkTarget::Uri(url.to_string()),
+        });
+    }
+
+    fn item_barcode(&self, entry: &CatalogItem) -> Option<Barcode> {
+        barcode::encode(&entry.item.code, self.barcode?).ok()
+    }
+
+    /// Barras alinhadas com o texto do item a partir de `top`, com o texto do
+    /// código embaixo; devolve o y final. A zona de silêncio da esquerda é o
+    /// espaço entre a imagem (ou a margem) e o texto; a da direita fica dentro
+    /// de `width`, antes do QR code ao lado.
+    fn barcode(&self, barcode: &Barcode, x: f32, top: f32, width: f32) -> f32 {
+        let bars_top = top - 2.0;
+        let module = barcode_module(barcode, width);
+        let bottom = bars_top - BARCODE_HEIGHT;
+        for (x1, x2) in barcode.bars(x, module) {
+            self.fill_rect(x1, bottom, x2, bars_top, (0.0, 0.0, 0.0));
+        }
+
+        // Códigos longos diminuem o texto para não invadir a zona de silêncio.
+        let bars_width = barcode.modules.len() as f32 * module;
+        let size = barcode_text_size(&barcode.text, width - barcode::QUIET_ZONE as f32 * module, self.fonts.char_width);
+        let text_x = x + (bars_width - text_len(&barcode.text, size, self.fonts.char_width)) / 2.0;
+        self.layer.set_fill_color(self.color(self.palette.text));
+        self.layer.use_text(&barcode.text, size, Mm(text_x.max(x)), Mm(bottom - 3.5), &self.fonts.regular);
+        top - BARCODE_BLOCK
+    }
+
+    fn fill_rect(&self, x1: f32, y1: f32, x2: f32, y2: f32, color: (f32, f32, f32)) {
+        self.layer.set_fill_color(self.color(color));
+        self.layer.add_rect(Rect::new(Mm(x1), Mm(y1), Mm(x2), Mm(y2)));
+    }
+
+    /// Fundo da página inteira, passando da linha de corte até o fim da sangria.
+    fn fill_page(&self, color: (f32, f32, f32)) {
+        let bleed = self.geometry.bleed;
+        self.fill_rect(-bleed, -bleed, self.geometry.width + bleed, self.geometry.height + bleed, color);
+    }
+
+    fn cmyk(&self) -> bool {
+        self.print.as_ref().is_some_and(|print| print.cmyk)
+    }
+
+    fn color(&self, rgb: (f32, f32, f32)) -> Color {
+        if self.cmyk() {
+            print::cmyk_color(rgb)
+        } else {
+            Palette::color(rgb)
+        }
+    }
+
+    /// No perfil de gráfica, confere a resolução da imagem do item no tamanho
+    /// em que ela vai ser impressa (só lê o cabeçalho do arquivo).
+    fn check_resolution(&mut self, entry: &CatalogItem, path: &str, box_width: f32, box_height: f32) {
+        let Some(print) = &self.print else {
+            return;
+        };
+        let Ok((width_px, height_px)) = image_crate::image_dimensions(path) else {
+            return;
+        };
+        let printed_width = box_width.min(box_height * width_px as f32 / height_px.max(1) as f32);
+        let dpi = (width_px as f32 / (printed_width / 25.4)).round() as u32;
+        if dpi < print.min_dpi {
+            self.low_resolution.push(LowResolutionImage {
+                item_id: entry.item.id.clone(),
+                item_code: entry.item.code.clone(),
+                image_path: path.to_string(),
+                page: self.page_number,
+                dpi,
+            });
+        }
+    }
+}
+
+/// Fontes de títulos e do corpo: das opções do catálogo ou, sem elas, das configurações.
+fn load_fonts(doc: &PdfDocumentReference, settings: &Settings, options: &CatalogOptions) -> Result<Fonts, String> {
+    let body_name = options.body_font.as_deref().or(settings.body_font.as_deref());
+    let heading_name = options.heading_font.as_deref().or(settings.heading_font.as_deref());
+
+    let body = fonts::load_family(body_name, options.is_pdf_a())?;
+    let regular = add_font(doc, &body.regular)?;
+    let bold = add_font(doc, &body.bold)?;
+    let heading = if heading_name.map(str::trim).unwrap_or_default().eq_ignore_ascii_case(body_name.map(str::trim).unwrap_or_default()) {
+        bold.clone()
+    } else {
+        add_font(doc, &fonts::load_family(heading_name, options.is_pdf_a())?.bold)?
+    };
+
+    Ok(Fonts { regular, bold, heading, char_width: body.char_width })
+}
+
+// Com a feature `font_subsetting` do printpdf, fontes TTF/OTF são embutidas
+// só com os glifos usados no catálogo.
+pub fn add_font(doc: &PdfDocumentReference, data: &FontData) -> Result<IndirectFontRef, String> {
+    match data {
+        FontData::Builtin(font) => doc.add_builtin_font(*font),
+        FontData::Embedded(bytes) => doc.add_external_font(bytes.as_slice()),
+    }
+    .map_err(|e| i18n::message("load_font_failed", &[&e]))
+}
+
+fn heading_size(depth: usize) -> f32 {
+    (22.0 - depth as f32 * 4.0).max(12.0)
+}
+
+// A primeira página do sumário perde espaço para o título.
+fn toc_entries_per_page(page_height: f32) -> usize {
+    (((page_height - 2.0 * MARGIN - 12.0) / TOC_LINE) as usize).saturating_sub(1).max(1)
+}
+
+fn toc_page_count(entries: usize, page_height: f32) -> usize {
+    entries.div_ceil(toc_entries_per_page(page_height)).max(1)
+}
+
+/// Largura da coluna de nomes da tabela de especificações.
+fn spec_label_width(entry: &CatalogItem, char_width: f32) -> f32 {
+    entry.infos.iter().map(|info| text_len(&info.name, 9.0, char_width)).fold(0.0, f32::max) + 4.0
+}
+
+fn item_text_height(entry: &CatalogItem, width: f32, char_width: f32) -> f32 {
+    let description = wrap(&entry.item.description, width, 10.0, char_width);
+    8.0 + description.len() as f32 * 4.5 + entry.infos.len() as f32 * 4.2 + 6.0
+}
+
+fn variant_row_height(entry: &CatalogItem) -> f32 {
+    let has_images = entry.variants.iter().any(|variant| variant.image_path.as_deref().is_some_and(|path| !path.is_empty()));
+    if has_images { VARIANT_IMAGE_ROW } else { TABLE_ROW }
+}
+
+fn variant_table_height(entry: &CatalogItem) -> f32 {
+    if entry.variants.is_empty() {
+        0.0
+    } else {
+        TABLE_ROW + entry.variants.len() as f32 * variant_row_height(entry) + 4.0
+    }
+}
+
+// Cabeçalho e a primeira linha: o mínimo que fica junto do item.
+fn variant_table_min_height(entry: &CatalogItem) -> f32 {
+    if entry.variants.is_empty() {
+        0.0
+    } else {
+        TABLE_ROW + variant_row_height(entry) + 4.0
+    }
+}
+
+/// Largura do texto (e do código de barras) de um item da grade de `width` mm.
+/// Com QR code, para antes da zona de silêncio dele, e a zona de silêncio da
+/// direita das barras fica dentro dessa largura.
+fn grid_text_width(width: f32, qr_code: bool) -> f32 {
+    if qr_code { width - QR_SIZE - QR_GAP } else { width }
+}
+
+/// Largura de um módulo para as barras e as duas zonas de silêncio caberem em `width` mm.
+fn barcode_module(barcode: &Barcode, width: f32) -> f32 {
+    let total = barcode.modules.len() + 2 * barcode::QUIET_ZONE;
+    (width.min(BARCODE_MAX_WIDTH) / total as f32).min(BARCODE_MODULE)
+}
+
+/// Tamanho do texto embaixo das barras: 8 pt, ou menos se não couber em `width` mm.
+fn barcode_text_size(text: &str, width: f32, char_width: f32) -> f32 {
+    let natural = text_len(text, 8.0, char_width);
+    if natural > width { 8.0 * width / natural } else { 8.0 }
+}
+
+/// Largura aproximada do texto em mm.
+pub fn text_len(text: &str, font_size: f32, char_width: f32) -> f32 {
+    text.chars().count() as f32 * font_size * char_width * PT_TO_MM
+}
+
+/// Quebra o texto em linhas que caibam em `width` mm.
+pub fn wrap(text: &str, width: f32, font_size: f32, char_width: f32) -> Vec<String> {
+    let max_chars = (width / (font_size * char_width * PT_TO_MM)).floor().max(1.0) as usize;
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::build_catalog;
+    use crate::catalog::options::{PageSize, QrTarget, MIN_CATALOG_HEIGHT, MIN_CATALOG_WIDTH};
+    use crate::db::connection::open_in_memory;
+    use crate::db::{item_repository, section_repository, variant_repository};
+    use crate::models::section::SectionDetails;
+    use crate::models::variant::VariantInput;
+
+    // Catálogo com um item e `variants` variantes, na seção com o layout dado.
+    fn render_with_variants(variants: usize, layout: &str, image_path: Option<&str>) -> lopdf::Document {
+        let conn = open_in_memory();
+        let details = SectionDetails { layout: Some(layout.into()), ..Default::default() };
+        section_repository::insert_section(&conn, "Anéis", None, &details).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        let id = item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap();
+        for number in 0..variants {
+            let variant = VariantInput {
+                code: format!("AN-1-{:03}", number),
+                price: Some(100.0 + number as f64),
+                image_path: image_path.map(str::to_string),
+                ..Default::default()
+            };
+            variant_repository::insert_variant(&conn, &id, &variant).unwrap();
+        }
+
+        let options = CatalogOptions::default();
+        let catalog = build_catalog(&conn, &options).unwrap();
+        let bytes = render_catalog(&catalog, &Settings::default(), &options).unwrap();
+        lopdf::Document::load_mem(&bytes).unwrap()
+    }
+
+    fn image_count(pdf: &lopdf::Document) -> usize {
+        pdf.objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| stream.dict.get(b"Subtype").and_then(|subtype| subtype.as_name()).ok() == Some(b"Image".as_slice()))
+            .count()
+    }
+
+    #[test]
+    fn long_variant_tables_continue_on_the_next_pages() {
+        for layout in ["list", "grid"] {
+            let short = render_with_variants(3, layout, None).get_pages().len();
+            let long = render_with_variants(150, layout, None).get_pages().len();
+            // 150 linhas de 4,5 mm não cabem numa página A4.
+            assert!(long >= short + 2, "{}: {} -> {}", layout, short, long);
+        }
+    }
+
+    #[test]
+    fn screen_target_is_reached_in_one_more_pass_without_restarting_progress() {
+        struct Items(std::cell::RefCell<Vec<usize>>);
+        impl Monitor for Items {
+            fn report(&self, progress: &Progress) {
+                self.0.borrow_mut().push(progress.item);
+            }
+
+            fn is_cancelled(&self) -> bool {
+                false
+            }
+        }
+
+        let path = std::env::temp_dir().join("render-screen-target.png");
+        image_crate::RgbImage::from_fn(400, 400, |x, y| image_crate::Rgb([(x % 251) as u8, (y % 241) as u8, ((x * y) % 239) as u8]))
+            .save(&path)
+            .unwrap();
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        for number in 0..4 {
+            let code = format!("AN-{:02}", number);
+            item_repository::insert_item(&conn, code, "Anel".into(), section_id.clone(), path.display().to_string()).unwrap();
+        }
+        let screen = ScreenOptions { target_size_kb: Some(1), ..Default::default() };
+        let options = CatalogOptions { profile: OutputProfile::Screen(screen), ..Default::default() };
+        let catalog = build_catalog(&conn, &options).unwrap();
+
+        let items = Items(Default::default());
+        let rendered = render_pages(&catalog, &Settings::default(), &options, &ImageFilter::All, &items).unwrap();
+        let report = rendered.screen.unwrap();
+        assert!(report.jpeg_quality < ScreenOptions::default().jpeg_quality);
+        assert!(!report.within_target);
+        assert_eq!(report.size_bytes, rendered.bytes.len() as u64);
+        assert!(items.0.borrow().windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn toc_always_fits_at_least_one_entry() {
+        assert_eq!(toc_entries_per_page(297.0), 35);
+        assert_eq!(toc_entries_per_page(50.0), 1);
+        assert_eq!(toc_entries_per_page(0.0), 1);
+        assert_eq!(toc_page_count(0, 50.0), 1);
+    }
+
+    #[test]
+    fn tiny_custom_pages_are_raised_to_a_usable_size() {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel dourado".into(), section_id, String::new()).unwrap();
+        let options = CatalogOptions {
+            page_size: PageSize::Custom { width: 60.0, height: 60.0 },
+            table_of_contents: true,
+            qr_code: Some(QrTarget::Website { url: Some("https://example.com/{code}".into()) }),
+            ..Default::default()
+        };
+        let geometry = PageGeometry::new(&options);
+        assert_eq!((geometry.width, geometry.height), (MIN_CATALOG_WIDTH, MIN_CATALOG_HEIGHT));
+        assert!(geometry.width - MARGIN - (MARGIN + IMAGE_BOX + 6.0) - (QR_SIZE + QR_GAP) > 0.0);
+
+        let catalog = build_catalog(&conn, &options).unwrap();
+        let bytes = render_catalog(&catalog, &Settings::default(), &options).unwrap();
+        assert!(lopdf::Document::load_mem(&bytes).is_ok());
+    }
+
+    #[test]
+    fn barcode_quiet_zone_stays_clear_of_the_grid_qr_code() {
+        let geometry = PageGeometry::new(&CatalogOptions::default());
+        let width = (geometry.width - 2.0 * MARGIN - GRID_GAP) / 2.0;
+        let text_width = grid_text_width(width, true);
+        assert_eq!(text_width + QR_GAP + QR_SIZE, width);
+
+        for code in ["AN-1", "AN-0001-OURO-18K-ARO-22-COM-PEDRA"] {
+            let barcode = barcode::encode(code, BarcodeFormat::Code128).unwrap();
+            let module = barcode_module(&barcode, text_width);
+            let bars_end = barcode.modules.len() as f32 * module;
+            assert!(bars_end + barcode::QUIET_ZONE as f32 * module <= text_width, "{}", code);
+
+            let text_room = text_width - barcode::QUIET_ZONE as f32 * module;
+            let size = barcode_text_size(&barcode.text, text_room, 0.5);
+            assert!(text_len(&barcode.text, size, 0.5) <= text_room + 0.001, "{}", code);
+        }
+    }
+
+    #[test]
+    fn on_request_applies_to_items_without_prices() {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap();
+        let catalog = build_catalog(&conn, &CatalogOptions::default()).unwrap();
+        let entry = &catalog.sections[0].items[0];
+
+        let on_request = CatalogOptions { price_mode: PriceMode::OnRequest, ..Default::default() };
+        let renderer = Renderer::new(&Settings::default(), &on_request).unwrap();
+        assert_eq!(renderer.price_line(entry).as_deref(), Some("Sob consulta"));
+        assert!(renderer.item_text_height(entry, 100.0) > item_text_height(entry, 100.0, renderer.fonts.char_width));
+
+        let show = Renderer::new(&Settings::default(), &CatalogOptions::default()).unwrap();
+        assert_eq!(show.price_line(entry), None);
+    }
+
+    #[test]
+    fn item_preview_draws_only_the_images_of_the_item_page() {
+        let path = std::env::temp_dir().join("render-item-page.png");
+        image_crate::RgbImage::from_pixel(8, 8, image_crate::Rgb([40, 80, 200])).save(&path).unwrap();
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        let mut last = String::new();
+        for number in 0..12 {
+            let code = format!("AN-{:02}", number);
+            last = item_repository::insert_item(&conn, code, "Anel".into(), section_id.clone(), path.display().to_string()).unwrap();
+        }
+        let options = CatalogOptions::default();
+        let catalog = build_catalog(&conn, &options).unwrap();
+        let render = |images: &ImageFilter| {
+            let rendered = render_pages(&catalog, &Settings::default(), &options, images, &Silent).unwrap();
+            let count = image_count(&lopdf::Document::load_mem(&rendered.bytes).unwrap());
+            (rendered.item_pages[&last], count)
+        };
+
+        let (page, all) = render(&ImageFilter::All);
+        assert_eq!(all, 12);
+        let (_, on_page) = render(&ImageFilter::Page(page));
+        assert!(on_page > 0 && on_page < all);
+        assert_eq!(render(&ImageFilter::ItemPage(last.clone())), (page, on_page));
+    }
+
+    #[test]
+    fn variant_images_are_drawn() {
+        let path = std::env::temp_dir().join("render-variant-thumbnail.png");
+        image_crate::RgbImage::from_pixel(8, 8, image_crate::Rgb([200, 160, 40])).save(&path).unwrap();
+
+        let pdf = render_with_variants(2, "list", path.to_str());
+        assert_eq!(image_count(&pdf), 2);
+    }
+}
@@ -0,0 +1,178 @@
+use printpdf::image_crate::codecs::jpeg::JpegEncoder;
+use printpdf::image_crate::imageops::{self, FilterType};
+use printpdf::image_crate::{ColorType, RgbImage};
+use printpdf::lopdf::Document;
+use printpdf::{ColorBits, ColorSpace, ImageFilter, ImageXObject, Px};
+use serde::Serialize;
+
+use crate::catalog::linearize::linearize;
+use crate::catalog::options::ScreenOptions;
+
+const MIN_JPEG_QUALITY: u8 = 40;
+const MIN_IMAGE_DPI: u32 = 60;
+
+/// Tamanho final do PDF no perfil de tela e como as imagens saíram.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScreenReport {
+    pub size_bytes: u64,
+    pub target_bytes: Option<u64>,
+    /// Falso quando nem no mínimo de qualidade o PDF coube no tamanho pedido.
+    pub within_target: bool,
+    pub image_dpi: u32,
+    pub jpeg_quality: u8,
+}
+
+impl ScreenReport {
+    pub fn new(size: usize, screen: &ScreenOptions) -> Self {
+        let target_bytes = screen.target_size_kb.map(|kb| kb as u64 * 1024);
+        Self {
+            size_bytes: size as u64,
+            target_bytes,
+            within_target: target_bytes.is_none_or(|target| size as u64 <= target),
+            image_dpi: screen.image_dpi,
+            jpeg_quality: screen.jpeg_quality,
+        }
+    }
+}
+
+/// Reduz a imagem para `dpi` no tamanho da caixa (em mm); nunca aumenta.
+pub fn downsample(image: RgbImage, box_width: f32, box_height: f32, dpi: u32) -> RgbImage {
+    let max_width = (box_width / 25.4 * dpi as f32).ceil();
+    let max_height = (box_height / 25.4 * dpi as f32).ceil();
+    let scale = (max_width / image.width() as f32).min(max_height / image.height() as f32);
+    if scale >= 1.0 {
+        return image;
+    }
+    let width = ((image.width() as f32 * scale).round() as u32).max(1);
+    let height = ((image.height() as f32 * scale).round() as u32).max(1);
+    imageops::resize(&image, width, height, FilterType::Triangle)
+}
+
+/// Imagem em JPEG, que o PDF guarda como está (filtro DCT).
+pub fn jpeg_image(image: &RgbImage, quality: u8) -> Option<ImageXObject> {
+    let mut data = Vec::new();
+    JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100))
+        .encode(image.as_raw(), image.width(), image.height(), ColorType::Rgb8)
+        .ok()?;
+
+    Some(ImageXObject {
+        width: Px(image.width() as usize),
+        height: Px(image.height() as usize),
+        color_space: ColorSpace::Rgb,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: true,
+        image_data: data,
+        image_filter: Some(ImageFilter::DCT),
+        smask: None,
+        clipping_bbox: None,
+    })
+}
+
+/// Próxima tentativa quando o PDF passa do tamanho pedido: primeiro baixa a
+/// qualidade do JPEG, depois a resolução. `None` quando os dois já estão no mínimo.
+pub fn reduce(screen: &ScreenOptions) -> Option<ScreenOptions> {
+    if screen.jpeg_quality > MIN_JPEG_QUALITY {
+        let jpeg_quality = screen.jpeg_quality.saturating_sub(15).max(MIN_JPEG_QUALITY);
+        Some(ScreenOptions { jpeg_quality, ..screen.clone() })
+    } else if screen.image_dpi > MIN_IMAGE_DPI {
+        let image_dpi = (screen.image_dpi * 3 / 4).max(MIN_IMAGE_DPI);
+        Some(ScreenOptions { image_dpi, ..screen.clone() })
+    } else {
+        None
+    }
+}
+
+/// Bytes das imagens do documento. O resto do PDF não muda com a qualidade
+/// das imagens, então o tamanho de outra tentativa pode ser estimado sem
+/// desenhar o catálogo de novo.
+pub fn image_bytes(doc: &Document) -> usize {
+    doc.objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .filter(|stream| stream.dict.get(b"Subtype").and_then(|subtype| subtype.as_name()).ok() == Some(b"Image".as_slice()))
+        .map(|stream| stream.content.len())
+        .sum()
+}
+
+// Tamanho relativo de um JPEG por qualidade (libjpeg, fotos de produto).
+const JPEG_WEIGHTS: [(u8, f64); 8] = [(1, 0.1), (40, 0.52), (50, 0.6), (60, 0.68), (75, 0.88), (85, 1.15), (95, 2.0), (100, 3.2)];
+
+fn jpeg_weight(quality: u8) -> f64 {
+    let quality = quality.clamp(1, 100);
+    let upper = JPEG_WEIGHTS.iter().position(|(q, _)| *q >= quality).unwrap_or(JPEG_WEIGHTS.len() - 1);
+    if upper == 0 {
+        return JPEG_WEIGHTS[0].1;
+    }
+    let ((q0, w0), (q1, w1)) = (JPEG_WEIGHTS[upper - 1], JPEG_WEIGHTS[upper]);
+    w0 + (w1 - w0) * (quality - q0) as f64 / (q1 - q0) as f64
+}
+
+/// Tamanho estimado do PDF refeito com `to`, a partir do feito com `from`:
+/// as imagens crescem com a área em pixels e com o peso da qualidade do JPEG.
+pub fn estimate(size: usize, image_bytes: usize, from: &ScreenOptions, to: &ScreenOptions) -> usize {
+    let pixels = (to.image_dpi as f64 / from.image_dpi.max(1) as f64).powi(2).min(1.0);
+    let quality = jpeg_weight(to.jpeg_quality) / jpeg_weight(from.jpeg_quality);
+    size - image_bytes.min(size) + (image_bytes as f64 * pixels * quality).round() as usize
+}
+
+/// Primeira redução que, pela estimativa, cabe no tamanho pedido; sem
+/// nenhuma, a menor de todas. Sem alvo ou já dentro dele, `screen` fica.
+pub fn fit(size: usize, image_bytes: usize, screen: &ScreenOptions) -> ScreenOptions {
+    let Some(target) = screen.target_size_kb.map(|kb| kb as usize * 1024) else {
+        return screen.clone();
+    };
+    let mut current = screen.clone();
+    while estimate(size, image_bytes, screen, &current) > target {
+        match reduce(&current) {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+    current
+}
+
+/// Tira os metadados e os objetos sem uso, compacta os fluxos e lineariza.
+pub fn optimize(mut doc: Document) -> Result<Vec<u8>, String> {
+    doc.trailer.remove(b"Info");
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.remove(b"Metadata");
+    }
+    doc.prune_objects();
+    doc.delete_zero_length_streams();
+    doc.compress();
+    linearize(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(image_dpi: u32, jpeg_quality: u8, target_size_kb: Option<u32>) -> ScreenOptions {
+        ScreenOptions { image_dpi, jpeg_quality, target_size_kb }
+    }
+
+    #[test]
+    fn estimate_only_scales_the_image_bytes() {
+        let from = screen(110, 75, None);
+        assert_eq!(estimate(1000, 600, &from, &from), 1000);
+        // Metade da resolução: um quarto dos pixels.
+        assert_eq!(estimate(1000, 600, &from, &screen(55, 75, None)), 400 + 150);
+        assert!(estimate(1000, 600, &from, &screen(110, 40, None)) < 1000);
+        assert_eq!(estimate(1000, 0, &from, &screen(60, 40, None)), 1000);
+    }
+
+    #[test]
+    fn fit_picks_the_first_reduction_estimated_to_fit() {
+        let options = screen(110, 75, Some(1000));
+        // 1.200 KB, 1.000 KB de imagens: a qualidade 60 já basta.
+        let fitted = fit(1200 * 1024, 1000 * 1024, &options);
+        assert_eq!((fitted.image_dpi, fitted.jpeg_quality), (110, 60));
+
+        // Só texto: nenhuma redução ajuda, fica no mínimo.
+        let fitted = fit(1200 * 1024, 0, &options);
+        assert_eq!((fitted.image_dpi, fitted.jpeg_quality), (MIN_IMAGE_DPI, MIN_JPEG_QUALITY));
+
+        assert_eq!(fit(500 * 1024, 400 * 1024, &options), options);
+        assert_eq!(fit(5000 * 1024, 4000 * 1024, &screen(110, 75, None)), screen(110, 75, None));
+    }
+}
@@ -0,0 +1,266 @@
+use printpdf::{lopdf, IndirectFontRef, Line, Mm, OffsetDateTime, PdfDocument, PdfLayerReference, Point, Rect};
+
+use crate::catalog::barcode::{self, Barcode};
+use crate::catalog::builder::{Catalog, CatalogItem};
+use crate::catalog::fonts;
+use crate::catalog::format::format_price;
+use crate::catalog::labels::label;
+use crate::catalog::metadata::{self, DocumentMetadata};
+use crate::catalog::options::{LabelOptions, LabelSheet, PriceMode};
+use crate::catalog::palette::Palette;
+use crate::catalog::qr::{self, QrLinks};
+use crate::i18n;
+use crate::catalog::render::{add_font, text_len, wrap, PT_TO_MM};
+use crate::settings::settings_repository::Settings;
+
+const PADDING: f32 = 2.0;
+const CODE_SIZE: f32 = 9.0;
+const PRICE_SIZE: f32 = 10.0;
+const DESCRIPTION_SIZE: f32 = 7.0;
+const DESCRIPTION_LINE: f32 = DESCRIPTION_SIZE * PT_TO_MM + 0.8;
+const BARCODE_TEXT_SIZE: f32 = 6.0;
+const BARCODE_TEXT: f32 = BARCODE_TEXT_SIZE * PT_TO_MM + 0.6; // altura do texto embaixo das barras
+const BARCODE_MODULE: f32 = 0.33; // largura máxima de um módulo, em mm
+const QR_MAX_SHARE: f32 = 0.4; // fração da largura da etiqueta que o QR code pode ocupar
+
+/// O que vai impresso numa etiqueta.
+pub struct Label {
+    pub code: String,
+    pub description: String,
+    pub price: Option<String>,
+    pub qr_url: Option<String>,
+    pub barcode: Option<Barcode>,
+}
+
+/// Uma etiqueta por cópia de cada item, na ordem do catálogo. Itens cujo
+/// código não serve para o formato de código de barras saem sem as barras.
+pub fn labels(catalog: &Catalog, settings: &Settings, options: &LabelOptions) -> Vec<Label> {
+    let language = options.language();
+    let qr_links = options.qr_code.as_ref().map(|target| QrLinks::new(target, settings));
+    let mut labels = Vec::new();
+
+    for entry in catalog.sections.iter().flat_map(|section| &section.items) {
+        for _ in 0..options.copies.max(1) {
+            labels.push(Label {
+                code: entry.item.code.clone(),
+                description: entry.item.description.clone(),
+                price: price(entry, options.price_mode, language),
+                qr_url: qr_links.as_ref().and_then(|links| links.url(&entry.item.code, language)),
+                barcode: options.barcode.and_then(|format| barcode::encode(&entry.item.code, format).ok()),
+            });
+        }
+    }
+    labels
+}
+
+/// Menor preço das variantes; com preços diferentes, "A partir de R$ ...".
+/// "Sob consulta" não depende de o item ter preço cadastrado.
+fn price(entry: &CatalogItem, mode: PriceMode, language: &str) -> Option<String> {
+    match mode {
+        PriceMode::Hide => return None,
+        PriceMode::OnRequest => return Some(label("on_request", language).to_string()),
+        PriceMode::Show => {}
+    }
+    let prices: Vec<f64> = entry.variants.iter().filter_map(|variant| variant.price).collect();
+    let lowest = prices.iter().copied().reduce(f64::min)?;
+    if prices.iter().any(|price| *price != lowest) {
+        Some(format!("{} {}", label("price_from", language), format_price(lowest, language)))
+    } else {
+        Some(format_price(lowest, language))
+    }
+}
+
+/// Gera o PDF das etiquetas, preenchendo a folha por linhas a partir da
+/// posição `options.skip`.
+pub fn render_labels(labels: &[Label], sheet: &LabelSheet, settings: &Settings, options: &LabelOptions) -> Result<Vec<u8>, String> {
+    sheet.validate()?;
+    let per_page = sheet.labels_per_page();
+    if options.skip as usize >= per_page {
+        return Err(i18n::message("label_skip_too_large", &[&per_page]));
+    }
+    let (page_width, page_height) = sheet.page_size.dimensions();
+    let (doc, page, layer) = PdfDocument::new(&settings.name, Mm(page_width), Mm(page_height), "Layer 1");
+    let doc = doc.with_conformance(metadata::conformance());
+
+    let body = fonts::load_family(options.body_font.as_deref().or(settings.body_font.as_deref()), false)?;
+    let fonts = LabelFonts {
+        regular: add_font(&doc, &body.regular)?,
+        bold: add_font(&doc, &body.bold)?,
+        char_width: body.char_width,
+    };
+
+    let skip = options.skip as usize;
+    let mut layer = doc.get_page(page).get_layer(layer);
+    for (index, content) in labels.iter().enumerate() {
+        let position = index + skip;
+        if position > 0 && position.is_multiple_of(per_page) {
+            let (page, new_layer) = doc.add_page(Mm(page_width), Mm(page_height), "Layer 1");
+            layer = doc.get_page(page).get_layer(new_layer);
+        }
+
+        let slot = position % per_page;
+        let column = (slot % sheet.columns as usize) as f32;
+        let row = (slot / sheet.columns as usize) as f32;
+        let x = sheet.margin_left + column * (sheet.label_width + sheet.gap_x);
+        let top = page_height - sheet.margin_top - row * (sheet.label_height + sheet.gap_y);
+        if options.outline {
+            outline(&layer, x, top, sheet.label_width, sheet.label_height);
+        }
+        draw_label(&layer, &fonts, content, x, top, sheet.label_width, sheet.label_height);
+    }
+
+    let bytes = doc.save_to_bytes().map_err(|e| e.to_string())?;
+    let mut pdf = lopdf::Document::load_mem(&bytes).map_err(|e| e.to_string())?;
+    let metadata = DocumentMetadata {
+        title: format!("{} - {}", settings.name, label("labels", options.language())),
+        author: settings.name.clone(),
+        subject: label("labels", options.language()).to_string(),
+        keywords: Vec::new(),
+        created: OffsetDateTime::now_utc(),
+    };
+    metadata::apply(&mut pdf, &metadata, false)?;
+    metadata::save(&mut pdf, false)
+}
+
+struct LabelFonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    char_width: f32,
+}
+
+/// Código e preço em cima, descrição no meio (até onde couber) e código de
+/// barras embaixo; o QR code fica à direita, na altura toda da etiqueta.
+fn draw_label(layer: &PdfLayerReference, fonts: &LabelFonts, content: &Label, x: f32, top: f32, width: f32, height: f32) {
+    let left = x + PADDING;
+    let bottom = top - height + PADDING;
+    let inner_height = height - 2.0 * PADDING;
+    let mut text_width = width - 2.0 * PADDING;
+
+    if let Some(modules) = content.qr_url.as_deref().and_then(qr::modules) {
+        let size = inner_height.min(width * QR_MAX_SHARE);
+        let module = size / modules.width as f32;
+        let qr_x = x + width - PADDING - size;
+        for (row, runs) in modules.rows.iter().enumerate() {
+            let row_top = top - PADDING - row as f32 * module;
+            for &(start, end) in runs {
+                fill_rect(layer, qr_x + start as f32 * module, row_top - module, qr_x + end as f32 * module, row_top);
+            }
+        }
+        text_width -= size + PADDING;
+    }
+
+    layer.set_fill_color(Palette::color((0.0, 0.0, 0.0)));
+    let mut y = top - PADDING - CODE_SIZE * PT_TO_MM;
+    if let Some(code) = wrap(&content.code, text_width, CODE_SIZE, fonts.char_width).first() {
+        layer.use_text(code, CODE_SIZE, Mm(left), Mm(y), &fonts.bold);
+    }
+    if let Some(price) = &content.price {
+        y -= PRICE_SIZE * PT_TO_MM + 1.0;
+        layer.use_text(price, PRICE_SIZE, Mm(left), Mm(y), &fonts.bold);
+    }
+
+    // As barras ficam com até 30% da etiqueta; a descrição usa o que sobrar.
+    let bars_height = (inner_height * 0.3).clamp(4.0, 10.0);
+    let barcode = content.barcode.as_ref().filter(|_| y - 1.0 >= bottom + bars_height + BARCODE_TEXT);
+    let text_bottom = bottom + barcode.map_or(0.0, |_| bars_height + BARCODE_TEXT + 1.0);
+    let line_count = ((y - 1.0 - text_bottom) / DESCRIPTION_LINE).max(0.0) as usize;
+    for line in wrap(&content.description, text_width, DESCRIPTION_SIZE, fonts.char_width).iter().take(line_count) {
+        y -= DESCRIPTION_LINE;
+        layer.use_text(line, DESCRIPTION_SIZE, Mm(left), Mm(y), &fonts.regular);
+    }
+
+    if let Some(barcode) = barcode {
+        let module = (text_width / (barcode.modules.len() + 2 * barcode::QUIET_ZONE) as f32).min(BARCODE_MODULE);
+        let bars_width = barcode.modules.len() as f32 * module;
+        let bars_x = left + (text_width - bars_width) / 2.0;
+        let bars_bottom = bottom + BARCODE_TEXT;
+        for (x1, x2) in barcode.bars(bars_x, module) {
+            fill_rect(layer, x1, bars_bottom, x2, bars_bottom + bars_height);
+        }
+        let text_x = bars_x + (bars_width - text_len(&barcode.text, BARCODE_TEXT_SIZE, fonts.char_width)) / 2.0;
+        layer.use_text(&barcode.text, BARCODE_TEXT_SIZE, Mm(text_x.max(left)), Mm(bottom), &fonts.regular);
+    }
+}
+
+fn fill_rect(layer: &PdfLayerReference, x1: f32, y1: f32, x2: f32, y2: f32) {
+    layer.set_fill_color(Palette::color((0.0, 0.0, 0.0)));
+    layer.add_rect(Rect::new(Mm(x1), Mm(y1), Mm(x2), Mm(y2)));
+}
+
+fn outline(layer: &PdfLayerReference, x: f32, top: f32, width: f32, height: f32) {
+    layer.set_outline_color(Palette::color((0.75, 0.75, 0.75)));
+    layer.set_outline_thickness(0.2);
+    let corners = [(x, top), (x + width, top), (x + width, top - height), (x, top - height)];
+    layer.add_line(Line {
+        points: corners.iter().map(|&(x, y)| (Point::new(Mm(x), Mm(y)), false)).collect(),
+        is_closed: true,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::build_catalog;
+    use crate::catalog::options::{CatalogOptions, LabelModel};
+    use crate::db::connection::open_in_memory;
+    use crate::db::{item_repository, section_repository, variant_repository};
+    use crate::models::variant::VariantInput;
+
+    fn sample(count: usize) -> Vec<Label> {
+        (0..count)
+            .map(|number| Label {
+                code: format!("AN-{}", number),
+                description: "Anel de ouro".into(),
+                price: Some("R$ 100,00".into()),
+                qr_url: None,
+                barcode: None,
+            })
+            .collect()
+    }
+
+    fn render(count: usize, skip: u32) -> Result<lopdf::Document, String> {
+        let options = LabelOptions { skip, ..Default::default() };
+        let bytes = render_labels(&sample(count), &LabelModel::A4x24.sheet(), &Settings::default(), &options)?;
+        Ok(lopdf::Document::load_mem(&bytes).unwrap())
+    }
+
+    #[test]
+    fn prices_follow_the_mode_even_without_variant_prices() {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id.clone(), String::new()).unwrap();
+        let id = item_repository::insert_item(&conn, "AN-2".into(), "Anel".into(), section_id, String::new()).unwrap();
+        for price in [120.0, 95.5] {
+            let variant = VariantInput { code: format!("AN-2-{}", price), price: Some(price), ..Default::default() };
+            variant_repository::insert_variant(&conn, &id, &variant).unwrap();
+        }
+        let catalog = build_catalog(&conn, &CatalogOptions::default()).unwrap();
+        let items = &catalog.sections[0].items;
+        let (unpriced, priced) = if items[0].item.code == "AN-1" { (&items[0], &items[1]) } else { (&items[1], &items[0]) };
+
+        assert_eq!(price(unpriced, PriceMode::OnRequest, "pt").as_deref(), Some(label("on_request", "pt")));
+        assert_eq!(price(unpriced, PriceMode::Show, "pt"), None);
+        assert_eq!(price(priced, PriceMode::Hide, "pt"), None);
+        let from = format!("{} R$ 95,50", label("price_from", "pt"));
+        assert_eq!(price(priced, PriceMode::Show, "pt"), Some(from));
+    }
+
+    #[test]
+    fn skip_must_leave_room_on_the_first_sheet() {
+        let per_page = LabelModel::A4x24.sheet().labels_per_page();
+        assert!(render(1, per_page as u32).is_err());
+        // Pulando todas menos uma, a segunda etiqueta já vai para a próxima folha.
+        assert_eq!(render(2, per_page as u32 - 1).unwrap().get_pages().len(), 2);
+        assert_eq!(render(per_page, 0).unwrap().get_pages().len(), 1);
+    }
+
+    #[test]
+    fn label_pdfs_have_no_xmp_metadata() {
+        let doc = render(3, 0).unwrap();
+        let catalog = doc.catalog().unwrap();
+        assert!(catalog.get(b"Metadata").is_err() && catalog.get(b"OutputIntents").is_err());
+        let info = doc.trailer.get(b"Info").and_then(lopdf::Object::as_reference).unwrap();
+        assert!(doc.get_dictionary(info).unwrap().get(b"Title").is_ok());
+    }
+}
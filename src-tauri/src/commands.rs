@@ -1,19 +1,44 @@
-use tauri::State;
+use tauri::ipc::Response;
+use tauri::{Emitter, Manager, State, Window};
+use std::collections::HashMap;
 use std::fs;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use rusqlite::Connection;
 
+use crate::catalog::{self, CatalogOptions};
+use crate::catalog::fonts::FontFamily;
+use crate::catalog::options::{BarcodeFormat, LabelModel, LabelOptions, LabelSheet};
+use crate::catalog::job::{GeneratedFile, JobError, JobFinished, Monitor, Progress, Silent, Throttled};
+use crate::catalog::preview::{PreviewCache, PreviewTarget};
+use crate::catalog::print::LowResolutionImage;
+use crate::catalog::render::ImageFilter;
+use crate::{i18n, images};
 use crate::models::{section::Section, item::Item, info::Info};
-use crate::db::{section_repository, item_repository, info_repository};
+use crate::models::section::{SectionDetails, SectionNode};
+use crate::models::bulk::{BulkSummary, CodeSuffixStrategy};
+use crate::models::attribute::{AttributeDefinition, AttributeInput, AttributeSuggestion};
+use crate::models::label_preset::LabelPreset;
+use crate::models::preset::CatalogPreset;
+use crate::models::tag::Tag;
+use crate::models::translation::Translation;
+use crate::models::template::{TemplateField, TemplateFieldInput};
+use crate::models::variant::{Variant, VariantAxis, VariantAxisInput, VariantInput};
+use crate::db::{section_repository, item_repository, info_repository, attribute_repository, bulk_repository, duplicate_repository, label_preset_repository, preset_repository, tag_repository, translation_repository, template_repository, variant_repository};
 use crate::settings::settings_repository::Settings;
 use crate::settings::{settings_repository};
 
 pub struct DbConn(pub Mutex<Connection>);
+pub struct PreviewState(pub Mutex<PreviewCache>);
+/// Gerações em segundo plano em andamento, com o sinal de cancelamento de cada uma.
+pub struct CatalogJobs(pub Mutex<HashMap<String, Arc<AtomicBool>>>);
 
 #[tauri::command]
-pub fn create_section(name: String, db: State<DbConn>) -> Result<(), String> {
+pub fn create_section(name: String, parent_id: Option<String>, details: Option<SectionDetails>, db: State<DbConn>) -> Result<(), String> {
     let conn = db.0.lock().unwrap();
-    section_repository::insert_section(&conn, &name).map_err(|e| e.to_string())
+    section_repository::insert_section(&conn, &name, parent_id.as_deref(), &details.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -29,9 +54,27 @@ pub fn list_sections(db: State<DbConn>) -> Result<Vec<Section>, String> {
 }
 
 #[tauri::command]
-pub fn update_section(uuid: String, name: String, db: State<DbConn>) -> Result<(), String> {
+pub fn list_section_tree(db: State<DbConn>) -> Result<Vec<SectionNode>, String> {
     let conn = db.0.lock().unwrap();
-    section_repository::update_section(&conn, &uuid, &name).map_err(|e| e.to_string())
+    section_repository::list_section_tree(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_subsections(parent_id: Option<String>, db: State<DbConn>) -> Result<Vec<Section>, String> {
+    let conn = db.0.lock().unwrap();
+    section_repository::list_children(&conn, parent_id.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn move_section(id: String, parent_id: Option<String>, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    section_repository::move_section(&conn, &id, parent_id.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_section(uuid: String, name: String, details: Option<SectionDetails>, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    section_repository::update_section(&conn, &uuid, &name, details.as_ref()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -46,11 +89,44 @@ pub fn get_section_by_name(name: String, db: State<DbConn>) -> Result<Option<Sec
     section_repository::get_section_by_name(&conn, &name).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn duplicate_section(id: String, new_name: String, strategy: Option<CodeSuffixStrategy>, db: State<DbConn>) -> Result<Section, String> {
+    let mut conn = db.0.lock().unwrap();
+    let (section, copies) = duplicate_repository::duplicate_section(&mut conn, &id, &new_name, &strategy.unwrap_or_default())
+        .map_err(|e| e.to_string())?
+        .ok_or(i18n::text("section_not_found"))?;
+
+    for (original, copy) in copies {
+        copy_item_image(&conn, &original, &copy)?;
+    }
+    Ok(section)
+}
+
+#[tauri::command]
+pub fn set_section_template(section_id: String, fields: Vec<TemplateFieldInput>, db: State<DbConn>) -> Result<Vec<TemplateField>, String> {
+    let mut conn = db.0.lock().unwrap();
+    template_repository::set_template(&mut conn, &section_id, &fields).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_section_template(section_id: String, db: State<DbConn>) -> Result<Vec<TemplateField>, String> {
+    let conn = db.0.lock().unwrap();
+    template_repository::list_template(&conn, &section_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn apply_section_template(section_id: String, overwrite_values: Option<bool>, db: State<DbConn>) -> Result<BulkSummary, String> {
+    let mut conn = db.0.lock().unwrap();
+    template_repository::apply_template(&mut conn, &section_id, overwrite_values.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
 // Item handlers
 #[tauri::command]
 pub fn create_item(code: String, description: String, section_id: String, image_path: String, db: State<DbConn>) -> Result<(), String> {
     let conn = db.0.lock().unwrap();
-    item_repository::insert_item(&conn, code, description, section_id, image_path).map_err(|e| e.to_string())
+    item_repository::insert_item(&conn, code, description, section_id, image_path)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -65,6 +141,12 @@ pub fn list_items(section_id: String, db: State<DbConn>) -> Result<Vec<Item>, St
     item_repository::list_items(&conn, &section_id).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn list_new_items(days: u32, db: State<DbConn>) -> Result<Vec<Item>, String> {
+    let conn = db.0.lock().unwrap();
+    item_repository::list_new_items(&conn, days).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn has_items(db: State<DbConn>) -> Result<bool, String> {
     let conn = db.0.lock().unwrap();
@@ -79,8 +161,8 @@ pub fn has_sections(db: State<DbConn>) -> Result<bool, String> {
 
 #[tauri::command]
 pub fn update_item(id: String, code: String, description: String, section_id: String, image_path: String, db: State<DbConn>) -> Result<(), String> {
-    let conn = db.0.lock().unwrap();
-    item_repository::update_item(&conn, id, code, description, section_id, image_path).map_err(|e| e.to_string())
+    let mut conn = db.0.lock().unwrap();
+    item_repository::update_item(&mut conn, id, code, description, section_id, image_path).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -89,6 +171,165 @@ pub fn delete_item(id: String, db: State<DbConn>) -> Result<(), String> {
     item_repository::delete_item(&conn, &id).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn duplicate_item(id: String, new_code: String, db: State<DbConn>) -> Result<Item, String> {
+    let mut conn = db.0.lock().unwrap();
+    let original = item_repository::get_item(&conn, &id)
+        .map_err(|e| e.to_string())?
+        .ok_or(i18n::text("item_not_found"))?;
+    let copy = duplicate_repository::duplicate_item(&mut conn, &id, &new_code)
+        .map_err(|e| e.to_string())?
+        .ok_or(i18n::text("item_not_found"))?;
+
+    copy_item_image(&conn, &original, &copy)
+}
+
+/// Dá à cópia um arquivo de imagem próprio; se não der, ela segue usando o do original.
+fn copy_item_image(conn: &Connection, original: &Item, copy: &Item) -> Result<Item, String> {
+    let source = original.image_path.as_deref().unwrap_or_default();
+    let Some(image_path) = images::copy_image(source, &copy.code) else {
+        return Ok(copy.clone());
+    };
+
+    item_repository::update_image_path(conn, &copy.id, &image_path).map_err(|e| e.to_string())?;
+    Ok(Item { image_path: Some(image_path), ..copy.clone() })
+}
+
+// Bulk item handlers
+#[tauri::command]
+pub fn bulk_move_items(ids: Vec<String>, section_id: String, db: State<DbConn>) -> Result<BulkSummary, String> {
+    let mut conn = db.0.lock().unwrap();
+    bulk_repository::bulk_move_items(&mut conn, &ids, &section_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn bulk_delete_items(ids: Vec<String>, db: State<DbConn>) -> Result<BulkSummary, String> {
+    let mut conn = db.0.lock().unwrap();
+    bulk_repository::bulk_delete_items(&mut conn, &ids).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn bulk_duplicate_items(ids: Vec<String>, strategy: Option<CodeSuffixStrategy>, db: State<DbConn>) -> Result<BulkSummary, String> {
+    let mut conn = db.0.lock().unwrap();
+    let (summary, copies) = bulk_repository::bulk_duplicate_items(&mut conn, &ids, &strategy.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    for (original, copy) in copies {
+        copy_item_image(&conn, &original, &copy)?;
+    }
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn bulk_add_tag(ids: Vec<String>, tag_id: String, db: State<DbConn>) -> Result<BulkSummary, String> {
+    let mut conn = db.0.lock().unwrap();
+    bulk_repository::bulk_add_tag(&mut conn, &ids, &tag_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn bulk_remove_tag(ids: Vec<String>, tag_id: String, db: State<DbConn>) -> Result<BulkSummary, String> {
+    let mut conn = db.0.lock().unwrap();
+    bulk_repository::bulk_remove_tag(&mut conn, &ids, &tag_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn bulk_update_infos(ids: Vec<String>, name: String, details: String, db: State<DbConn>) -> Result<BulkSummary, String> {
+    let mut conn = db.0.lock().unwrap();
+    bulk_repository::bulk_update_infos(&mut conn, &ids, &name, &details).map_err(|e| e.to_string())
+}
+
+// Variant handlers
+#[tauri::command]
+pub fn set_variant_axes(item_id: String, axes: Vec<VariantAxisInput>, db: State<DbConn>) -> Result<Vec<VariantAxis>, String> {
+    let mut conn = db.0.lock().unwrap();
+    variant_repository::set_axes(&mut conn, &item_id, &axes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_variant_axes(item_id: String, db: State<DbConn>) -> Result<Vec<VariantAxis>, String> {
+    let conn = db.0.lock().unwrap();
+    variant_repository::list_axes(&conn, &item_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_variant(item_id: String, variant: VariantInput, db: State<DbConn>) -> Result<Variant, String> {
+    let conn = db.0.lock().unwrap();
+    let id = variant_repository::insert_variant(&conn, &item_id, &variant).map_err(|e| e.to_string())?;
+    variant_repository::get_variant(&conn, &id)
+        .map_err(|e| e.to_string())?
+        .ok_or(i18n::text("variant_not_found").into())
+}
+
+#[tauri::command]
+pub fn list_variants(item_id: String, db: State<DbConn>) -> Result<Vec<Variant>, String> {
+    let conn = db.0.lock().unwrap();
+    variant_repository::list_variants(&conn, &item_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_variant(id: String, variant: VariantInput, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    variant_repository::update_variant(&conn, &id, &variant).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_variant(id: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    variant_repository::delete_variant(&conn, &id).map_err(|e| e.to_string())
+}
+
+// Tag handlers
+#[tauri::command]
+pub fn create_tag(name: String, db: State<DbConn>) -> Result<Tag, String> {
+    let conn = db.0.lock().unwrap();
+    let id = tag_repository::insert_tag(&conn, &name).map_err(|e| e.to_string())?;
+    tag_repository::get_tag(&conn, &id)
+        .map_err(|e| e.to_string())?
+        .ok_or(i18n::text("tag_not_found").into())
+}
+
+#[tauri::command]
+pub fn list_tags(db: State<DbConn>) -> Result<Vec<Tag>, String> {
+    let conn = db.0.lock().unwrap();
+    tag_repository::list_tags(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_tag(id: String, name: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    tag_repository::update_tag(&conn, &id, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_tag(id: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    tag_repository::delete_tag(&conn, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_item_tag(item_id: String, tag_id: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    tag_repository::add_item_tag(&conn, &item_id, &tag_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_item_tag(item_id: String, tag_id: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    tag_repository::remove_item_tag(&conn, &item_id, &tag_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_item_tags(item_id: String, db: State<DbConn>) -> Result<Vec<Tag>, String> {
+    let conn = db.0.lock().unwrap();
+    tag_repository::list_item_tags(&conn, &item_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_items_by_tag(tag_id: String, db: State<DbConn>) -> Result<Vec<Item>, String> {
+    let conn = db.0.lock().unwrap();
+    tag_repository::list_items_by_tag(&conn, &tag_id).map_err(|e| e.to_string())
+}
+
 // Info handlers
 #[tauri::command]
 pub fn create_info(id: &str, item_code: &str, name: &str, details: &str, db: State<DbConn>) -> Result<(), String> {
@@ -120,43 +361,367 @@ pub fn delete_info(id: String, db: State<DbConn>) -> Result<(), String> {
     info_repository::delete_info(&conn, &id).map_err(|e| e.to_string())
 }
 
+// Attribute handlers
 #[tauri::command]
-pub fn save_image(image: Vec<u8>, code: String) -> Result<String, String> {
-    let folder_path: &'static str = "../images";
+pub fn create_attribute(attribute: AttributeInput, db: State<DbConn>) -> Result<AttributeDefinition, String> {
+    let conn = db.0.lock().unwrap();
+    let id = attribute_repository::insert_attribute(&conn, &attribute).map_err(|e| e.to_string())?;
+    attribute_repository::get_attribute(&conn, &id)
+        .map_err(|e| e.to_string())?
+        .ok_or(i18n::text("attribute_not_found").into())
+}
+
+#[tauri::command]
+pub fn list_attributes(db: State<DbConn>) -> Result<Vec<AttributeDefinition>, String> {
+    let conn = db.0.lock().unwrap();
+    attribute_repository::list_attributes(&conn).map_err(|e| e.to_string())
+}
 
-    fs::create_dir_all(folder_path).map_err(|e| format!("Erro ao criar pasta: {}", e))?;
+#[tauri::command]
+pub fn update_attribute(id: String, attribute: AttributeInput, db: State<DbConn>) -> Result<(), String> {
+    let mut conn = db.0.lock().unwrap();
+    attribute_repository::update_attribute(&mut conn, &id, &attribute).map_err(|e| e.to_string())
+}
 
-    let sanitized_code: String = code
-        .chars()
-        .map(|c: char| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
-        .collect();
+#[tauri::command]
+pub fn delete_attribute(id: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    attribute_repository::delete_attribute(&conn, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn attribute_suggestions(db: State<DbConn>) -> Result<Vec<AttributeSuggestion>, String> {
+    let conn = db.0.lock().unwrap();
+    attribute_repository::list_suggestions(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_image(image: Vec<u8>, code: String) -> Result<String, String> {
+    fs::create_dir_all(images::IMAGES_FOLDER).map_err(|e| i18n::message("create_folder_failed", &[&e]))?;
 
-    let file_path: String = format!("{}/{}.png", folder_path, sanitized_code);
+    let file_path: String = images::image_path_for_code(&code);
 
-    fs::write(&file_path, image).map_err(|e| format!("Erro ao salvar imagem: {}", e))?;
+    fs::write(&file_path, image).map_err(|e| i18n::message("save_image_failed", &[&e]))?;
 
     Ok(file_path)
 }
 
+// Catalog commands
+// Os comandos que montam PDFs (ou desenham a prévia) são `async`: rodam fora
+// da thread principal e não travam a janela.
+/// PDFs gravados: um só ou, no modo dividido, um por seção (com o catálogo
+/// completo primeiro, se pedido). No perfil de tela, cada um traz o tamanho final.
+#[tauri::command(async)]
+pub fn generate_catalog(options: Option<CatalogOptions>, db: State<DbConn>) -> Result<Vec<GeneratedFile>, String> {
+    let settings = settings_repository::get_settings();
+    let generated = catalog::job::generate(&db.0, &settings, &options.unwrap_or_default(), "", &Silent)
+        .map_err(|e| e.to_string())?;
+    Ok(generated.files)
+}
+
+/// `language` gera o mesmo preset em outro idioma, sem alterar o preset salvo.
+#[tauri::command(async)]
+pub fn generate_from_preset(name: String, language: Option<String>, db: State<DbConn>) -> Result<Vec<GeneratedFile>, String> {
+    let mut preset = find_preset(&db, &name)?;
+    if language.is_some() {
+        preset.options.language = language;
+    }
+    let settings = settings_repository::get_settings();
+    let generated = catalog::job::generate(&db.0, &settings, &preset.options, &preset.name, &Silent)
+        .map_err(|e| e.to_string())?;
+    Ok(generated.files)
+}
+
+/// Confere, sem desenhar as imagens, quais imagens de itens ficam abaixo da
+/// resolução mínima do perfil de gráfica. Fora desse perfil, a lista vem vazia.
+#[tauri::command(async)]
+pub fn check_print_images(
+    options: Option<CatalogOptions>,
+    preset: Option<String>,
+    db: State<DbConn>,
+) -> Result<Vec<LowResolutionImage>, String> {
+    let options = match preset {
+        Some(name) => find_preset(&db, &name)?.options,
+        None => options.unwrap_or_default(),
+    };
+    let catalog = {
+        let conn = db.0.lock().unwrap();
+        catalog::build_catalog(&conn, &options).map_err(|e| e.to_string())?
+    };
+    let settings = settings_repository::get_settings();
+    let rendered = catalog::render::render_pages(&catalog, &settings, &options, &ImageFilter::Page(0), &Silent)?;
+    Ok(rendered.low_resolution)
+}
+
+/// Confere se o código do item vira código de barras no formato pedido e
+/// devolve o texto impresso embaixo das barras (no EAN-13, com o verificador).
+#[tauri::command]
+pub fn check_barcode(code: String, format: Option<BarcodeFormat>) -> Result<String, String> {
+    catalog::barcode::encode(&code, format.unwrap_or_default()).map(|barcode| barcode.text)
+}
+
+fn find_preset(db: &DbConn, name: &str) -> Result<CatalogPreset, String> {
+    let conn = db.0.lock().unwrap();
+    preset_repository::get_preset_by_name(&conn, name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| i18n::message("preset_not_found", &[&name]))
+}
+
+/// Intervalo mínimo entre dois eventos `catalog-progress`.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Progresso de uma geração em segundo plano, enviado como evento para a janela.
+struct WindowMonitor {
+    window: Window,
+    job_id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct JobProgress<'a> {
+    job_id: &'a str,
+    #[serde(flatten)]
+    progress: &'a Progress,
+}
+
+impl Monitor for WindowMonitor {
+    fn report(&self, progress: &Progress) {
+        let event = JobProgress { job_id: &self.job_id, progress };
+        self.window.emit("catalog-progress", event).ok();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Gera o catálogo (das opções ou do preset) numa thread separada e devolve o id do job.
+/// A janela recebe `catalog-progress` durante a geração e `catalog-finished` no fim.
+#[tauri::command]
+pub fn start_catalog_job(
+    options: Option<CatalogOptions>,
+    preset: Option<String>,
+    language: Option<String>,
+    window: Window,
+    db: State<DbConn>,
+    jobs: State<CatalogJobs>,
+) -> Result<String, String> {
+    let (mut options, preset_name) = match preset {
+        Some(name) => {
+            let preset = find_preset(&db, &name)?;
+            (preset.options, preset.name)
+        }
+        None => (options.unwrap_or_default(), String::new()),
+    };
+    if language.is_some() {
+        options.language = language;
+    }
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    jobs.0.lock().unwrap().insert(job_id.clone(), cancelled.clone());
+
+    let monitor = Throttled::new(WindowMonitor { window, job_id: job_id.clone(), cancelled }, PROGRESS_INTERVAL);
+    std::thread::spawn(move || {
+        let app = monitor.inner().window.app_handle().clone();
+        let settings = settings_repository::get_settings();
+        // Um pânico na geração ainda precisa tirar o job da lista e avisar a janela.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            catalog::job::generate(&app.state::<DbConn>().0, &settings, &options, &preset_name, &monitor)
+        }))
+        .unwrap_or_else(|_| Err(JobError::Render(i18n::text("generation_failed").to_string())));
+
+        let WindowMonitor { window, job_id, .. } = monitor.into_inner();
+        app.state::<CatalogJobs>().0.lock().unwrap().remove(&job_id);
+        let (files, error, low_resolution) = match result {
+            Ok(generated) => (generated.files, None, generated.low_resolution),
+            Err(error) => (Vec::new(), Some(error), Vec::new()),
+        };
+        let finished = JobFinished { job_id, files, error, low_resolution };
+        window.emit("catalog-finished", finished).ok();
+    });
+
+    Ok(job_id)
+}
+
+/// Pede o cancelamento do job; devolve `false` se ele já tinha terminado.
+#[tauri::command]
+pub fn cancel_catalog_job(job_id: String, jobs: State<CatalogJobs>) -> Result<bool, String> {
+    match jobs.0.lock().unwrap().get(&job_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// PNG de uma página do catálogo do preset; com `item_id`, a página onde o item aparece.
+#[tauri::command(async)]
+pub fn preview_catalog_page(
+    preset: String,
+    page: Option<usize>,
+    item_id: Option<String>,
+    db: State<DbConn>,
+    cache: State<PreviewState>,
+) -> Result<Response, String> {
+    let settings = settings_repository::get_settings();
+    let target = match item_id {
+        Some(item_id) => PreviewTarget::Item(item_id),
+        None => PreviewTarget::Page(page.unwrap_or(1)),
+    };
+
+    let (options, catalog, revision) = {
+        let conn = db.0.lock().unwrap();
+        let revision = catalog::preview::current_revision(&conn, &settings).map_err(|e| e.to_string())?;
+        if let Some(png) = cache.0.lock().unwrap().get(&revision, &preset, &target) {
+            return Ok(Response::new(png));
+        }
+
+        let options = preset_repository::get_preset_by_name(&conn, &preset)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| i18n::message("preset_not_found", &[&preset]))?
+            .options;
+        let catalog = catalog::build_catalog(&conn, &options).map_err(|e| e.to_string())?;
+        (options, catalog, revision)
+    };
+
+    let png = catalog::preview::preview_page(&catalog, &settings, &options, &target)?;
+    cache.0.lock().unwrap().insert(&revision, &preset, target, png.clone());
+    Ok(Response::new(png))
+}
+
+#[tauri::command]
+pub fn save_catalog_preset(name: String, options: CatalogOptions, db: State<DbConn>) -> Result<String, String> {
+    let conn = db.0.lock().unwrap();
+    preset_repository::save_preset(&conn, &name, &options).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_catalog_presets(db: State<DbConn>) -> Result<Vec<CatalogPreset>, String> {
+    let conn = db.0.lock().unwrap();
+    preset_repository::list_presets(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_catalog_preset(id: String, db: State<DbConn>) -> Result<Option<CatalogPreset>, String> {
+    let conn = db.0.lock().unwrap();
+    preset_repository::get_preset(&conn, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_catalog_preset(id: String, name: String, options: CatalogOptions, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    preset_repository::update_preset(&conn, &id, &name, &options).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_catalog_preset(id: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    preset_repository::delete_preset(&conn, &id).map_err(|e| e.to_string())
+}
+
+// Label commands
+/// Grava a folha de etiquetas e devolve o caminho do PDF.
+#[tauri::command(async)]
+pub fn generate_labels(options: Option<LabelOptions>, db: State<DbConn>) -> Result<String, String> {
+    let settings = settings_repository::get_settings();
+    catalog::job::generate_labels(&db.0, &settings, &options.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Modelos de folha prontos e as medidas de cada um, para servir de base a um preset.
+#[tauri::command]
+pub fn list_label_models() -> Vec<(LabelModel, LabelSheet)> {
+    LabelModel::ALL.into_iter().map(|model| (model, model.sheet())).collect()
+}
+
+#[tauri::command]
+pub fn save_label_preset(name: String, sheet: LabelSheet, db: State<DbConn>) -> Result<String, String> {
+    let conn = db.0.lock().unwrap();
+    label_preset_repository::save_label_preset(&conn, &name, &sheet).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_label_presets(db: State<DbConn>) -> Result<Vec<LabelPreset>, String> {
+    let conn = db.0.lock().unwrap();
+    label_preset_repository::list_label_presets(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_label_preset(id: String, db: State<DbConn>) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    label_preset_repository::delete_label_preset(&conn, &id).map_err(|e| e.to_string())
+}
+
+// Translation commands
+/// Valor vazio remove a tradução, e o catálogo volta a usar o texto original.
+#[tauri::command]
+pub fn set_translation(
+    entity_type: String,
+    entity_id: String,
+    field: String,
+    locale: String,
+    value: String,
+    db: State<DbConn>,
+) -> Result<(), String> {
+    let conn = db.0.lock().unwrap();
+    translation_repository::set_translation(&conn, &entity_type, &entity_id, &field, &locale, &value)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_translations(entity_type: String, entity_id: String, db: State<DbConn>) -> Result<Vec<Translation>, String> {
+    let conn = db.0.lock().unwrap();
+    translation_repository::list_translations(&conn, &entity_type, &entity_id).map_err(|e| e.to_string())
+}
+
+// Font commands
+#[tauri::command]
+pub fn list_fonts() -> Result<Vec<FontFamily>, String> {
+    Ok(catalog::fonts::list_families())
+}
+
+#[tauri::command]
+pub fn register_font(name: String, regular_path: String, bold_path: Option<String>) -> Result<FontFamily, String> {
+    catalog::fonts::register_family(&name, &regular_path, bold_path.as_deref())
+}
+
+#[tauri::command]
+pub fn remove_font(name: String) -> Result<(), String> {
+    catalog::fonts::remove_family(&name)
+}
+
 #[tauri::command]
 pub fn get_settings(_db: State<DbConn>) -> Result<Settings, String> {
     Ok(settings_repository::get_settings())
 }
 
+/// Idioma das mensagens de erro e do catálogo quando ele não define um.
+/// Aceita "pt", "en", "es" ou com região ("pt-BR"); devolve o código aplicado.
+#[tauri::command]
+pub fn set_locale(locale: String) -> Result<String, String> {
+    i18n::set_locale(&locale).map(str::to_string)
+}
+
+#[tauri::command]
+pub fn get_locale() -> String {
+    i18n::locale().to_string()
+}
+
 // Auth commands
 #[tauri::command]
 pub fn save_user_id(user_id: String) -> Result<(), String> {
     let config_dir = dirs::config_dir()
-        .ok_or("Erro ao obter diretório de configuração")?
+        .ok_or(i18n::text("config_dir_unavailable"))?
         .join("BrilliantSoftware");
     
     fs::create_dir_all(&config_dir)
-        .map_err(|e| format!("Erro ao criar diretório de configuração: {}", e))?;
+        .map_err(|e| i18n::message("create_config_dir_failed", &[&e]))?;
     
     let user_id_file = config_dir.join(".user_id");
     
     fs::write(&user_id_file, &user_id)
-        .map_err(|e| format!("Erro ao salvar ID do usuário: {}", e))?;
+        .map_err(|e| i18n::message("save_user_id_failed", &[&e]))?;
     
     Ok(())
 }
@@ -164,7 +729,7 @@ pub fn save_user_id(user_id: String) -> Result<(), String> {
 #[tauri::command]
 pub fn load_user_id() -> Result<Option<String>, String> {
     let config_dir = dirs::config_dir()
-        .ok_or("Erro ao obter diretório de configuração")?
+        .ok_or(i18n::text("config_dir_unavailable"))?
         .join("BrilliantSoftware");
     
     let user_id_file = config_dir.join(".user_id");
@@ -181,14 +746,14 @@ pub fn load_user_id() -> Result<Option<String>, String> {
         Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => {
             Ok(None)
         }
-        Err(e) => Err(format!("Erro ao carregar ID do usuário: {}", e))
+        Err(e) => Err(i18n::message("load_user_id_failed", &[&e]))
     }
 }
 
 #[tauri::command]
 pub fn clear_user_id() -> Result<(), String> {
     let config_dir = dirs::config_dir()
-        .ok_or("Erro ao obter diretório de configuração")?
+        .ok_or(i18n::text("config_dir_unavailable"))?
         .join("BrilliantSoftware");
     
     let user_id_file = config_dir.join(".user_id");
@@ -198,6 +763,6 @@ pub fn clear_user_id() -> Result<(), String> {
         Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => {
             Ok(()) // Arquivo não existe, consideramos como sucesso
         }
-        Err(e) => Err(format!("Erro ao limpar ID do usuário: {}", e))
+        Err(e) => Err(i18n::message("clear_user_id_failed", &[&e]))
     }
 }
\ No newline at end of file
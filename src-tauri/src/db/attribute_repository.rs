@@ -0,0 +1,263 @@
+use rusqlite::{params, Connection, Error, Row};
+use uuid::Uuid;
+
+use crate::db::{validation_error, Result};
+use crate::i18n;
+use crate::models::attribute::{AttributeDefinition, AttributeInput, AttributeSuggestion, AttributeType};
+
+const ATTRIBUTE_COLUMNS: &str = "id, name, kind, unit, allowed_values, created_at, updated_at";
+const SUGGESTION_LIMIT: i64 = 20;
+
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attributes (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            kind TEXT NOT NULL,
+            unit TEXT,
+            allowed_values TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn map_attribute(row: &Row) -> rusqlite::Result<AttributeDefinition> {
+    let allowed_values: String = row.get(4)?;
+    Ok(AttributeDefinition {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: AttributeType::parse(&row.get::<_, String>(2)?),
+        unit: row.get(3)?,
+        allowed_values: serde_json::from_str(&allowed_values)
+            .map_err(|e| Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn allowed_values_json(input: &AttributeInput) -> rusqlite::Result<String> {
+    serde_json::to_string(&input.allowed_values).map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Insere a definição, liga a ela as infos que já usam o mesmo nome e devolve o id.
+/// Valores antigos que passam na validação são normalizados; os demais ficam como estão.
+pub fn insert_attribute(conn: &Connection, input: &AttributeInput) -> Result<String> {
+    let uuid = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO attributes (id, name, kind, unit, allowed_values, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'), datetime('now'))",
+        params![uuid, input.name.trim(), input.kind.as_str(), input.unit, allowed_values_json(input)?],
+    )?;
+
+    let attribute = get_attribute(conn, &uuid)?.ok_or(Error::QueryReturnedNoRows)?;
+    let mut stmt = conn.prepare("SELECT id, details FROM infos WHERE lower(name) = lower(?1)")?;
+    let existing = stmt
+        .query_map([&attribute.name], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for (info_id, details) in existing {
+        let details = attribute.normalize(&details).unwrap_or(details);
+        conn.execute(
+            "UPDATE infos SET attribute_id = ?1, name = ?2, details = ?3, updated_at = datetime('now') WHERE id = ?4",
+            params![attribute.id, attribute.name, details, info_id],
+        )?;
+    }
+    Ok(uuid)
+}
+
+pub fn get_attribute(conn: &Connection, id: &str) -> Result<Option<AttributeDefinition>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM attributes WHERE id = ?1", ATTRIBUTE_COLUMNS))?;
+    let mut rows = stmt.query(params![id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_attribute(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn find_attribute_by_name(conn: &Connection, name: &str) -> Result<Option<AttributeDefinition>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM attributes WHERE name = ?1", ATTRIBUTE_COLUMNS))?;
+    let mut rows = stmt.query(params![name.trim()])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_attribute(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn list_attributes(conn: &Connection) -> Result<Vec<AttributeDefinition>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM attributes ORDER BY name", ATTRIBUTE_COLUMNS))?;
+    let attribute_iter = stmt.query_map([], map_attribute)?;
+
+    let mut attributes = Vec::new();
+    for attribute in attribute_iter {
+        attributes.push(attribute?);
+    }
+    Ok(attributes)
+}
+
+/// Atualiza a definição e renomeia as infos ligadas a ela. Os valores são
+/// validados de novo com a nova definição; se algum não passar, nada é gravado.
+pub fn update_attribute(conn: &mut Connection, id: &str, input: &AttributeInput) -> Result<()> {
+    let tx = conn.transaction()?;
+    let previous = get_attribute(&tx, id)?.ok_or_else(|| validation_error(i18n::text("attribute_not_found")))?;
+    tx.execute(
+        "UPDATE attributes SET name = ?1, kind = ?2, unit = ?3, allowed_values = ?4, updated_at = datetime('now')
+         WHERE id = ?5",
+        params![input.name.trim(), input.kind.as_str(), input.unit, allowed_values_json(input)?, id],
+    )?;
+    let attribute = get_attribute(&tx, id)?.ok_or(Error::QueryReturnedNoRows)?;
+
+    let existing = {
+        let mut stmt = tx.prepare("SELECT id, details FROM infos WHERE attribute_id = ?1")?;
+        let rows = stmt.query_map([id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    for (info_id, details) in existing {
+        // Entre tipos numéricos o valor gravado já está na forma canônica
+        // ("1.5"); passá-lo de novo pelo parser leria o ponto como milhar.
+        let details = if previous.kind.is_numeric() && attribute.kind.is_numeric() {
+            details
+        } else {
+            attribute.normalize(&details).map_err(validation_error)?
+        };
+        tx.execute(
+            "UPDATE infos SET name = ?1, details = ?2, updated_at = datetime('now') WHERE id = ?3",
+            params![attribute.name, details, info_id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Remove a definição; as infos continuam existindo como texto livre.
+pub fn delete_attribute(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute("UPDATE infos SET attribute_id = NULL WHERE attribute_id = ?1", params![id])?;
+    conn.execute("DELETE FROM attributes WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Nomes e valores para autocompletar: primeiro as definições (com os valores
+/// permitidos ou os mais usados), depois os nomes livres já cadastrados.
+pub fn list_suggestions(conn: &Connection) -> Result<Vec<AttributeSuggestion>> {
+    let mut suggestions = Vec::new();
+
+    for attribute in list_attributes(conn)? {
+        let values = if attribute.kind == AttributeType::Enum {
+            attribute.allowed_values.clone()
+        } else {
+            used_values(conn, "attribute_id = ?1", &attribute.id)?
+        };
+        suggestions.push(AttributeSuggestion {
+            name: attribute.name,
+            kind: Some(attribute.kind),
+            unit: attribute.unit,
+            values,
+        });
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT name FROM infos WHERE attribute_id IS NULL ORDER BY name COLLATE NOCASE",
+    )?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    for name in names {
+        let name = name?;
+        let values = used_values(conn, "attribute_id IS NULL AND name = ?1", &name)?;
+        suggestions.push(AttributeSuggestion { name, kind: None, unit: None, values });
+    }
+
+    Ok(suggestions)
+}
+
+// Valores mais frequentes das infos que atendem ao filtro.
+fn used_values(conn: &Connection, filter: &str, param: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT details FROM infos WHERE {} GROUP BY details ORDER BY COUNT(*) DESC, details LIMIT {}",
+        filter, SUGGESTION_LIMIT
+    ))?;
+    let value_iter = stmt.query_map([param], |row| row.get::<_, String>(0))?;
+
+    let mut values = Vec::new();
+    for value in value_iter {
+        values.push(value?);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{info_repository, item_repository, section_repository, DbError};
+
+    fn item_with_info(conn: &Connection, name: &str, details: &str) -> String {
+        section_repository::insert_section(conn, "Anéis", None, &Default::default()).unwrap();
+        let section = section_repository::get_section_by_name(conn, "Anéis").unwrap().unwrap();
+        item_repository::insert_item(conn, "A1".into(), "Anel".into(), section.id, String::new()).unwrap();
+        info_repository::insert_info(conn, "info-1", "A1", name, details).unwrap();
+        "info-1".to_string()
+    }
+
+    fn input(name: &str, kind: AttributeType) -> AttributeInput {
+        AttributeInput { name: name.into(), kind, unit: None, allowed_values: Vec::new() }
+    }
+
+    #[test]
+    fn insert_links_and_normalizes_existing_infos() {
+        let conn = open_in_memory();
+        let info = item_with_info(&conn, "peso", "4,5");
+        let id = insert_attribute(&conn, &input("Peso", AttributeType::Number)).unwrap();
+
+        let stored = info_repository::get_info(&conn, &info).unwrap().unwrap();
+        assert_eq!(stored.attribute_id.as_deref(), Some(id.as_str()));
+        assert_eq!(stored.name, "Peso");
+        assert_eq!(stored.details, "4.5");
+    }
+
+    #[test]
+    fn update_revalidates_values_when_the_type_changes() {
+        let mut conn = open_in_memory();
+        let info = item_with_info(&conn, "Banho", "sim");
+        let id = insert_attribute(&conn, &input("Banho", AttributeType::Text)).unwrap();
+
+        update_attribute(&mut conn, &id, &input("Banho", AttributeType::Boolean)).unwrap();
+        assert_eq!(info_repository::get_info(&conn, &info).unwrap().unwrap().details, "true");
+    }
+
+    #[test]
+    fn update_rolls_back_when_a_value_is_invalid() {
+        let mut conn = open_in_memory();
+        let info = item_with_info(&conn, "Cor", "dourado");
+        let id = insert_attribute(&conn, &input("Cor", AttributeType::Text)).unwrap();
+
+        let error = update_attribute(&mut conn, &id, &input("Tom", AttributeType::Number)).unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+
+        let attribute = get_attribute(&conn, &id).unwrap().unwrap();
+        assert_eq!((attribute.name.as_str(), attribute.kind), ("Cor", AttributeType::Text));
+        assert_eq!(info_repository::get_info(&conn, &info).unwrap().unwrap().name, "Cor");
+    }
+
+    #[test]
+    fn update_between_numeric_types_keeps_stored_numbers() {
+        let mut conn = open_in_memory();
+        let info = item_with_info(&conn, "Peso", "1,234");
+        let id = insert_attribute(&conn, &input("Peso", AttributeType::Number)).unwrap();
+
+        let unit = AttributeInput { unit: Some("g".into()), ..input("Peso", AttributeType::Unit) };
+        update_attribute(&mut conn, &id, &unit).unwrap();
+        assert_eq!(info_repository::get_info(&conn, &info).unwrap().unwrap().details, "1.234");
+    }
+
+    #[test]
+    fn update_unknown_attribute_is_a_validation_error() {
+        let mut conn = open_in_memory();
+        let error = update_attribute(&mut conn, "missing", &input("Peso", AttributeType::Number)).unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+    }
+}
@@ -0,0 +1,242 @@
+use rusqlite::{params, Connection};
+use uuid::Uuid;
+
+use crate::db::duplicate_repository::{copy_item, next_available_code, ItemCopies};
+use crate::db::{info_repository, item_repository, section_repository, tag_repository, validation_error, Result};
+use crate::i18n;
+use crate::models::bulk::{BulkSummary, CodeSuffixStrategy};
+
+/// Move os itens para outra seção.
+pub fn bulk_move_items(conn: &mut Connection, ids: &[String], section_id: &str) -> Result<BulkSummary> {
+    let tx = conn.transaction()?;
+    if section_repository::get_section(&tx, section_id)?.is_none() {
+        return Err(validation_error(i18n::text("section_not_found")));
+    }
+    let mut summary = BulkSummary { requested: ids.len(), ..Default::default() };
+
+    for id in ids {
+        let changed = tx.execute(
+            "UPDATE items SET section_id = ?1, updated_at = datetime('now') WHERE id = ?2",
+            params![section_id, id],
+        )?;
+        if changed == 0 {
+            summary.missing.push(id.clone());
+        } else {
+            summary.affected += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+/// Remove os itens; infos, variantes e tags caem junto pelo `ON DELETE CASCADE`.
+pub fn bulk_delete_items(conn: &mut Connection, ids: &[String]) -> Result<BulkSummary> {
+    let tx = conn.transaction()?;
+    let mut summary = BulkSummary { requested: ids.len(), ..Default::default() };
+
+    for id in ids {
+        let changed = tx.execute("DELETE FROM items WHERE id = ?1", params![id])?;
+        if changed == 0 {
+            summary.missing.push(id.clone());
+        } else {
+            summary.affected += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+/// Duplica os itens na mesma seção, com códigos gerados pela estratégia.
+/// Devolve também os pares (original, cópia), para quem chama copiar as imagens.
+pub fn bulk_duplicate_items(conn: &mut Connection, ids: &[String], strategy: &CodeSuffixStrategy) -> Result<(BulkSummary, ItemCopies)> {
+    let tx = conn.transaction()?;
+    let mut summary = BulkSummary { requested: ids.len(), ..Default::default() };
+    let mut copies = Vec::new();
+
+    for id in ids {
+        match item_repository::get_item(&tx, id)? {
+            Some(item) => {
+                let code = next_available_code(&tx, &item.code, strategy)?;
+                let new_item = copy_item(&tx, &item, &code, &item.section_id)?;
+                summary.created.push(new_item.id.clone());
+                summary.affected += 1;
+                copies.push((item, new_item));
+            }
+            None => summary.missing.push(id.clone()),
+        }
+    }
+
+    tx.commit()?;
+    Ok((summary, copies))
+}
+
+/// Coloca a tag em todos os itens; os que já tinham contam como afetados.
+pub fn bulk_add_tag(conn: &mut Connection, ids: &[String], tag_id: &str) -> Result<BulkSummary> {
+    bulk_tag(conn, ids, tag_id, tag_repository::add_item_tag)
+}
+
+/// Tira a tag de todos os itens.
+pub fn bulk_remove_tag(conn: &mut Connection, ids: &[String], tag_id: &str) -> Result<BulkSummary> {
+    bulk_tag(conn, ids, tag_id, tag_repository::remove_item_tag)
+}
+
+fn bulk_tag(
+    conn: &mut Connection,
+    ids: &[String],
+    tag_id: &str,
+    apply: fn(&Connection, &str, &str) -> Result<()>,
+) -> Result<BulkSummary> {
+    let tx = conn.transaction()?;
+    if tag_repository::get_tag(&tx, tag_id)?.is_none() {
+        return Err(validation_error(i18n::text("tag_not_found")));
+    }
+    let mut summary = BulkSummary { requested: ids.len(), ..Default::default() };
+
+    for id in ids {
+        if item_repository::get_item(&tx, id)?.is_none() {
+            summary.missing.push(id.clone());
+            continue;
+        }
+        apply(&tx, id, tag_id)?;
+        summary.affected += 1;
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+/// Define `name` = `details` em todos os itens: atualiza a info se já existir, senão cria.
+pub fn bulk_update_infos(conn: &mut Connection, ids: &[String], name: &str, details: &str) -> Result<BulkSummary> {
+    let tx = conn.transaction()?;
+    let mut summary = BulkSummary { requested: ids.len(), ..Default::default() };
+
+    for id in ids {
+        let Some(item) = item_repository::get_item(&tx, id)? else {
+            summary.missing.push(id.clone());
+            continue;
+        };
+
+        match info_repository::find_info_by_name(&tx, &item.code, name)? {
+            Some(info) => info_repository::update_info(&tx, &info.id, &item.code, &info.name, details)?,
+            None => {
+                let info_id = Uuid::new_v4().to_string();
+                info_repository::insert_info(&tx, &info_id, &item.code, name, details)?;
+            }
+        }
+        summary.affected += 1;
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{variant_repository, DbError};
+    use crate::models::variant::{VariantAxisInput, VariantInput};
+
+    fn section(conn: &Connection, name: &str) -> String {
+        section_repository::insert_section(conn, name, None, &Default::default()).unwrap();
+        section_repository::get_section_by_name(conn, name).unwrap().unwrap().id
+    }
+
+    // Item "AL-001" com uma info, uma variante de tamanho e a tag "Promoção".
+    fn full_item(conn: &mut Connection, section_id: &str) -> (String, String) {
+        let id = item_repository::insert_item(conn, "AL-001".into(), "Aliança".into(), section_id.into(), String::new()).unwrap();
+        info_repository::insert_info(conn, "info-1", "AL-001", "Peso", "4 g").unwrap();
+        let axis = VariantAxisInput { name: "Tamanho".into(), values: vec!["16".into(), "18".into()] };
+        variant_repository::set_axes(conn, &id, &[axis]).unwrap();
+        let variant = VariantInput {
+            code: "AL-001-16".into(),
+            options: [("Tamanho".to_string(), "16".to_string())].into(),
+            price: Some(120.0),
+            ..Default::default()
+        };
+        variant_repository::insert_variant(conn, &id, &variant).unwrap();
+        let tag = tag_repository::insert_tag(conn, "Promoção").unwrap();
+        tag_repository::add_item_tag(conn, &id, &tag).unwrap();
+        (id, tag)
+    }
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn delete_removes_dependent_rows() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn, "Alianças");
+        let (id, _) = full_item(&mut conn, &section_id);
+
+        let summary = bulk_delete_items(&mut conn, &[id, "missing".into()]).unwrap();
+        assert_eq!((summary.affected, summary.missing.len()), (1, 1));
+        for table in ["infos", "variants", "variant_axes", "item_tags"] {
+            assert_eq!(count(&conn, table), 0, "{}", table);
+        }
+    }
+
+    #[test]
+    fn move_rejects_unknown_section() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn, "Alianças");
+        let (id, _) = full_item(&mut conn, &section_id);
+
+        let error = bulk_move_items(&mut conn, std::slice::from_ref(&id), "missing").unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+        assert_eq!(item_repository::get_item(&conn, &id).unwrap().unwrap().section_id, section_id);
+
+        let other = section(&conn, "Anéis");
+        assert_eq!(bulk_move_items(&mut conn, &[id], &other).unwrap().affected, 1);
+    }
+
+    #[test]
+    fn duplicate_copies_infos_variants_and_tags() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn, "Alianças");
+        let (id, tag) = full_item(&mut conn, &section_id);
+
+        let (summary, copies) = bulk_duplicate_items(&mut conn, &[id], &CodeSuffixStrategy::Copy).unwrap();
+        assert_eq!(summary.created.len(), 1);
+        let copy = &copies[0].1;
+        assert_eq!(copy.code, "AL-001-COPIA");
+
+        assert_eq!(info_repository::list_infos(&conn, &copy.code).unwrap()[0].details, "4 g");
+        assert_eq!(variant_repository::list_axes(&conn, &copy.id).unwrap()[0].values, ["16", "18"]);
+        let variants = variant_repository::list_variants(&conn, &copy.id).unwrap();
+        assert_eq!((variants[0].code.as_str(), variants[0].price), ("AL-001-COPIA-16", Some(120.0)));
+        assert_eq!(tag_repository::list_item_tags(&conn, &copy.id).unwrap()[0].id, tag);
+    }
+
+    #[test]
+    fn bulk_tag_adds_and_removes() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn, "Alianças");
+        let (id, _) = full_item(&mut conn, &section_id);
+        let tag = tag_repository::insert_tag(&conn, "Lançamento").unwrap();
+
+        let summary = bulk_add_tag(&mut conn, &[id.clone(), "missing".into()], &tag).unwrap();
+        assert_eq!((summary.affected, summary.missing), (1, vec!["missing".to_string()]));
+        assert_eq!(tag_repository::list_items_by_tag(&conn, &tag).unwrap().len(), 1);
+
+        bulk_remove_tag(&mut conn, std::slice::from_ref(&id), &tag).unwrap();
+        assert!(tag_repository::list_items_by_tag(&conn, &tag).unwrap().is_empty());
+
+        let error = bulk_add_tag(&mut conn, &[id], "missing").unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+    }
+
+    #[test]
+    fn changing_the_code_keeps_the_infos() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn, "Alianças");
+        let (id, _) = full_item(&mut conn, &section_id);
+
+        item_repository::update_item(&mut conn, id, "AL-002".into(), "Aliança".into(), section_id, String::new()).unwrap();
+        assert_eq!(info_repository::list_infos(&conn, "AL-002").unwrap().len(), 1);
+        assert!(info_repository::list_infos(&conn, "AL-001").unwrap().is_empty());
+    }
+}
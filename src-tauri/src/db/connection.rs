@@ -2,7 +2,10 @@ use std::fs;
 use std::path::PathBuf;
 use dirs_next::data_dir;
 use rusqlite::Connection;
-use crate::db::{info_repository, item_repository, section_repository};
+use crate::i18n;
+use crate::db::{attribute_repository, info_repository, item_repository, label_preset_repository, migrations, preset_repository, section_repository, tag_repository, template_repository, translation_repository, variant_repository};
+
+type CreateTable = fn(&Connection) -> crate::db::Result<()>;
 
 pub fn database_connect() -> Connection {
     let mut path: PathBuf = data_dir().unwrap();
@@ -10,22 +13,54 @@ pub fn database_connect() -> Connection {
 
     if !path.exists() {
         match fs::create_dir_all(&path) {
-            Ok(_) => println!("Pasta criada: {:?}", path.display()),
-            Err(e) => eprintln!("Erro ao criar pasta: {}", e),
+            Ok(_) => println!("{}", i18n::message("folder_created", &[&path.display()])),
+            Err(e) => eprintln!("{}", i18n::message("create_folder_failed", &[&e])),
         }
     } else {
-        println!("Pasta já existe: {:?}", path.display());
+        println!("{}", i18n::message("folder_exists", &[&path.display()]));
     }
 
     path.push("db.sqlite");
-    println!("db data directory: {}", path.display());
+    println!("{}", i18n::message("database_path", &[&path.display()]));
+
+    let conn = Connection::open(&path).unwrap_or_else(|e| panic!("{}: {}", i18n::text("open_database_failed"), e));
+    create_schema(&conn);
+    conn
+}
 
-    let conn = Connection::open(&path).expect("Falha ao abrir banco");
+/// Cria as tabelas que faltam e roda as migrações.
+pub fn create_schema(conn: &Connection) {
+    // O SQLite só respeita as FOREIGN KEY (e os ON DELETE CASCADE) com isto
+    // ligado, e a configuração vale por conexão.
+    conn.execute_batch("PRAGMA foreign_keys = ON")
+        .unwrap_or_else(|e| panic!("{}: {}", i18n::text("open_database_failed"), e));
 
     // Criar tabelas se não existirem
-    section_repository::create_table(&conn).expect("Erro ao criar tabela de seções");
-    item_repository::create_table(&conn).expect("Erro ao criar tabela de itens");
-    info_repository::create_table(&conn).expect("Erro ao criar tabela de infos");
+    let tables: [(&str, CreateTable); 10] = [
+        ("sections", section_repository::create_table),
+        ("items", item_repository::create_table),
+        ("infos", info_repository::create_table),
+        ("variants", variant_repository::create_table),
+        ("tags", tag_repository::create_table),
+        ("attributes", attribute_repository::create_table),
+        ("section_templates", template_repository::create_table),
+        ("catalog_presets", preset_repository::create_table),
+        ("label_presets", label_preset_repository::create_table),
+        ("translations", translation_repository::create_table),
+    ];
+    for (table, create_table) in tables {
+        if let Err(e) = create_table(conn) {
+            panic!("{}: {}", i18n::message("create_table_failed", &[&table]), e);
+        }
+    }
+
+    migrations::run_migrations(conn).unwrap_or_else(|e| panic!("{}: {}", i18n::text("migrate_database_failed"), e));
+}
 
+/// Banco em memória com o esquema completo, para os testes.
+#[cfg(test)]
+pub fn open_in_memory() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    create_schema(&conn);
     conn
 }
\ No newline at end of file
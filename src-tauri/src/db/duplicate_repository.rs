@@ -0,0 +1,251 @@
+use rusqlite::{params, Connection, Error};
+use uuid::Uuid;
+
+use crate::db::{info_repository, item_repository, section_repository, template_repository, translation_repository, variant_repository, Result};
+use crate::models::bulk::CodeSuffixStrategy;
+use crate::models::item::Item;
+use crate::models::section::{Section, SectionDetails};
+use crate::models::template::TemplateFieldInput;
+use crate::models::variant::{VariantAxisInput, VariantInput};
+
+/// Pares (original, cópia).
+pub type ItemCopies = Vec<(Item, Item)>;
+
+/// Duplica o item (infos, variantes e tags) com o código informado.
+/// A imagem continua apontando para o arquivo original; quem chama decide se copia.
+pub fn duplicate_item(conn: &mut Connection, id: &str, new_code: &str) -> Result<Option<Item>> {
+    let tx = conn.transaction()?;
+
+    let Some(item) = item_repository::get_item(&tx, id)? else {
+        return Ok(None);
+    };
+    let new_item = copy_item(&tx, &item, new_code, &item.section_id)?;
+
+    tx.commit()?;
+    Ok(Some(new_item))
+}
+
+/// Cria uma nova seção com cópias do template, dos itens e de todas as
+/// subseções da seção `id`. Devolve a seção criada e as cópias dos itens.
+pub fn duplicate_section(
+    conn: &mut Connection,
+    id: &str,
+    new_name: &str,
+    strategy: &CodeSuffixStrategy,
+) -> Result<Option<(Section, ItemCopies)>> {
+    let tx = conn.transaction()?;
+
+    let Some(original) = section_repository::get_section(&tx, id)? else {
+        return Ok(None);
+    };
+    let mut copies = Vec::new();
+    let new_section = copy_section(&tx, &original, new_name, original.parent_id.as_deref(), strategy, &mut copies)?;
+
+    tx.commit()?;
+    Ok(Some((new_section, copies)))
+}
+
+// Os nomes de seção são únicos, então as subseções copiadas levam o nome da
+// nova seção entre parênteses: "Ouro" vira "Ouro (Anéis 2025)".
+fn copy_section(
+    conn: &Connection,
+    original: &Section,
+    new_name: &str,
+    parent_id: Option<&str>,
+    strategy: &CodeSuffixStrategy,
+    copies: &mut ItemCopies,
+) -> Result<Section> {
+    let details = SectionDetails {
+        description: original.description.clone(),
+        cover_image: original.cover_image.clone(),
+        palette: original.palette.clone(),
+        layout: original.layout.clone(),
+    };
+    section_repository::insert_section(conn, new_name, parent_id, &details)?;
+    let new_section = section_repository::get_section_by_name(conn, new_name)?
+        .ok_or(Error::QueryReturnedNoRows)?;
+    // O nome mudou, então a tradução dele não vale para a cópia.
+    translation_repository::copy_translations(conn, "section", &original.id, &new_section.id, &["name"])?;
+
+    for field in template_repository::list_template(conn, &original.id)? {
+        let input = TemplateFieldInput { name: field.name, default_value: field.default_value };
+        template_repository::insert_field(conn, &new_section.id, &input, field.position)?;
+    }
+
+    for item in item_repository::list_items(conn, &original.id)? {
+        let code = next_available_code(conn, &item.code, strategy)?;
+        let new_item = copy_item(conn, &item, &code, &new_section.id)?;
+        copies.push((item, new_item));
+    }
+
+    for child in section_repository::list_children(conn, Some(&original.id))? {
+        let child_name = free_section_name(conn, &format!("{} ({})", child.name, new_name))?;
+        copy_section(conn, &child, &child_name, Some(&new_section.id), strategy, copies)?;
+    }
+
+    Ok(new_section)
+}
+
+fn free_section_name(conn: &Connection, base: &str) -> Result<String> {
+    let mut candidate = base.to_string();
+    let mut counter = 2;
+    while section_repository::get_section_by_name(conn, &candidate)?.is_some() {
+        candidate = format!("{} {}", base, counter);
+        counter += 1;
+    }
+    Ok(candidate)
+}
+
+/// Copia o item, as infos, as variantes, as tags e as traduções para
+/// `section_id` com um novo código.
+pub fn copy_item(conn: &Connection, item: &Item, new_code: &str, section_id: &str) -> Result<Item> {
+    let new_id = item_repository::insert_item(
+        conn,
+        new_code.to_string(),
+        item.description.clone(),
+        section_id.to_string(),
+        item.image_path.clone().unwrap_or_default(),
+    )?;
+
+    translation_repository::copy_translations(conn, "item", &item.id, &new_id, &[])?;
+
+    // O novo item pode já ter infos vindas do template da seção.
+    for info in info_repository::list_infos(conn, &item.code)? {
+        let info_id = match info_repository::find_info_by_name(conn, new_code, &info.name)? {
+            Some(existing) => {
+                info_repository::update_info(conn, &existing.id, new_code, &info.name, &info.details)?;
+                existing.id
+            }
+            None => {
+                let info_id = Uuid::new_v4().to_string();
+                info_repository::insert_info(conn, &info_id, new_code, &info.name, &info.details)?;
+                info_id
+            }
+        };
+        translation_repository::copy_translations(conn, "info", &info.id, &info_id, &[])?;
+    }
+
+    copy_variants(conn, item, &new_id, new_code)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO item_tags (item_id, tag_id) SELECT ?1, tag_id FROM item_tags WHERE item_id = ?2",
+        params![new_id, item.id],
+    )?;
+
+    Ok(item_repository::get_item(conn, &new_id)?.ok_or(Error::QueryReturnedNoRows)?)
+}
+
+// Eixos e variantes do original. O código da variante troca o prefixo do item
+// pelo novo código ("AL-001-16" vira "AL-001-COPIA-16").
+fn copy_variants(conn: &Connection, item: &Item, new_item_id: &str, new_code: &str) -> Result<()> {
+    for axis in variant_repository::list_axes(conn, &item.id)? {
+        let input = VariantAxisInput { name: axis.name, values: axis.values };
+        variant_repository::insert_axis(conn, new_item_id, &input, axis.position)?;
+    }
+
+    for variant in variant_repository::list_variants(conn, &item.id)? {
+        let base = match variant.code.strip_prefix(&item.code) {
+            Some(rest) => format!("{}{}", new_code, rest),
+            None => format!("{}-{}", new_code, variant.code),
+        };
+        let input = VariantInput {
+            code: free_code(conn, &base)?,
+            options: variant.options,
+            price: variant.price,
+            stock: variant.stock,
+            image_path: variant.image_path,
+        };
+        variant_repository::insert_variant(conn, new_item_id, &input)?;
+    }
+    Ok(())
+}
+
+// Primeiro código livre entre itens e variantes: `base`, `base-2`, `base-3`...
+fn free_code(conn: &Connection, base: &str) -> Result<String> {
+    let mut candidate = base.to_string();
+    let mut counter = 2;
+    while item_repository::code_exists(conn, &candidate)? {
+        candidate = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    Ok(candidate)
+}
+
+pub fn next_available_code(conn: &Connection, code: &str, strategy: &CodeSuffixStrategy) -> Result<String> {
+    let base = match strategy {
+        CodeSuffixStrategy::Copy => format!("{}-COPIA", code),
+        CodeSuffixStrategy::Numbered => format!("{}-1", code),
+        CodeSuffixStrategy::Custom(suffix) => format!("{}{}", code, suffix),
+    };
+    if !item_repository::code_exists(conn, &base)? {
+        return Ok(base);
+    }
+
+    let mut counter = 2;
+    loop {
+        let candidate = match strategy {
+            CodeSuffixStrategy::Numbered => format!("{}-{}", code, counter),
+            _ => format!("{}-{}", base, counter),
+        };
+        if !item_repository::code_exists(conn, &candidate)? {
+            return Ok(candidate);
+        }
+        counter += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::open_in_memory;
+    use crate::db::tag_repository;
+
+    fn section(conn: &Connection, name: &str, parent_id: Option<&str>) -> String {
+        section_repository::insert_section(conn, name, parent_id, &Default::default()).unwrap();
+        section_repository::get_section_by_name(conn, name).unwrap().unwrap().id
+    }
+
+    #[test]
+    fn duplicate_section_copies_the_whole_subtree() {
+        let mut conn = open_in_memory();
+        let rings = section(&conn, "Anéis", None);
+        let gold = section(&conn, "Ouro", Some(&rings));
+        let fields = [TemplateFieldInput { name: "Metal".into(), default_value: Some("Ouro 18k".into()) }];
+        template_repository::set_template(&mut conn, &gold, &fields).unwrap();
+        let item = item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), gold.clone(), String::new()).unwrap();
+        let variant = VariantInput { code: "AN-1-16".into(), ..Default::default() };
+        variant_repository::insert_variant(&conn, &item, &variant).unwrap();
+        let tag = tag_repository::insert_tag(&conn, "Promoção").unwrap();
+        tag_repository::add_item_tag(&conn, &item, &tag).unwrap();
+
+        let (copy, copies) = duplicate_section(&mut conn, &rings, "Anéis 2025", &CodeSuffixStrategy::Numbered)
+            .unwrap()
+            .unwrap();
+        assert_eq!(copy.parent_id, None);
+
+        let children = section_repository::list_children(&conn, Some(&copy.id)).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "Ouro (Anéis 2025)");
+        assert_eq!(template_repository::list_template(&conn, &children[0].id).unwrap()[0].name, "Metal");
+
+        assert_eq!(copies.len(), 1);
+        let new_item = &copies[0].1;
+        assert_eq!((new_item.code.as_str(), new_item.section_id.as_str()), ("AN-1-1", children[0].id.as_str()));
+        assert_eq!(variant_repository::list_variants(&conn, &new_item.id).unwrap()[0].code, "AN-1-1-16");
+        assert_eq!(tag_repository::list_item_tags(&conn, &new_item.id).unwrap().len(), 1);
+
+        // A seção original fica como estava.
+        assert_eq!(section_repository::list_children(&conn, Some(&rings)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn variant_copies_get_free_codes() {
+        let mut conn = open_in_memory();
+        let rings = section(&conn, "Anéis", None);
+        let item = item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), rings.clone(), String::new()).unwrap();
+        variant_repository::insert_variant(&conn, &item, &VariantInput { code: "X-16".into(), ..Default::default() }).unwrap();
+        item_repository::insert_item(&conn, "AN-2-X-16".into(), "Outro".into(), rings, String::new()).unwrap();
+
+        let copy = duplicate_item(&mut conn, &item, "AN-2").unwrap().unwrap();
+        assert_eq!(variant_repository::list_variants(&conn, &copy.id).unwrap()[0].code, "AN-2-X-16-2");
+    }
+}
@@ -1,6 +1,9 @@
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, Row};
+use crate::db::{attribute_repository, validation_error, Result};
 use crate::models::info::Info;
 
+const INFO_COLUMNS: &str = "id, item_code, name, details, attribute_id, created_at, updated_at";
+
 pub fn create_table(conn: &Connection) -> Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS infos (
@@ -8,6 +11,9 @@ pub fn create_table(conn: &Connection) -> Result<()> {
             item_code TEXT NOT NULL,
             name TEXT NOT NULL,
             details TEXT NOT NULL,
+            attribute_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
             FOREIGN KEY (item_code) REFERENCES items(code) ON DELETE CASCADE
         )",
         [],
@@ -15,54 +21,90 @@ pub fn create_table(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+fn map_info(row: &Row) -> rusqlite::Result<Info> {
+    Ok(Info {
+        id: row.get(0)?,
+        item_code: row.get(1)?,
+        name: row.get(2)?,
+        details: row.get(3)?,
+        attribute_id: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+/// Liga a info à definição de atributo com o mesmo nome, validando o valor.
+/// Devolve (attribute_id, nome, valor) como devem ser gravados.
+fn apply_attribute(conn: &Connection, name: &str, details: &str) -> Result<(Option<String>, String, String)> {
+    match attribute_repository::find_attribute_by_name(conn, name)? {
+        Some(attribute) => {
+            let details = attribute.normalize(details).map_err(validation_error)?;
+            Ok((Some(attribute.id), attribute.name, details))
+        }
+        None => Ok((None, name.to_string(), details.to_string())),
+    }
+}
+
 pub fn insert_info(conn: &Connection, id: &str, item_code: &str, name: &str, details: &str) -> Result<()> {
+    let (attribute_id, name, details) = apply_attribute(conn, name, details)?;
     conn.execute(
-        "INSERT INTO infos (id, item_code, name, details) VALUES (?1, ?2, ?3, ?4)",
-        params![id, item_code, name, details],
+        "INSERT INTO infos (id, item_code, name, details, attribute_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'), datetime('now'))",
+        params![id, item_code, name, details, attribute_id],
     )?;
     Ok(())
 }
 
 pub fn get_info(conn: &Connection, id: &str) -> Result<Option<Info>> {
-    let mut stmt = conn.prepare("SELECT id, item_code, name, details FROM infos WHERE id = ?1")?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM infos WHERE id = ?1", INFO_COLUMNS))?;
     let mut rows = stmt.query(params![id])?;
 
     if let Some(row) = rows.next()? {
-        Ok(Some(Info {
-            id: row.get(0)?,
-            item_code: row.get(1)?,
-            name: row.get(2)?,
-            details: row.get(3)?,
-        }))
+        Ok(Some(map_info(row)?))
     } else {
         Ok(None)
     }
 }
 
 pub fn update_info(conn: &Connection, id: &str, item_code: &str, name: &str, details: &str) -> Result<()> {
+    // Valor que voltou do formulário sem mudança já está na forma gravada.
+    let (attribute_id, name, details) = match get_info(conn, id)? {
+        Some(current) if current.details == details && current.name.to_lowercase() == name.to_lowercase() => {
+            (current.attribute_id, current.name, current.details)
+        }
+        _ => apply_attribute(conn, name, details)?,
+    };
     conn.execute(
-        "UPDATE infos SET item_code = ?1, name = ?2, details = ?3 WHERE id = ?4",
-        params![item_code, name, details, id],
+        "UPDATE infos SET item_code = ?1, name = ?2, details = ?3, attribute_id = ?4, updated_at = datetime('now')
+         WHERE id = ?5",
+        params![item_code, name, details, attribute_id, id],
     )?;
     Ok(())
 }
 
 pub fn delete_info(conn: &Connection, id: &str) -> Result<()> {
-    println!("Deleting info with id: {}", id);
     conn.execute("DELETE FROM infos WHERE id = ?1", params![id])?;
     Ok(())
 }
 
+/// Busca a info de um item pelo nome, sem diferenciar maiúsculas.
+pub fn find_info_by_name(conn: &Connection, item_code: &str, name: &str) -> Result<Option<Info>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM infos WHERE item_code = ?1 AND lower(name) = lower(?2)",
+        INFO_COLUMNS
+    ))?;
+    let mut rows = stmt.query(params![item_code, name])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_info(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
 pub fn list_infos(conn: &Connection, item_code: &str) -> Result<Vec<Info>> {
-    let mut stmt = conn.prepare("SELECT id, item_code, name, details FROM infos WHERE item_code = ?1")?;
-    let info_iter = stmt.query_map([item_code], |row| {
-        Ok(Info {
-            id: row.get(0)?,
-            item_code: row.get(1)?,
-            name: row.get(2)?,
-            details: row.get(3)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM infos WHERE item_code = ?1", INFO_COLUMNS))?;
+    let info_iter = stmt.query_map([item_code], map_info)?;
 
     let mut infos = Vec::new();
     for info in info_iter {
@@ -1,7 +1,11 @@
-use rusqlite::{params, Connection, Error, Result};
+use rusqlite::{params, Connection, Error, OptionalExtension, Row};
 use uuid::Uuid;
+use crate::db::{template_repository, validation_error, Result};
+use crate::i18n;
 use crate::models::item::Item;
 
+pub const ITEM_COLUMNS: &str = "id, code, description, section_id, image_path, created_at, updated_at";
+
 pub fn create_table(conn: &Connection) -> Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS items (
@@ -10,6 +14,8 @@ pub fn create_table(conn: &Connection) -> Result<()> {
             description TEXT NOT NULL,
             section_id TEXT NOT NULL,
             image_path TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
             FOREIGN KEY (section_id) REFERENCES sections(id) ON DELETE CASCADE
         )",
         [],
@@ -17,60 +23,105 @@ pub fn create_table(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
-pub fn insert_item(conn: &Connection, code: String, description: String, section_id: String, image_path: String) -> Result<()> {
+pub fn map_item(row: &Row) -> rusqlite::Result<Item> {
+    Ok(Item {
+        id: row.get(0)?,
+        code: row.get(1)?,
+        description: row.get(2)?,
+        section_id: row.get(3)?,
+        image_path: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+/// Insere o item, já com as infos do template da seção, e devolve o id gerado.
+pub fn insert_item(conn: &Connection, code: String, description: String, section_id: String, image_path: String) -> Result<String> {
+    // A UNIQUE da tabela só cobre os itens; variantes usam o mesmo espaço de códigos.
+    if variant_code_exists(conn, &code)? {
+        return Err(validation_error(i18n::message("code_exists", &[&code])));
+    }
     let uuid = Uuid::new_v4().to_string();
      let rows_affected = conn.execute(
-        "INSERT INTO items (id, code, description, section_id, image_path) VALUES (?1, ?2, ?3, ?4, ?5)",
+        "INSERT INTO items (id, code, description, section_id, image_path, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'), datetime('now'))",
         params![uuid, code, description, section_id, image_path],
     )?;
     if rows_affected == 0 {
-        Err(Error::StatementChangedRows(0))
-    } else {
-        Ok(())
+        return Err(Error::StatementChangedRows(0).into());
     }
+
+    template_repository::populate_item(conn, &section_id, &code, false)?;
+    Ok(uuid)
 }
 
 pub fn get_item(conn: &Connection, id: &str) -> Result<Option<Item>> {
-    let mut stmt = conn.prepare("SELECT id, code, description, section_id, image_path FROM items WHERE id = ?1")?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM items WHERE id = ?1", ITEM_COLUMNS))?;
     let mut rows = stmt.query(params![id])?;
 
     if let Some(row) = rows.next()? {
-        Ok(Some(Item {
-            id: row.get(0)?,
-            code: row.get(1)?,
-            description: row.get(2)?,
-            section_id: row.get(3)?,
-            image_path: row.get(4)?,
-        }))
+        Ok(Some(map_item(row)?))
     } else {
         Ok(None)
     }
 }
 
-pub fn update_item(conn: &Connection, id: String, code: String, description: String, section_id: String, image_path: String) -> Result<()> {
-    conn.execute(
-        "UPDATE items SET code=?1, description = ?2, section_id = ?3, image_path = ?4 WHERE id = ?5",
+/// Atualiza o item. As infos apontam para o código, então mudam junto; a
+/// checagem da chave estrangeira fica para o commit, quando os dois lados já batem.
+pub fn update_item(conn: &mut Connection, id: String, code: String, description: String, section_id: String, image_path: String) -> Result<()> {
+    if variant_code_exists(conn, &code)? {
+        return Err(validation_error(i18n::message("code_exists", &[&code])));
+    }
+    let tx = conn.transaction()?;
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+
+    let previous_code: Option<String> = tx
+        .query_row("SELECT code FROM items WHERE id = ?1", params![id], |row| row.get(0))
+        .optional()?;
+    tx.execute(
+        "UPDATE items SET code=?1, description = ?2, section_id = ?3, image_path = ?4, updated_at = datetime('now') WHERE id = ?5",
         params![code, description, section_id, image_path, id],
     )?;
+    if let Some(previous_code) = previous_code.filter(|previous| *previous != code) {
+        tx.execute("UPDATE infos SET item_code = ?1 WHERE item_code = ?2", params![code, previous_code])?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn update_image_path(conn: &Connection, id: &str, image_path: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET image_path = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![image_path, id],
+    )?;
     Ok(())
 }
 
+/// Remove o item; infos, variantes e tags caem junto pelo `ON DELETE CASCADE`.
 pub fn delete_item(conn: &Connection, id: &str) -> Result<()> {
     conn.execute("DELETE FROM items WHERE id = ?1", params![id])?;
     Ok(())
 }
 
 pub fn list_items(conn: &Connection, section_id: &str) -> Result<Vec<Item>> {
-    let mut stmt = conn.prepare("SELECT id, code, description, section_id, image_path FROM items WHERE section_id = ?1")?;
-    let item_iter = stmt.query_map([section_id], |row| {
-        Ok(Item {
-            id: row.get(0)?,
-            code: row.get(1)?,
-            description: row.get(2)?,
-            section_id: row.get(3)?,
-            image_path: row.get(4)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM items WHERE section_id = ?1", ITEM_COLUMNS))?;
+    let item_iter = stmt.query_map([section_id], map_item)?;
+
+    let mut items = Vec::new();
+    for item in item_iter {
+        items.push(item?);
+    }
+    Ok(items)
+}
+
+/// Itens criados nos últimos `days` dias, mais recentes primeiro.
+pub fn list_new_items(conn: &Connection, days: u32) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM items WHERE created_at >= datetime('now', ?1) ORDER BY created_at DESC",
+        ITEM_COLUMNS
+    ))?;
+    let item_iter = stmt.query_map([format!("-{} days", days)], map_item)?;
 
     let mut items = Vec::new();
     for item in item_iter {
@@ -79,8 +130,43 @@ pub fn list_items(conn: &Connection, section_id: &str) -> Result<Vec<Item>> {
     Ok(items)
 }
 
+/// Código em uso por um item ou por uma variante.
+pub fn code_exists(conn: &Connection, code: &str) -> Result<bool> {
+    let count: i32 = conn.query_row("SELECT COUNT(*) FROM items WHERE code = ?1", [code], |row| row.get(0))?;
+    Ok(count > 0 || variant_code_exists(conn, code)?)
+}
+
+fn variant_code_exists(conn: &Connection, code: &str) -> Result<bool> {
+    let count: i32 = conn.query_row("SELECT COUNT(*) FROM variants WHERE code = ?1", [code], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
 pub fn has_items(conn: &Connection) -> Result<bool> {
     let mut stmt = conn.prepare("SELECT COUNT(*) FROM items")?;
     let count: i32 = stmt.query_row([], |row| row.get(0))?;
     Ok(count > 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{section_repository, variant_repository, DbError};
+    use crate::models::variant::VariantInput;
+
+    #[test]
+    fn codes_are_shared_with_variants() {
+        let mut conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        let id = insert_item(&conn, "AN-1".into(), "Anel".into(), section_id.clone(), String::new()).unwrap();
+        let variant = VariantInput { code: "AN-1-16".into(), ..Default::default() };
+        variant_repository::insert_variant(&conn, &id, &variant).unwrap();
+
+        assert!(code_exists(&conn, "AN-1-16").unwrap());
+        let error = insert_item(&conn, "AN-1-16".into(), "Outro".into(), section_id.clone(), String::new()).unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+        let error = update_item(&mut conn, id, "AN-1-16".into(), "Anel".into(), section_id, String::new()).unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+    }
+}
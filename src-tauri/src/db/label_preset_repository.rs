@@ -0,0 +1,97 @@
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+use crate::catalog::options::LabelSheet;
+use crate::db::{from_json, to_json, validation_error, Result};
+use crate::i18n;
+use crate::models::label_preset::LabelPreset;
+
+const LABEL_PRESET_COLUMNS: &str = "id, name, sheet, created_at, updated_at";
+
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS label_presets (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            sheet TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn map_label_preset(row: &Row) -> rusqlite::Result<LabelPreset> {
+    Ok(LabelPreset {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        sheet: from_json(2, row.get(2)?)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+/// Salva as medidas com o nome dado; se já existir um preset com o nome, ele é substituído.
+/// Devolve o id do preset.
+pub fn save_label_preset(conn: &Connection, name: &str, sheet: &LabelSheet) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(validation_error(i18n::text("preset_name_empty")));
+    }
+    sheet.validate().map_err(validation_error)?;
+
+    if let Some(existing) = get_label_preset_by_name(conn, name)? {
+        conn.execute(
+            "UPDATE label_presets SET sheet = ?1, updated_at = datetime('now') WHERE id = ?2",
+            params![to_json(sheet)?, existing.id],
+        )?;
+        return Ok(existing.id);
+    }
+
+    let uuid = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO label_presets (id, name, sheet, created_at, updated_at)
+         VALUES (?1, ?2, ?3, datetime('now'), datetime('now'))",
+        params![uuid, name, to_json(sheet)?],
+    )?;
+    Ok(uuid)
+}
+
+pub fn get_label_preset(conn: &Connection, id: &str) -> Result<Option<LabelPreset>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM label_presets WHERE id = ?1", LABEL_PRESET_COLUMNS))?;
+    let mut rows = stmt.query(params![id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_label_preset(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn get_label_preset_by_name(conn: &Connection, name: &str) -> Result<Option<LabelPreset>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM label_presets WHERE name = ?1", LABEL_PRESET_COLUMNS))?;
+    let mut rows = stmt.query(params![name.trim()])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_label_preset(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn list_label_presets(conn: &Connection) -> Result<Vec<LabelPreset>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM label_presets ORDER BY name", LABEL_PRESET_COLUMNS))?;
+    let preset_iter = stmt.query_map([], map_label_preset)?;
+
+    let mut presets = Vec::new();
+    for preset in preset_iter {
+        presets.push(preset?);
+    }
+    Ok(presets)
+}
+
+pub fn delete_label_preset(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute("DELETE FROM label_presets WHERE id = ?1", params![id])?;
+    Ok(())
+}
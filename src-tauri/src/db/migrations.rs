@@ -0,0 +1,135 @@
+use rusqlite::{Connection, Result};
+
+/// Ajustes de esquema para bancos criados por versões anteriores do app.
+///
+/// `CREATE TABLE IF NOT EXISTS` não altera tabelas existentes, então cada
+/// coluna nova precisa ser adicionada aqui também.
+pub fn run_migrations(conn: &Connection) -> Result<()> {
+    for table in ["sections", "items", "infos"] {
+        add_timestamps(conn, table)?;
+    }
+    add_column_if_missing(conn, "infos", "attribute_id", "TEXT")?;
+    for column in ["parent_id", "description", "cover_image", "palette", "layout"] {
+        add_column_if_missing(conn, "sections", column, "TEXT")?;
+    }
+    remove_orphans(conn)?;
+    run_data_migrations(conn)
+}
+
+// Mudanças nos dados que só podem rodar uma vez, na ordem da lista.
+// `PRAGMA user_version` guarda quantas o banco já recebeu; novas entram no fim.
+const DATA_MIGRATIONS: [fn(&Connection) -> Result<()>; 1] = [move_info_name_translations];
+
+fn run_data_migrations(conn: &Connection) -> Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in DATA_MIGRATIONS.iter().enumerate().skip(version) {
+        migration(conn)?;
+        conn.pragma_update(None, "user_version", index + 1)?;
+    }
+    Ok(())
+}
+
+// Nomes de info com atributo eram traduzidos em cada info; agora a tradução
+// fica no atributo e vale para todas. Infos livres mantêm as suas.
+fn move_info_name_translations(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "INSERT OR IGNORE INTO translations (entity_type, entity_id, field, locale, value, updated_at)
+         SELECT 'attribute', infos.attribute_id, 'name', translations.locale, translations.value, translations.updated_at
+         FROM translations JOIN infos ON infos.id = translations.entity_id
+         WHERE translations.entity_type = 'info' AND translations.field = 'name' AND infos.attribute_id IS NOT NULL;
+         DELETE FROM translations WHERE entity_type = 'info' AND field = 'name'
+                                    AND entity_id IN (SELECT id FROM infos WHERE attribute_id IS NOT NULL);",
+    )
+}
+
+// Antes de `foreign_keys` ser ligado, apagar uma seção ou um item deixava
+// para trás as linhas que dependiam dele. O SQLite não confere linhas já
+// gravadas, então elas são removidas aqui. Itens sem seção ficavam escondidos
+// e ainda prendiam o código, então saem primeiro.
+fn remove_orphans(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "DELETE FROM items WHERE section_id NOT IN (SELECT id FROM sections);
+         DELETE FROM infos WHERE item_code NOT IN (SELECT code FROM items);
+         DELETE FROM variant_axes WHERE item_id NOT IN (SELECT id FROM items);
+         DELETE FROM variants WHERE item_id NOT IN (SELECT id FROM items);
+         DELETE FROM item_tags WHERE item_id NOT IN (SELECT id FROM items)
+                                  OR tag_id NOT IN (SELECT id FROM tags);
+         DELETE FROM section_templates WHERE section_id NOT IN (SELECT id FROM sections);
+         DELETE FROM translations WHERE entity_type = 'section' AND entity_id NOT IN (SELECT id FROM sections);
+         DELETE FROM translations WHERE entity_type = 'item' AND entity_id NOT IN (SELECT id FROM items);
+         DELETE FROM translations WHERE entity_type = 'info' AND entity_id NOT IN (SELECT id FROM infos);",
+    )
+}
+
+pub fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+
+    for name in names {
+        if name? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    if !column_exists(conn, table, column)? {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
+
+// SQLite não aceita DEFAULT com expressão em ALTER TABLE, então as linhas
+// antigas recebem a data da migração.
+fn add_timestamps(conn: &Connection, table: &str) -> Result<()> {
+    add_column_if_missing(conn, table, "created_at", "TEXT")?;
+    add_column_if_missing(conn, table, "updated_at", "TEXT")?;
+    conn.execute(
+        &format!(
+            "UPDATE {} SET created_at = COALESCE(created_at, datetime('now')),
+                           updated_at = COALESCE(updated_at, created_at, datetime('now'))
+             WHERE created_at IS NULL OR updated_at IS NULL",
+            table
+        ),
+        [],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{info_repository, item_repository, section_repository};
+
+    #[test]
+    fn items_left_by_deleted_sections_are_removed() {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id.clone(), String::new()).unwrap();
+        info_repository::insert_info(&conn, "peso", "AN-1", "Peso", "4 g").unwrap();
+
+        // Como nas versões sem `foreign_keys`: a seção some e o item fica.
+        conn.pragma_update(None, "foreign_keys", false).unwrap();
+        conn.execute("DELETE FROM sections WHERE id = ?1", [&section_id]).unwrap();
+        conn.pragma_update(None, "foreign_keys", true).unwrap();
+        assert!(item_repository::code_exists(&conn, "AN-1").unwrap());
+
+        run_migrations(&conn).unwrap();
+        assert!(!item_repository::code_exists(&conn, "AN-1").unwrap());
+        assert!(info_repository::get_info(&conn, "peso").unwrap().is_none());
+    }
+
+    #[test]
+    fn deleting_a_section_removes_its_items() {
+        let conn = open_in_memory();
+        section_repository::insert_section(&conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(&conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id.clone(), String::new()).unwrap();
+
+        section_repository::delete_section(&conn, &section_id).unwrap();
+        assert!(!item_repository::code_exists(&conn, "AN-1").unwrap());
+    }
+}
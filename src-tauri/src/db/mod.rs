@@ -1,4 +1,58 @@
+use std::fmt;
+
 pub mod connection;
+pub mod migrations;
 pub mod section_repository;
 pub mod item_repository;
-pub mod info_repository;
\ No newline at end of file
+pub mod info_repository;
+pub mod variant_repository;
+pub mod tag_repository;
+pub mod attribute_repository;
+pub mod template_repository;
+pub mod bulk_repository;
+pub mod duplicate_repository;
+pub mod preset_repository;
+pub mod label_preset_repository;
+pub mod translation_repository;
+
+/// Erro dos repositórios: falha do SQLite ou regra de negócio violada
+/// (valor inválido, ciclo etc.). A mensagem de validação chega ao frontend
+/// sem prefixo pelo `to_string()` dos comandos.
+#[derive(Debug)]
+pub enum DbError {
+    Sqlite(rusqlite::Error),
+    Validation(String),
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DbError::Sqlite(error) => write!(f, "{}", error),
+            DbError::Validation(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(error: rusqlite::Error) -> Self {
+        DbError::Sqlite(error)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, DbError>;
+
+pub fn validation_error(message: impl Into<String>) -> DbError {
+    DbError::Validation(message.into())
+}
+
+/// Listas, mapas e opções ficam como JSON em colunas TEXT.
+pub fn from_json<T: serde::de::DeserializeOwned>(index: usize, text: String) -> rusqlite::Result<T> {
+    serde_json::from_str(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+pub fn to_json<T: serde::Serialize>(value: &T) -> rusqlite::Result<String> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
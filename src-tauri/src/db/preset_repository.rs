@@ -0,0 +1,113 @@
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+use crate::catalog::CatalogOptions;
+use crate::db::{from_json, to_json, validation_error, Result};
+use crate::i18n;
+use crate::models::preset::CatalogPreset;
+
+const PRESET_COLUMNS: &str = "id, name, options, created_at, updated_at";
+
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS catalog_presets (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            options TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn map_preset(row: &Row) -> rusqlite::Result<CatalogPreset> {
+    Ok(CatalogPreset {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        options: from_json(2, row.get(2)?)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+/// Salva as opções com o nome dado; se já existir um preset com o nome, ele é substituído.
+/// Devolve o id do preset.
+pub fn save_preset(conn: &Connection, name: &str, options: &CatalogOptions) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(validation_error(i18n::text("preset_name_empty")));
+    }
+
+    if let Some(existing) = get_preset_by_name(conn, name)? {
+        conn.execute(
+            "UPDATE catalog_presets SET options = ?1, updated_at = datetime('now') WHERE id = ?2",
+            params![to_json(options)?, existing.id],
+        )?;
+        return Ok(existing.id);
+    }
+
+    let uuid = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO catalog_presets (id, name, options, created_at, updated_at)
+         VALUES (?1, ?2, ?3, datetime('now'), datetime('now'))",
+        params![uuid, name, to_json(options)?],
+    )?;
+    Ok(uuid)
+}
+
+pub fn get_preset(conn: &Connection, id: &str) -> Result<Option<CatalogPreset>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM catalog_presets WHERE id = ?1", PRESET_COLUMNS))?;
+    let mut rows = stmt.query(params![id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_preset(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn get_preset_by_name(conn: &Connection, name: &str) -> Result<Option<CatalogPreset>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM catalog_presets WHERE name = ?1", PRESET_COLUMNS))?;
+    let mut rows = stmt.query(params![name.trim()])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_preset(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn list_presets(conn: &Connection) -> Result<Vec<CatalogPreset>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM catalog_presets ORDER BY name", PRESET_COLUMNS))?;
+    let preset_iter = stmt.query_map([], map_preset)?;
+
+    let mut presets = Vec::new();
+    for preset in preset_iter {
+        presets.push(preset?);
+    }
+    Ok(presets)
+}
+
+/// Renomeia e/ou troca as opções do preset.
+pub fn update_preset(conn: &Connection, id: &str, name: &str, options: &CatalogOptions) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(validation_error(i18n::text("preset_name_empty")));
+    }
+    if get_preset_by_name(conn, name)?.is_some_and(|other| other.id != id) {
+        return Err(validation_error(i18n::message("preset_name_taken", &[&name])));
+    }
+
+    conn.execute(
+        "UPDATE catalog_presets SET name = ?1, options = ?2, updated_at = datetime('now') WHERE id = ?3",
+        params![name, to_json(options)?, id],
+    )?;
+    Ok(())
+}
+
+pub fn delete_preset(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute("DELETE FROM catalog_presets WHERE id = ?1", params![id])?;
+    Ok(())
+}
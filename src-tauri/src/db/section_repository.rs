@@ -1,78 +1,121 @@
-use rusqlite::{params, Connection, Result};
-use crate::models::section::Section;
+use rusqlite::{params, Connection, Row};
+use crate::db::{validation_error, Result};
+use crate::i18n;
+use crate::models::section::{Section, SectionDetails, SectionNode};
+
+const SECTION_COLUMNS: &str = "id, name, parent_id, description, cover_image, palette, layout, created_at, updated_at";
 
 pub fn create_table(conn: &Connection) -> Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS sections (
             id TEXT PRIMARY KEY,
-            name TEXT NOT NULL UNIQUE
+            name TEXT NOT NULL UNIQUE,
+            parent_id TEXT,
+            description TEXT,
+            cover_image TEXT,
+            palette TEXT,
+            layout TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
         )",
         [],
     )?;
     Ok(())
 }
 
-pub fn insert_section(conn: &Connection, name: &str) -> Result<()> {
+fn map_section(row: &Row) -> rusqlite::Result<Section> {
+    Ok(Section {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        parent_id: row.get(2)?,
+        description: row.get(3)?,
+        cover_image: row.get(4)?,
+        palette: row.get(5)?,
+        layout: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+pub fn insert_section(conn: &Connection, name: &str, parent_id: Option<&str>, details: &SectionDetails) -> Result<()> {
     create_table(conn)?;
+    if let Some(parent_id) = parent_id {
+        if get_section(conn, parent_id)?.is_none() {
+            return Err(validation_error(i18n::text("parent_section_not_found")));
+        }
+    }
+
     let uuid = uuid::Uuid::new_v4().to_string();
     conn.execute(
-        "INSERT INTO sections (id, name) VALUES (?1, ?2)",
-        params![uuid, name],
+        "INSERT INTO sections (id, name, parent_id, description, cover_image, palette, layout, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'), datetime('now'))",
+        params![
+            uuid,
+            name,
+            parent_id,
+            details.description,
+            details.cover_image,
+            details.palette,
+            details.layout,
+        ],
     )?;
     Ok(())
 }
 
 pub fn get_section(conn: &Connection, id: &str) -> Result<Option<Section>> {
-    let mut stmt = conn.prepare("SELECT id, name FROM sections WHERE id = ?1")?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM sections WHERE id = ?1", SECTION_COLUMNS))?;
     let mut rows = stmt.query(params![id])?;
 
     if let Some(row) = rows.next()?
     {
-        Ok(Some(Section {
-            id: row.get(0)?,
-            name: row.get(1)?,
-        }))
+        Ok(Some(map_section(row)?))
     } else {
         Ok(None)
     }
 }
 
 pub fn get_section_by_name(conn: &Connection, name: &str) -> Result<Option<Section>> {
-    let mut stmt = conn.prepare("SELECT id, name FROM sections WHERE name = ?1")?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM sections WHERE name = ?1", SECTION_COLUMNS))?;
     let mut rows = stmt.query(params![name])?;
 
     if let Some(row) = rows.next()?
     {
-        Ok(Some(Section {
-            id: row.get(0)?,
-            name: row.get(1)?,
-        }))
+        Ok(Some(map_section(row)?))
     } else {
         Ok(None)
     }
 }
 
-pub fn update_section(conn: &Connection, uuid: &str, name: &str) -> Result<()> {
+/// Atualiza o nome e, se `details` vier preenchido, os campos do catálogo.
+pub fn update_section(conn: &Connection, uuid: &str, name: &str, details: Option<&SectionDetails>) -> Result<()> {
     conn.execute(
-        "UPDATE sections SET name = ?1 WHERE id = ?2",
+        "UPDATE sections SET name = ?1, updated_at = datetime('now') WHERE id = ?2",
         params![name, uuid],
     )?;
+
+    if let Some(details) = details {
+        conn.execute(
+            "UPDATE sections SET description = ?1, cover_image = ?2, palette = ?3, layout = ?4 WHERE id = ?5",
+            params![details.description, details.cover_image, details.palette, details.layout, uuid],
+        )?;
+    }
     Ok(())
 }
 
+/// Remove a seção; as subseções sobem um nível em vez de serem apagadas. Os
+/// itens da seção (com infos, variantes e tags) vão junto, pelo ON DELETE CASCADE.
 pub fn delete_section(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE sections SET parent_id = (SELECT parent_id FROM sections WHERE id = ?1) WHERE parent_id = ?1",
+        params![id],
+    )?;
     conn.execute("DELETE FROM sections WHERE id = ?1", params![id])?;
     Ok(())
 }
 
 pub fn list_sections(conn: &Connection) -> Result<Vec<Section>> {
-    let mut stmt = conn.prepare("SELECT id, name FROM sections")?;
-    let section_iter = stmt.query_map([], |row| {
-        Ok(Section {
-            id: row.get(0)?,
-            name: row.get(1)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM sections", SECTION_COLUMNS))?;
+    let section_iter = stmt.query_map([], map_section)?;
 
     let mut sections = Vec::new();
     for section in section_iter {
@@ -81,8 +124,64 @@ pub fn list_sections(conn: &Connection) -> Result<Vec<Section>> {
     Ok(sections)
 }
 
+pub fn list_children(conn: &Connection, parent_id: Option<&str>) -> Result<Vec<Section>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM sections WHERE parent_id IS ?1 ORDER BY name COLLATE NOCASE",
+        SECTION_COLUMNS
+    ))?;
+    let section_iter = stmt.query_map([parent_id], map_section)?;
+
+    let mut sections = Vec::new();
+    for section in section_iter {
+        sections.push(section?);
+    }
+    Ok(sections)
+}
+
+/// Árvore completa de seções, ordenada por nome em cada nível.
+pub fn list_section_tree(conn: &Connection) -> Result<Vec<SectionNode>> {
+    let mut sections = list_sections(conn)?;
+    sections.sort_by_key(|section| section.name.to_lowercase());
+    Ok(build_tree(&sections, None))
+}
+
+fn build_tree(sections: &[Section], parent_id: Option<&str>) -> Vec<SectionNode> {
+    sections
+        .iter()
+        .filter(|section| section.parent_id.as_deref() == parent_id)
+        .map(|section| SectionNode {
+            section: section.clone(),
+            children: build_tree(sections, Some(&section.id)),
+        })
+        .collect()
+}
+
+/// Move a seção (com toda a subárvore) para baixo de `new_parent_id`, ou para a raiz.
+/// Recusa mover uma seção para dentro dela mesma ou de uma descendente.
+pub fn move_section(conn: &Connection, id: &str, new_parent_id: Option<&str>) -> Result<()> {
+    if get_section(conn, id)?.is_none() {
+        return Err(validation_error(i18n::text("section_not_found")));
+    }
+
+    let mut ancestor = new_parent_id.map(str::to_string);
+    while let Some(current) = ancestor {
+        if current == id {
+            return Err(validation_error(i18n::text("section_into_itself")));
+        }
+        ancestor = get_section(conn, &current)?
+            .ok_or_else(|| validation_error(i18n::text("parent_section_not_found")))?
+            .parent_id;
+    }
+
+    conn.execute(
+        "UPDATE sections SET parent_id = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![new_parent_id, id],
+    )?;
+    Ok(())
+}
+
 pub fn has_sections(db: &Connection) -> Result<bool> {
     let mut stmt = db.prepare("SELECT count(*) FROM sections")?;
     let count: i32 = stmt.query_row([], |row| row.get(0))?;
     Ok(count > 0)
-}
\ No newline at end of file
+}
@@ -0,0 +1,195 @@
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+use crate::db::item_repository::{self, map_item, ITEM_COLUMNS};
+use crate::db::{validation_error, Result};
+use crate::i18n;
+use crate::models::item::Item;
+use crate::models::tag::Tag;
+
+const TAG_COLUMNS: &str = "id, name, created_at, updated_at";
+
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_tags (
+            item_id TEXT NOT NULL,
+            tag_id TEXT NOT NULL,
+            PRIMARY KEY (item_id, tag_id),
+            FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn map_tag(row: &Row) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+        updated_at: row.get(3)?,
+    })
+}
+
+/// Insere a tag e devolve o id gerado.
+pub fn insert_tag(conn: &Connection, name: &str) -> Result<String> {
+    let uuid = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tags (id, name, created_at, updated_at) VALUES (?1, ?2, datetime('now'), datetime('now'))",
+        params![uuid, name],
+    )?;
+    Ok(uuid)
+}
+
+pub fn get_tag(conn: &Connection, id: &str) -> Result<Option<Tag>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM tags WHERE id = ?1", TAG_COLUMNS))?;
+    let mut rows = stmt.query(params![id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_tag(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn list_tags(conn: &Connection) -> Result<Vec<Tag>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM tags ORDER BY name", TAG_COLUMNS))?;
+    let tag_iter = stmt.query_map([], map_tag)?;
+
+    let mut tags = Vec::new();
+    for tag in tag_iter {
+        tags.push(tag?);
+    }
+    Ok(tags)
+}
+
+pub fn update_tag(conn: &Connection, id: &str, name: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE tags SET name = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![name, id],
+    )?;
+    Ok(())
+}
+
+/// Remove a tag; as ligações com os itens caem pelo `ON DELETE CASCADE`.
+pub fn delete_tag(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute("DELETE FROM tags WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Liga a tag ao item; repetir a ligação não faz nada.
+pub fn add_item_tag(conn: &Connection, item_id: &str, tag_id: &str) -> Result<()> {
+    if item_repository::get_item(conn, item_id)?.is_none() {
+        return Err(validation_error(i18n::text("item_not_found")));
+    }
+    if get_tag(conn, tag_id)?.is_none() {
+        return Err(validation_error(i18n::text("tag_not_found")));
+    }
+    conn.execute(
+        "INSERT OR IGNORE INTO item_tags (item_id, tag_id) VALUES (?1, ?2)",
+        params![item_id, tag_id],
+    )?;
+    Ok(())
+}
+
+pub fn remove_item_tag(conn: &Connection, item_id: &str, tag_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM item_tags WHERE item_id = ?1 AND tag_id = ?2",
+        params![item_id, tag_id],
+    )?;
+    Ok(())
+}
+
+pub fn list_item_tags(conn: &Connection, item_id: &str) -> Result<Vec<Tag>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.name, t.created_at, t.updated_at FROM tags t
+         JOIN item_tags it ON it.tag_id = t.id
+         WHERE it.item_id = ?1 ORDER BY t.name",
+    )?;
+    let tag_iter = stmt.query_map([item_id], map_tag)?;
+
+    let mut tags = Vec::new();
+    for tag in tag_iter {
+        tags.push(tag?);
+    }
+    Ok(tags)
+}
+
+/// Itens com a tag, de qualquer seção.
+pub fn list_items_by_tag(conn: &Connection, tag_id: &str) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM items WHERE id IN (SELECT item_id FROM item_tags WHERE tag_id = ?1) ORDER BY code",
+        ITEM_COLUMNS
+    ))?;
+    let item_iter = stmt.query_map([tag_id], map_item)?;
+
+    let mut items = Vec::new();
+    for item in item_iter {
+        items.push(item?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::options::{CatalogOptions, CatalogSelection};
+    use crate::catalog::build_catalog;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{section_repository, DbError};
+
+    fn item(conn: &Connection) -> String {
+        section_repository::insert_section(conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(conn, "Anéis").unwrap().unwrap().id;
+        item_repository::insert_item(conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap()
+    }
+
+    #[test]
+    fn deleting_a_tag_removes_its_links() {
+        let conn = open_in_memory();
+        let item_id = item(&conn);
+        let tag = insert_tag(&conn, "Promoção").unwrap();
+        add_item_tag(&conn, &item_id, &tag).unwrap();
+
+        delete_tag(&conn, &tag).unwrap();
+        let links: i64 = conn.query_row("SELECT COUNT(*) FROM item_tags", [], |row| row.get(0)).unwrap();
+        assert_eq!(links, 0);
+        assert!(list_item_tags(&conn, &item_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn linking_requires_item_and_tag() {
+        let conn = open_in_memory();
+        let item_id = item(&conn);
+        let tag = insert_tag(&conn, "Promoção").unwrap();
+
+        assert!(matches!(add_item_tag(&conn, "missing", &tag), Err(DbError::Validation(_))));
+        assert!(matches!(add_item_tag(&conn, &item_id, "missing"), Err(DbError::Validation(_))));
+        add_item_tag(&conn, &item_id, &tag).unwrap();
+        add_item_tag(&conn, &item_id, &tag).unwrap();
+        assert_eq!(list_item_tags(&conn, &item_id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn unknown_tag_in_the_catalog_is_an_error() {
+        let conn = open_in_memory();
+        item(&conn);
+
+        let by_tag = CatalogOptions { tag_id: Some("missing".into()), ..Default::default() };
+        assert!(matches!(build_catalog(&conn, &by_tag), Err(DbError::Validation(_))));
+
+        let selection = CatalogSelection { tag_id: Some("missing".into()), ..Default::default() };
+        let filtered = CatalogOptions { selection: Some(selection), ..Default::default() };
+        assert!(matches!(build_catalog(&conn, &filtered), Err(DbError::Validation(_))));
+    }
+}
@@ -0,0 +1,183 @@
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+use crate::db::{attribute_repository, info_repository, item_repository, validation_error, DbError, Result};
+use crate::models::bulk::BulkSummary;
+use crate::models::template::{TemplateField, TemplateFieldInput};
+
+const TEMPLATE_COLUMNS: &str = "id, section_id, name, default_value, position";
+
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS section_templates (
+            id TEXT PRIMARY KEY,
+            section_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            default_value TEXT,
+            position INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (section_id) REFERENCES sections(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn map_field(row: &Row) -> rusqlite::Result<TemplateField> {
+    Ok(TemplateField {
+        id: row.get(0)?,
+        section_id: row.get(1)?,
+        name: row.get(2)?,
+        default_value: row.get(3)?,
+        position: row.get(4)?,
+    })
+}
+
+/// Substitui o template da seção, mantendo a ordem recebida.
+pub fn set_template(conn: &mut Connection, section_id: &str, fields: &[TemplateFieldInput]) -> Result<Vec<TemplateField>> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM section_templates WHERE section_id = ?1", params![section_id])?;
+
+    for (position, field) in fields.iter().enumerate() {
+        insert_field(&tx, section_id, field, position as i64)?;
+    }
+
+    tx.commit()?;
+    list_template(conn, section_id)
+}
+
+/// Grava um campo do template. O valor padrão precisa passar na validação do
+/// atributo de mesmo nome; senão todo item novo da seção falharia ao ser criado.
+pub fn insert_field(conn: &Connection, section_id: &str, field: &TemplateFieldInput, position: i64) -> Result<()> {
+    if let (Some(default_value), Some(attribute)) = (
+        field.default_value.as_deref(),
+        attribute_repository::find_attribute_by_name(conn, field.name.trim())?,
+    ) {
+        attribute.normalize(default_value).map_err(validation_error)?;
+    }
+    let uuid = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO section_templates (id, section_id, name, default_value, position) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![uuid, section_id, field.name.trim(), field.default_value, position],
+    )?;
+    Ok(())
+}
+
+pub fn list_template(conn: &Connection, section_id: &str) -> Result<Vec<TemplateField>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM section_templates WHERE section_id = ?1 ORDER BY position",
+        TEMPLATE_COLUMNS
+    ))?;
+    let field_iter = stmt.query_map([section_id], map_field)?;
+
+    let mut fields = Vec::new();
+    for field in field_iter {
+        fields.push(field?);
+    }
+    Ok(fields)
+}
+
+/// Cria no item as infos do template que ainda não existem.
+/// Com `overwrite_values`, campos com valor padrão também sobrescrevem o valor atual.
+/// Um padrão que deixou de valer (o atributo mudou depois) cria a info vazia.
+/// Devolve se o item foi alterado.
+pub fn populate_item(conn: &Connection, section_id: &str, item_code: &str, overwrite_values: bool) -> Result<bool> {
+    let mut changed = false;
+
+    for field in list_template(conn, section_id)? {
+        let default_value = field.default_value.as_deref().unwrap_or_default();
+        match info_repository::find_info_by_name(conn, item_code, &field.name)? {
+            Some(info) if overwrite_values && field.default_value.is_some() && info.details != default_value => {
+                info_repository::update_info(conn, &info.id, item_code, &info.name, default_value)?;
+                changed = true;
+            }
+            Some(_) => {}
+            None => {
+                let info_id = Uuid::new_v4().to_string();
+                match info_repository::insert_info(conn, &info_id, item_code, &field.name, default_value) {
+                    Err(DbError::Validation(_)) => info_repository::insert_info(conn, &info_id, item_code, &field.name, "")?,
+                    result => result?,
+                }
+                changed = true;
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Aplica o template (atual) a todos os itens da seção.
+pub fn apply_template(conn: &mut Connection, section_id: &str, overwrite_values: bool) -> Result<BulkSummary> {
+    let tx = conn.transaction()?;
+    let items = item_repository::list_items(&tx, section_id)?;
+    let mut summary = BulkSummary { requested: items.len(), ..Default::default() };
+
+    for item in items {
+        if populate_item(&tx, section_id, &item.code, overwrite_values)? {
+            summary.affected += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::build_catalog;
+    use crate::catalog::options::CatalogOptions;
+    use crate::db::connection::open_in_memory;
+    use crate::db::section_repository;
+    use crate::models::attribute::{AttributeInput, AttributeType};
+
+    fn section(conn: &Connection) -> String {
+        section_repository::insert_section(conn, "Anéis", None, &Default::default()).unwrap();
+        section_repository::get_section_by_name(conn, "Anéis").unwrap().unwrap().id
+    }
+
+    fn field(name: &str, default_value: Option<&str>) -> TemplateFieldInput {
+        TemplateFieldInput { name: name.into(), default_value: default_value.map(str::to_string) }
+    }
+
+    fn number_attribute(conn: &Connection, name: &str) -> String {
+        let input = AttributeInput { name: name.into(), kind: AttributeType::Number, unit: None, allowed_values: Vec::new() };
+        attribute_repository::insert_attribute(conn, &input).unwrap()
+    }
+
+    #[test]
+    fn invalid_defaults_are_rejected_when_saved() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn);
+        number_attribute(&conn, "Peso");
+        set_template(&mut conn, &section_id, &[field("Metal", Some("Ouro"))]).unwrap();
+
+        let error = set_template(&mut conn, &section_id, &[field("Metal", None), field("Peso", Some("leve"))]).unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+        // O template anterior continua valendo.
+        assert_eq!(list_template(&conn, &section_id).unwrap()[0].name, "Metal");
+    }
+
+    #[test]
+    fn defaults_invalidated_later_do_not_block_new_items() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn);
+        set_template(&mut conn, &section_id, &[field("Peso", Some("leve"))]).unwrap();
+        number_attribute(&conn, "Peso");
+
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap();
+        let info = info_repository::find_info_by_name(&conn, "AN-1", "Peso").unwrap().unwrap();
+        assert_eq!(info.details, "");
+    }
+
+    #[test]
+    fn empty_infos_are_only_hidden_on_request() {
+        let mut conn = open_in_memory();
+        let section_id = section(&conn);
+        set_template(&mut conn, &section_id, &[field("Metal", None), field("Banho", Some("Ródio"))]).unwrap();
+        item_repository::insert_item(&conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap();
+
+        let infos = |options: &CatalogOptions| build_catalog(&conn, options).unwrap().sections[0].items[0].infos.len();
+        assert_eq!(infos(&CatalogOptions::default()), 2);
+        assert_eq!(infos(&CatalogOptions { hide_empty_infos: true, ..Default::default() }), 1);
+    }
+}
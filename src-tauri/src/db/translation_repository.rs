@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection, Row};
+
+use crate::db::{validation_error, Result};
+use crate::i18n;
+use crate::models::translation::Translation;
+
+const TRANSLATION_COLUMNS: &str = "entity_type, entity_id, field, locale, value, updated_at";
+
+/// Campos que aceitam tradução, por tipo de registro. O nome de uma info com
+/// atributo é traduzido uma vez, no atributo; o de uma info livre, nela mesma.
+/// O valor só é traduzido em infos livres ou de atributos de texto.
+pub const TRANSLATABLE_FIELDS: [(&str, &str); 6] = [
+    ("section", "name"),
+    ("section", "description"),
+    ("item", "description"),
+    ("attribute", "name"),
+    ("info", "name"),
+    ("info", "details"),
+];
+
+// Tabela de cada tipo de registro traduzível.
+const ENTITY_TABLES: [(&str, &str); 4] = [("section", "sections"), ("item", "items"), ("attribute", "attributes"), ("info", "infos")];
+
+/// Chave das traduções carregadas: (tipo, id, campo).
+pub type TranslationKey = (String, String, String);
+
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS translations (
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            field TEXT NOT NULL,
+            locale TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (entity_type, entity_id, field, locale)
+        )",
+        [],
+    )?;
+
+    // As traduções apontam para tabelas diferentes, então não dá para usar
+    // FOREIGN KEY; os gatilhos apagam as traduções junto com o registro. As
+    // exclusões em cascata (itens da seção, infos do item) também disparam os
+    // gatilhos, porque `create_schema` liga `foreign_keys`.
+    for (entity_type, table) in ENTITY_TABLES {
+        conn.execute(
+            &format!(
+                "CREATE TRIGGER IF NOT EXISTS delete_{entity_type}_translations AFTER DELETE ON {table}
+                 BEGIN
+                     DELETE FROM translations WHERE entity_type = '{entity_type}' AND entity_id = OLD.id;
+                 END",
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn map_translation(row: &Row) -> rusqlite::Result<Translation> {
+    Ok(Translation {
+        entity_type: row.get(0)?,
+        entity_id: row.get(1)?,
+        field: row.get(2)?,
+        locale: row.get(3)?,
+        value: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+fn normalize_locale(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_lowercase()
+}
+
+/// Grava a tradução; valor vazio remove a tradução (volta para o texto original).
+pub fn set_translation(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: &str,
+    field: &str,
+    locale: &str,
+    value: &str,
+) -> Result<()> {
+    if !TRANSLATABLE_FIELDS.contains(&(entity_type, field)) {
+        return Err(validation_error(i18n::message("field_not_translatable", &[&entity_type, &field])));
+    }
+    let locale = normalize_locale(locale);
+    if locale.is_empty() {
+        return Err(validation_error(i18n::text("translation_locale_required")));
+    }
+    if !entity_exists(conn, entity_type, entity_id)? {
+        return Err(validation_error(i18n::text("translation_entity_not_found")));
+    }
+    // O valor de uma info com atributo (número, medida, lista, sim/não) já sai
+    // formatado no idioma do catálogo; uma tradução o substituiria.
+    if entity_type == "info" && field == "details" && has_typed_attribute(conn, entity_id)? {
+        return Err(validation_error(i18n::text("info_value_not_translatable")));
+    }
+    if entity_type == "info" && field == "name" && has_attribute(conn, entity_id)? {
+        return Err(validation_error(i18n::text("info_name_translated_by_attribute")));
+    }
+
+    if value.trim().is_empty() {
+        conn.execute(
+            "DELETE FROM translations WHERE entity_type = ?1 AND entity_id = ?2 AND field = ?3 AND locale = ?4",
+            params![entity_type, entity_id, field, locale],
+        )?;
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO translations (entity_type, entity_id, field, locale, value, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))
+         ON CONFLICT (entity_type, entity_id, field, locale)
+         DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![entity_type, entity_id, field, locale, value],
+    )?;
+    Ok(())
+}
+
+fn entity_exists(conn: &Connection, entity_type: &str, entity_id: &str) -> Result<bool> {
+    let Some((_, table)) = ENTITY_TABLES.iter().find(|(kind, _)| *kind == entity_type) else {
+        return Ok(false);
+    };
+    Ok(conn.query_row(&format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", table), [entity_id], |row| row.get(0))?)
+}
+
+fn has_typed_attribute(conn: &Connection, info_id: &str) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM infos JOIN attributes ON attributes.id = infos.attribute_id
+                       WHERE infos.id = ?1 AND attributes.kind <> 'text')",
+        [info_id],
+        |row| row.get(0),
+    )?)
+}
+
+fn has_attribute(conn: &Connection, info_id: &str) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM infos WHERE id = ?1 AND attribute_id IS NOT NULL)",
+        [info_id],
+        |row| row.get(0),
+    )?)
+}
+
+/// Copia todas as traduções de um registro para outro do mesmo tipo, menos
+/// as dos campos em `except` (ex: o nome de uma seção duplicada, que muda).
+pub fn copy_translations(conn: &Connection, entity_type: &str, from_id: &str, to_id: &str, except: &[&str]) -> Result<()> {
+    for (_, field) in TRANSLATABLE_FIELDS.iter().filter(|(kind, field)| *kind == entity_type && !except.contains(field)) {
+        conn.execute(
+            "INSERT OR REPLACE INTO translations (entity_type, entity_id, field, locale, value, updated_at)
+             SELECT entity_type, ?3, field, locale, value, datetime('now') FROM translations
+             WHERE entity_type = ?1 AND entity_id = ?2 AND field = ?4",
+            params![entity_type, from_id, to_id, field],
+        )?;
+    }
+    Ok(())
+}
+
+/// Todas as traduções de um registro, em todos os idiomas.
+pub fn list_translations(conn: &Connection, entity_type: &str, entity_id: &str) -> Result<Vec<Translation>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM translations WHERE entity_type = ?1 AND entity_id = ?2 ORDER BY field, locale",
+        TRANSLATION_COLUMNS
+    ))?;
+    let translation_iter = stmt.query_map(params![entity_type, entity_id], map_translation)?;
+
+    let mut translations = Vec::new();
+    for translation in translation_iter {
+        translations.push(translation?);
+    }
+    Ok(translations)
+}
+
+/// Traduções de um idioma, já com a regra de fallback: "es-ar" usa as de "es"
+/// onde não houver uma própria. Campos sem tradução ficam de fora (usa-se o
+/// texto original).
+pub fn load_locale(conn: &Connection, locale: &str) -> Result<HashMap<TranslationKey, String>> {
+    let locale = normalize_locale(locale);
+    let base = locale.split('-').next().unwrap_or_default().to_string();
+
+    let mut stmt = conn.prepare(
+        "SELECT entity_type, entity_id, field, value FROM translations
+         WHERE locale = ?1 OR locale = ?2
+         ORDER BY locale = ?1",
+    )?;
+    let rows = stmt.query_map(params![locale, base], |row| {
+        Ok(((row.get(0)?, row.get(1)?, row.get(2)?), row.get(3)?))
+    })?;
+
+    // O idioma completo vem por último e sobrescreve o idioma base.
+    let mut translations = HashMap::new();
+    for row in rows {
+        let (key, value) = row?;
+        translations.insert(key, value);
+    }
+    Ok(translations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::build_catalog;
+    use crate::catalog::options::CatalogOptions;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{attribute_repository, duplicate_repository, info_repository, item_repository, migrations, section_repository, DbError};
+    use crate::models::attribute::{AttributeInput, AttributeType};
+    use crate::models::bulk::CodeSuffixStrategy;
+
+    // Seção "Anéis" com o item AN-1 e as infos "Metal" (texto livre) e "Peso" (número).
+    fn setup(conn: &Connection) -> (String, String, String) {
+        section_repository::insert_section(conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(conn, "Anéis").unwrap().unwrap().id;
+        let item_id = item_repository::insert_item(conn, "AN-1".into(), "Anel".into(), section_id.clone(), String::new()).unwrap();
+        let weight = AttributeInput { name: "Peso".into(), kind: AttributeType::Number, unit: None, allowed_values: Vec::new() };
+        let attribute_id = attribute_repository::insert_attribute(conn, &weight).unwrap();
+        info_repository::insert_info(conn, "metal", "AN-1", "Metal", "Ouro").unwrap();
+        info_repository::insert_info(conn, "peso", "AN-1", "Peso", "4,5").unwrap();
+        (section_id, item_id, attribute_id)
+    }
+
+    fn count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM translations", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn translations_need_an_existing_record() {
+        let conn = open_in_memory();
+        let (_, item_id, _) = setup(&conn);
+        set_translation(&conn, "item", &item_id, "description", "en", "Ring").unwrap();
+
+        let error = set_translation(&conn, "item", "missing", "description", "en", "Ring").unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+        let error = set_translation(&conn, "info", "metal", "price", "en", "10").unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+    }
+
+    #[test]
+    fn info_names_are_translated_once_per_attribute_and_typed_values_keep_their_format() {
+        let conn = open_in_memory();
+        let (_, _, attribute_id) = setup(&conn);
+        set_translation(&conn, "attribute", &attribute_id, "name", "en", "Weight").unwrap();
+        set_translation(&conn, "info", "metal", "name", "en", "Material").unwrap();
+        set_translation(&conn, "info", "metal", "details", "en", "Gold").unwrap();
+        let error = set_translation(&conn, "info", "peso", "details", "en", "light").unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+        let error = set_translation(&conn, "info", "peso", "name", "en", "Weight").unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+
+        let options = CatalogOptions { language: Some("en".into()), ..Default::default() };
+        let catalog = build_catalog(&conn, &options).unwrap();
+        let infos: Vec<(&str, &str)> =
+            catalog.sections[0].items[0].infos.iter().map(|info| (info.name.as_str(), info.details.as_str())).collect();
+        assert!(infos.contains(&("Material", "Gold")));
+        assert!(infos.contains(&("Weight", "4.5")));
+    }
+
+    #[test]
+    fn cascading_deletes_remove_translations() {
+        let conn = open_in_memory();
+        let (section_id, item_id, _) = setup(&conn);
+        set_translation(&conn, "section", &section_id, "name", "en", "Rings").unwrap();
+        set_translation(&conn, "item", &item_id, "description", "en", "Ring").unwrap();
+        set_translation(&conn, "info", "metal", "details", "en", "Gold").unwrap();
+        assert_eq!(count(&conn), 3);
+
+        // Seção -> item -> infos, tudo pelo ON DELETE CASCADE.
+        section_repository::delete_section(&conn, &section_id).unwrap();
+        assert_eq!(count(&conn), 0);
+    }
+
+    #[test]
+    fn duplicates_keep_the_translations() {
+        let mut conn = open_in_memory();
+        let (section_id, item_id, _) = setup(&conn);
+        set_translation(&conn, "section", &section_id, "name", "en", "Rings").unwrap();
+        set_translation(&conn, "section", &section_id, "description", "en", "Gold rings").unwrap();
+        set_translation(&conn, "item", &item_id, "description", "en", "Ring").unwrap();
+        set_translation(&conn, "info", "metal", "details", "en", "Gold").unwrap();
+
+        let copy = duplicate_repository::duplicate_item(&mut conn, &item_id, "AN-2").unwrap().unwrap();
+        assert_eq!(list_translations(&conn, "item", &copy.id).unwrap()[0].value, "Ring");
+        let metal = info_repository::find_info_by_name(&conn, "AN-2", "Metal").unwrap().unwrap();
+        assert_eq!(list_translations(&conn, "info", &metal.id).unwrap()[0].value, "Gold");
+
+        let strategy = CodeSuffixStrategy::default();
+        let (section, _) = duplicate_repository::duplicate_section(&mut conn, &section_id, "Anéis 2", &strategy).unwrap().unwrap();
+        let fields: Vec<String> = list_translations(&conn, "section", &section.id).unwrap().into_iter().map(|t| t.field).collect();
+        assert_eq!(fields, vec!["description"]);
+    }
+
+    #[test]
+    fn old_info_name_translations_move_to_the_attribute() {
+        let conn = open_in_memory();
+        let (_, _, attribute_id) = setup(&conn);
+        let insert_name = |info: &str, value: &str| {
+            conn.execute(
+                "INSERT OR REPLACE INTO translations (entity_type, entity_id, field, locale, value) VALUES ('info', ?1, 'name', 'en', ?2)",
+                [info, value],
+            )
+            .unwrap();
+        };
+        // Banco de uma versão anterior, ainda sem as migrações de dados.
+        insert_name("metal", "Material");
+        insert_name("peso", "Weight");
+        conn.pragma_update(None, "user_version", 0).unwrap();
+        migrations::run_migrations(&conn).unwrap();
+
+        let translations = list_translations(&conn, "attribute", &attribute_id).unwrap();
+        assert_eq!(translations.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(), vec!["Weight"]);
+        assert!(list_translations(&conn, "info", "peso").unwrap().is_empty());
+        // A info livre mantém a tradução do nome.
+        assert_eq!(list_translations(&conn, "info", "metal").unwrap()[0].value, "Material");
+
+        // Só roda uma vez: ao abrir de novo, nada é movido nem apagado.
+        insert_name("peso", "Mass");
+        migrations::run_migrations(&conn).unwrap();
+        assert_eq!(list_translations(&conn, "info", "peso").unwrap()[0].value, "Mass");
+        assert_eq!(count(&conn), 3);
+    }
+}
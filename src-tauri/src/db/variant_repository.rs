@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+use crate::db::{from_json, to_json, validation_error, Result};
+use crate::i18n;
+use crate::models::variant::{Variant, VariantAxis, VariantAxisInput, VariantInput};
+
+const AXIS_COLUMNS: &str = "id, item_id, name, axis_values, position";
+const VARIANT_COLUMNS: &str = "id, item_id, code, options, price, stock, image_path, created_at, updated_at";
+
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS variant_axes (
+            id TEXT PRIMARY KEY,
+            item_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            axis_values TEXT NOT NULL,
+            position INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS variants (
+            id TEXT PRIMARY KEY,
+            item_id TEXT NOT NULL,
+            code TEXT UNIQUE NOT NULL,
+            options TEXT NOT NULL,
+            price REAL,
+            stock INTEGER,
+            image_path TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn map_axis(row: &Row) -> rusqlite::Result<VariantAxis> {
+    Ok(VariantAxis {
+        id: row.get(0)?,
+        item_id: row.get(1)?,
+        name: row.get(2)?,
+        values: from_json(3, row.get(3)?)?,
+        position: row.get(4)?,
+    })
+}
+
+fn map_variant(row: &Row) -> rusqlite::Result<Variant> {
+    Ok(Variant {
+        id: row.get(0)?,
+        item_id: row.get(1)?,
+        code: row.get(2)?,
+        options: from_json(3, row.get(3)?)?,
+        price: row.get(4)?,
+        stock: row.get(5)?,
+        image_path: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// Substitui os eixos de variação do item, mantendo a ordem recebida. Falha
+/// (sem alterar nada) se alguma variante usar um eixo ou valor que deixou de existir.
+pub fn set_axes(conn: &mut Connection, item_id: &str, axes: &[VariantAxisInput]) -> Result<Vec<VariantAxis>> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM variant_axes WHERE item_id = ?1", params![item_id])?;
+
+    for (position, axis) in axes.iter().enumerate() {
+        insert_axis(&tx, item_id, axis, position as i64)?;
+    }
+    for variant in list_variants(&tx, item_id)? {
+        validate_options(&tx, item_id, &variant.options)?;
+    }
+
+    tx.commit()?;
+    list_axes(conn, item_id)
+}
+
+pub fn insert_axis(conn: &Connection, item_id: &str, axis: &VariantAxisInput, position: i64) -> Result<()> {
+    let uuid = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO variant_axes (id, item_id, name, axis_values, position) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![uuid, item_id, axis.name, to_json(&axis.values)?, position],
+    )?;
+    Ok(())
+}
+
+pub fn list_axes(conn: &Connection, item_id: &str) -> Result<Vec<VariantAxis>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM variant_axes WHERE item_id = ?1 ORDER BY position",
+        AXIS_COLUMNS
+    ))?;
+    let axis_iter = stmt.query_map([item_id], map_axis)?;
+
+    let mut axes = Vec::new();
+    for axis in axis_iter {
+        axes.push(axis?);
+    }
+    Ok(axes)
+}
+
+/// Insere a variante e devolve o id gerado.
+pub fn insert_variant(conn: &Connection, item_id: &str, variant: &VariantInput) -> Result<String> {
+    validate_variant(conn, item_id, variant, None)?;
+    let uuid = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO variants (id, item_id, code, options, price, stock, image_path, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'), datetime('now'))",
+        params![
+            uuid,
+            item_id,
+            variant.code,
+            to_json(&variant.options)?,
+            variant.price,
+            variant.stock,
+            variant.image_path,
+        ],
+    )?;
+    Ok(uuid)
+}
+
+pub fn get_variant(conn: &Connection, id: &str) -> Result<Option<Variant>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM variants WHERE id = ?1", VARIANT_COLUMNS))?;
+    let mut rows = stmt.query(params![id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(map_variant(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn update_variant(conn: &Connection, id: &str, variant: &VariantInput) -> Result<()> {
+    let current = get_variant(conn, id)?.ok_or_else(|| validation_error(i18n::text("variant_not_found")))?;
+    validate_variant(conn, &current.item_id, variant, Some(id))?;
+    conn.execute(
+        "UPDATE variants SET code = ?1, options = ?2, price = ?3, stock = ?4, image_path = ?5, updated_at = datetime('now')
+         WHERE id = ?6",
+        params![
+            variant.code,
+            to_json(&variant.options)?,
+            variant.price,
+            variant.stock,
+            variant.image_path,
+            id,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn delete_variant(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute("DELETE FROM variants WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+pub fn list_variants(conn: &Connection, item_id: &str) -> Result<Vec<Variant>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM variants WHERE item_id = ?1 ORDER BY code",
+        VARIANT_COLUMNS
+    ))?;
+    let variant_iter = stmt.query_map([item_id], map_variant)?;
+
+    let mut variants = Vec::new();
+    for variant in variant_iter {
+        variants.push(variant?);
+    }
+    Ok(variants)
+}
+
+/// Confere se o código está livre (entre itens e variantes) e se as opções usam
+/// eixos e valores definidos no item.
+fn validate_variant(conn: &Connection, item_id: &str, variant: &VariantInput, ignore_id: Option<&str>) -> Result<()> {
+    if variant.code.trim().is_empty() {
+        return Err(validation_error(i18n::text("variant_code_required")));
+    }
+
+    let taken: i32 = conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM items WHERE code = ?1)
+              + (SELECT COUNT(*) FROM variants WHERE code = ?1 AND id != ?2)",
+        params![variant.code, ignore_id.unwrap_or_default()],
+        |row| row.get(0),
+    )?;
+    if taken > 0 {
+        return Err(validation_error(i18n::message("code_exists", &[&variant.code])));
+    }
+    validate_options(conn, item_id, &variant.options)
+}
+
+fn validate_options(conn: &Connection, item_id: &str, options: &BTreeMap<String, String>) -> Result<()> {
+    let axes = list_axes(conn, item_id)?;
+    for (name, value) in options {
+        let Some(axis) = axes.iter().find(|axis| &axis.name == name) else {
+            return Err(validation_error(i18n::message("unknown_axis", &[name])));
+        };
+        if !axis.values.is_empty() && !axis.values.contains(value) {
+            return Err(validation_error(i18n::message("invalid_axis_value", &[name, value])));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::open_in_memory;
+    use crate::db::{item_repository, section_repository, DbError};
+
+    // Item "AN-1" com o eixo "Tamanho" (16 e 18) e a variante AN-1-16.
+    fn setup(conn: &mut Connection) -> (String, String) {
+        section_repository::insert_section(conn, "Anéis", None, &Default::default()).unwrap();
+        let section_id = section_repository::get_section_by_name(conn, "Anéis").unwrap().unwrap().id;
+        let item_id = item_repository::insert_item(conn, "AN-1".into(), "Anel".into(), section_id, String::new()).unwrap();
+        let axis = VariantAxisInput { name: "Tamanho".into(), values: vec!["16".into(), "18".into()] };
+        set_axes(conn, &item_id, &[axis]).unwrap();
+        let variant_id = insert_variant(conn, &item_id, &size("AN-1-16", "16")).unwrap();
+        (item_id, variant_id)
+    }
+
+    fn size(code: &str, value: &str) -> VariantInput {
+        VariantInput { code: code.into(), options: [("Tamanho".to_string(), value.to_string())].into(), ..Default::default() }
+    }
+
+    #[test]
+    fn insert_and_update_validate_codes_and_options() {
+        let mut conn = open_in_memory();
+        let (item_id, variant_id) = setup(&mut conn);
+
+        for invalid in [size("", "16"), size("AN-1", "18"), size("AN-1-16", "18"), size("AN-1-20", "20")] {
+            let error = insert_variant(&conn, &item_id, &invalid).unwrap_err();
+            assert!(matches!(error, DbError::Validation(_)), "{:?}", invalid);
+        }
+        let other_axis = VariantInput { options: [("Cor".to_string(), "Azul".to_string())].into(), ..size("AN-1-A", "16") };
+        assert!(matches!(insert_variant(&conn, &item_id, &other_axis).unwrap_err(), DbError::Validation(_)));
+
+        // A própria variante pode manter o código.
+        update_variant(&conn, &variant_id, &size("AN-1-16", "18")).unwrap();
+        let error = update_variant(&conn, &variant_id, &size("AN-1-16", "20")).unwrap_err();
+        assert!(matches!(error, DbError::Validation(_)));
+        assert!(matches!(update_variant(&conn, "missing", &size("AN-1-X", "16")).unwrap_err(), DbError::Validation(_)));
+        assert_eq!(list_variants(&conn, &item_id).unwrap()[0].options["Tamanho"], "18");
+    }
+
+    #[test]
+    fn axes_cannot_drop_values_in_use() {
+        let mut conn = open_in_memory();
+        let (item_id, _) = setup(&mut conn);
+
+        let without_16 = VariantAxisInput { name: "Tamanho".into(), values: vec!["18".into()] };
+        assert!(matches!(set_axes(&mut conn, &item_id, &[without_16]).unwrap_err(), DbError::Validation(_)));
+        let renamed = VariantAxisInput { name: "Aro".into(), values: Vec::new() };
+        assert!(matches!(set_axes(&mut conn, &item_id, &[renamed]).unwrap_err(), DbError::Validation(_)));
+        // Nada mudou.
+        assert_eq!(list_axes(&conn, &item_id).unwrap()[0].values, vec!["16", "18"]);
+
+        let wider = VariantAxisInput { name: "Tamanho".into(), values: vec!["14".into(), "16".into()] };
+        assert_eq!(set_axes(&mut conn, &item_id, &[wider]).unwrap()[0].values, vec!["14", "16"]);
+    }
+}
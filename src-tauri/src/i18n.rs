@@ -0,0 +1,367 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use dirs::data_dir;
+
+/// Idiomas do app, os mesmos do frontend (src/i18n).
+pub const LOCALES: [&str; 3] = ["pt", "en", "es"];
+const DEFAULT_LOCALE: &str = "pt";
+
+static LOCALE: RwLock<&str> = RwLock::new(DEFAULT_LOCALE);
+
+// O idioma escolhido fica salvo para as mensagens da próxima abertura,
+// antes de o frontend chamar `set_locale`.
+fn locale_file() -> PathBuf {
+    let mut path: PathBuf = data_dir().unwrap();
+    path.push("BrilliantPDF");
+    path.push("locale");
+    path
+}
+
+/// "pt-BR", "es_AR" e "EN" viram "pt", "es" e "en"; `None` se não for suportado.
+pub fn normalize(locale: &str) -> Option<&'static str> {
+    let language = locale.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    LOCALES.iter().copied().find(|supported| *supported == language)
+}
+
+/// Carrega o idioma salvo; sem arquivo, continua em português.
+pub fn init() {
+    if let Some(locale) = fs::read_to_string(locale_file()).ok().as_deref().and_then(normalize) {
+        *LOCALE.write().unwrap() = locale;
+    }
+}
+
+pub fn locale() -> &'static str {
+    *LOCALE.read().unwrap()
+}
+
+/// Troca o idioma das mensagens e devolve o código aplicado (ex: "pt").
+pub fn set_locale(locale: &str) -> Result<&'static str, String> {
+    let applied = normalize(locale).ok_or_else(|| message("unsupported_locale", &[&locale]))?;
+    *LOCALE.write().unwrap() = applied;
+
+    let path = locale_file();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    fs::write(&path, applied).map_err(|e| message("save_locale_failed", &[&e]))?;
+    Ok(applied)
+}
+
+/// Mensagem no idioma atual, sem argumentos. Chave sem texto volta como está,
+/// para a falta aparecer na tela em vez de uma mensagem vazia.
+pub fn text(key: &'static str) -> &'static str {
+    translate(key, locale()).unwrap_or(key)
+}
+
+/// Mensagem no idioma atual; cada `{}` do texto recebe um argumento, em ordem.
+pub fn message(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = text(key).split("{}");
+    let mut result = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            result.push_str(&arg.to_string());
+        }
+        result.push_str(part);
+    }
+    result
+}
+
+/// Mensagens do backend (erros e logs), por idioma ("pt", "en", "es").
+fn translate(key: &str, language: &str) -> Option<&'static str> {
+    let text = match (key, language) {
+        // Idioma
+        ("unsupported_locale", "en") => "Unsupported language: {}",
+        ("unsupported_locale", "es") => "Idioma no soportado: {}",
+        ("unsupported_locale", _) => "Idioma não suportado: {}",
+        ("save_locale_failed", "en") => "Failed to save language: {}",
+        ("save_locale_failed", "es") => "Error al guardar el idioma: {}",
+        ("save_locale_failed", _) => "Erro ao salvar idioma: {}",
+
+        // Banco de dados
+        ("folder_created", "en") => "Folder created: {}",
+        ("folder_created", "es") => "Carpeta creada: {}",
+        ("folder_created", _) => "Pasta criada: {}",
+        ("folder_exists", "en") => "Folder already exists: {}",
+        ("folder_exists", "es") => "La carpeta ya existe: {}",
+        ("folder_exists", _) => "Pasta já existe: {}",
+        ("database_path", "en") => "Database: {}",
+        ("database_path", "es") => "Base de datos: {}",
+        ("database_path", _) => "Banco de dados: {}",
+        ("open_database_failed", "en") => "Failed to open database",
+        ("open_database_failed", "es") => "Error al abrir la base de datos",
+        ("open_database_failed", _) => "Falha ao abrir banco",
+        ("create_table_failed", "en") => "Failed to create table {}",
+        ("create_table_failed", "es") => "Error al crear la tabla {}",
+        ("create_table_failed", _) => "Erro ao criar tabela {}",
+        ("migrate_database_failed", "en") => "Failed to migrate database",
+        ("migrate_database_failed", "es") => "Error al migrar la base de datos",
+        ("migrate_database_failed", _) => "Erro ao migrar banco de dados",
+
+        // Registros não encontrados e validação
+        ("section_not_found", "en") => "Section not found",
+        ("section_not_found", "es") => "Sección no encontrada",
+        ("section_not_found", _) => "Seção não encontrada",
+        ("parent_section_not_found", "en") => "Parent section not found",
+        ("parent_section_not_found", "es") => "Sección padre no encontrada",
+        ("parent_section_not_found", _) => "Seção mãe não encontrada",
+        ("section_into_itself", "en") => "A section cannot be moved into itself",
+        ("section_into_itself", "es") => "No se puede mover una sección dentro de sí misma",
+        ("section_into_itself", _) => "Não é possível mover uma seção para dentro dela mesma",
+        ("item_not_found", "en") => "Item not found",
+        ("item_not_found", "es") => "Artículo no encontrado",
+        ("item_not_found", _) => "Item não encontrado",
+        ("variant_not_found", "en") => "Variant not found",
+        ("variant_not_found", "es") => "Variante no encontrada",
+        ("variant_not_found", _) => "Variante não encontrada",
+        ("tag_not_found", "en") => "Tag not found",
+        ("tag_not_found", "es") => "Etiqueta no encontrada",
+        ("tag_not_found", _) => "Tag não encontrada",
+        ("attribute_not_found", "en") => "Attribute not found",
+        ("attribute_not_found", "es") => "Atributo no encontrado",
+        ("attribute_not_found", _) => "Atributo não encontrado",
+        ("variant_code_required", "en") => "Variant code is required",
+        ("variant_code_required", "es") => "El código de la variante es obligatorio",
+        ("variant_code_required", _) => "Código da variante é obrigatório",
+        ("code_exists", "en") => "Code already exists: {}",
+        ("code_exists", "es") => "El código ya existe: {}",
+        ("code_exists", _) => "Código já existe: {}",
+        ("unknown_axis", "en") => "Unknown variation axis: {}",
+        ("unknown_axis", "es") => "Eje de variación inexistente: {}",
+        ("unknown_axis", _) => "Eixo de variação inexistente: {}",
+        ("invalid_axis_value", "en") => "Invalid value for {}: {}",
+        ("invalid_axis_value", "es") => "Valor no válido para {}: {}",
+        ("invalid_axis_value", _) => "Valor inválido para {}: {}",
+        ("not_a_number", "en") => "{}: \"{}\" is not a number",
+        ("not_a_number", "es") => "{}: \"{}\" no es un número",
+        ("not_a_number", _) => "{}: \"{}\" não é um número",
+        ("invalid_measure", "en") => "{}: \"{}\" is not a valid measurement",
+        ("invalid_measure", "es") => "{}: \"{}\" no es una medida válida",
+        ("invalid_measure", _) => "{}: \"{}\" não é uma medida válida",
+        ("value_not_allowed", "en") => "{}: \"{}\" is not one of {}",
+        ("value_not_allowed", "es") => "{}: \"{}\" no está entre {}",
+        ("value_not_allowed", _) => "{}: \"{}\" não está entre {}",
+        ("yes_or_no", "en") => "{}: \"{}\" must be yes or no",
+        ("yes_or_no", "es") => "{}: \"{}\" debe ser sí o no",
+        ("yes_or_no", _) => "{}: \"{}\" deve ser sim ou não",
+        ("preset_not_found", "en") => "Preset \"{}\" not found",
+        ("preset_not_found", "es") => "Preset \"{}\" no encontrado",
+        ("preset_not_found", _) => "Preset \"{}\" não encontrado",
+        ("preset_name_empty", "en") => "The preset name cannot be empty",
+        ("preset_name_empty", "es") => "El nombre del preset no puede estar vacío",
+        ("preset_name_empty", _) => "O nome do preset não pode ficar vazio",
+        ("preset_name_taken", "en") => "A preset named \"{}\" already exists",
+        ("preset_name_taken", "es") => "Ya existe un preset llamado \"{}\"",
+        ("preset_name_taken", _) => "Já existe um preset chamado \"{}\"",
+        ("field_not_translatable", "en") => "The field {}.{} cannot be translated",
+        ("field_not_translatable", "es") => "El campo {}.{} no admite traducción",
+        ("field_not_translatable", _) => "O campo {}.{} não aceita tradução",
+        ("translation_locale_required", "en") => "Enter the translation language",
+        ("translation_locale_required", "es") => "Indique el idioma de la traducción",
+        ("translation_locale_required", _) => "Informe o idioma da tradução",
+        ("translation_entity_not_found", "en") => "The record to translate does not exist",
+        ("translation_entity_not_found", "es") => "El registro a traducir no existe",
+        ("translation_entity_not_found", _) => "O registro a traduzir não existe",
+        ("info_name_translated_by_attribute", "en") => "The name of an info with an attribute is translated in the attribute",
+        ("info_name_translated_by_attribute", "es") => "El nombre de una info con atributo se traduce en el atributo",
+        ("info_name_translated_by_attribute", _) => "O nome de uma info com atributo é traduzido no atributo",
+        ("info_value_not_translatable", "en") => "Values of typed attributes are formatted in the catalog language and cannot be translated",
+        ("info_value_not_translatable", "es") => "Los valores de atributos con tipo se formatean en el idioma del catálogo y no admiten traducción",
+        ("info_value_not_translatable", _) => "Valores de atributos com tipo já saem formatados no idioma do catálogo e não aceitam tradução",
+
+        // Arquivos e imagens
+        ("create_folder_failed", "en") => "Failed to create folder: {}",
+        ("create_folder_failed", "es") => "Error al crear la carpeta: {}",
+        ("create_folder_failed", _) => "Erro ao criar pasta: {}",
+        ("save_image_failed", "en") => "Failed to save image: {}",
+        ("save_image_failed", "es") => "Error al guardar la imagen: {}",
+        ("save_image_failed", _) => "Erro ao salvar imagem: {}",
+        ("copy_image_failed", "en") => "Failed to copy image {}: {}",
+        ("copy_image_failed", "es") => "Error al copiar la imagen {}: {}",
+        ("copy_image_failed", _) => "Erro ao copiar imagem {}: {}",
+        ("image_not_found", "en") => "Image not found: {}",
+        ("image_not_found", "es") => "Imagen no encontrada: {}",
+        ("image_not_found", _) => "Imagem não encontrada: {}",
+
+        // Usuário
+        ("config_dir_unavailable", "en") => "Could not find the configuration folder",
+        ("config_dir_unavailable", "es") => "Error al obtener la carpeta de configuración",
+        ("config_dir_unavailable", _) => "Erro ao obter diretório de configuração",
+        ("create_config_dir_failed", "en") => "Failed to create configuration folder: {}",
+        ("create_config_dir_failed", "es") => "Error al crear la carpeta de configuración: {}",
+        ("create_config_dir_failed", _) => "Erro ao criar diretório de configuração: {}",
+        ("save_user_id_failed", "en") => "Failed to save user ID: {}",
+        ("save_user_id_failed", "es") => "Error al guardar el ID de usuario: {}",
+        ("save_user_id_failed", _) => "Erro ao salvar ID do usuário: {}",
+        ("load_user_id_failed", "en") => "Failed to load user ID: {}",
+        ("load_user_id_failed", "es") => "Error al cargar el ID de usuario: {}",
+        ("load_user_id_failed", _) => "Erro ao carregar ID do usuário: {}",
+        ("clear_user_id_failed", "en") => "Failed to clear user ID: {}",
+        ("clear_user_id_failed", "es") => "Error al borrar el ID de usuario: {}",
+        ("clear_user_id_failed", _) => "Erro ao limpar ID do usuário: {}",
+
+        // Atualização
+        ("update_downloaded", "en") => "[Updater] Downloaded {} bytes of {}",
+        ("update_downloaded", "es") => "[Updater] Descargados {} bytes de {}",
+        ("update_downloaded", _) => "[Updater] Baixado {} bytes de {}",
+        ("update_download_finished", "en") => "[Updater] Download finished",
+        ("update_download_finished", "es") => "[Updater] Descarga finalizada",
+        ("update_download_finished", _) => "[Updater] Download finalizado",
+        ("update_installed", "en") => "[Updater] Update installed. Restarting app...",
+        ("update_installed", "es") => "[Updater] Actualización instalada. Reiniciando la app...",
+        ("update_installed", _) => "[Updater] Atualização instalada. Reiniciando app...",
+        ("no_update_available", "en") => "No update available.",
+        ("no_update_available", "es") => "No hay actualizaciones disponibles.",
+        ("no_update_available", _) => "Nenhuma atualização disponível.",
+
+        // Catálogo
+        ("generation_cancelled", "en") => "Generation cancelled",
+        ("generation_cancelled", "es") => "Generación cancelada",
+        ("generation_cancelled", _) => "Geração cancelada",
+        ("generation_failed", "en") => "Unexpected error while generating the catalog",
+        ("generation_failed", "es") => "Error inesperado al generar el catálogo",
+        ("generation_failed", _) => "Erro inesperado ao gerar o catálogo",
+        ("pdf_a_cmyk", "en") => "PDF/A does not support CMYK colors; turn off one of the two",
+        ("pdf_a_cmyk", "es") => "PDF/A no admite colores CMYK; desactive una de las dos opciones",
+        ("pdf_a_cmyk", _) => "O PDF/A não aceita cores CMYK; desative uma das duas opções",
+        ("save_catalog_failed", "en") => "Failed to save catalog: {}",
+        ("save_catalog_failed", "es") => "Error al guardar el catálogo: {}",
+        ("save_catalog_failed", _) => "Erro ao salvar catálogo: {}",
+        ("load_font_failed", "en") => "Failed to load font: {}",
+        ("load_font_failed", "es") => "Error al cargar la fuente: {}",
+        ("load_font_failed", _) => "Erro ao carregar fonte: {}",
+        ("pdf_page_missing", "en") => "Page {} does not exist in the PDF",
+        ("pdf_page_missing", "es") => "La página {} no existe en el PDF",
+        ("pdf_page_missing", _) => "Página {} não existe no PDF",
+        ("item_not_in_catalog", "en") => "The item does not appear in this catalog",
+        ("item_not_in_catalog", "es") => "El artículo no aparece en este catálogo",
+        ("item_not_in_catalog", _) => "O item não aparece neste catálogo",
+        ("page_out_of_range", "en") => "Page {} does not exist; the catalog has {} pages",
+        ("page_out_of_range", "es") => "La página {} no existe; el catálogo tiene {} páginas",
+        ("page_out_of_range", _) => "Página {} não existe; o catálogo tem {} páginas",
+        ("png_failed", "en") => "Failed to create PNG: {}",
+        ("png_failed", "es") => "Error al generar el PNG: {}",
+        ("png_failed", _) => "Erro ao gerar PNG: {}",
+        ("pdfium_not_found", "en") => "PDFium library not found: {}",
+        ("pdfium_not_found", "es") => "Biblioteca PDFium no encontrada: {}",
+        ("pdfium_not_found", _) => "Biblioteca PDFium não encontrada: {}",
+
+        // Fontes
+        ("font_name_empty", "en") => "The font name cannot be empty",
+        ("font_name_empty", "es") => "El nombre de la fuente no puede estar vacío",
+        ("font_name_empty", _) => "O nome da fonte não pode ficar vazio",
+        ("font_name_taken", "en") => "A font named \"{}\" already exists",
+        ("font_name_taken", "es") => "Ya existe una fuente llamada \"{}\"",
+        ("font_name_taken", _) => "Já existe uma fonte chamada \"{}\"",
+        ("read_file_failed", "en") => "Failed to read {}: {}",
+        ("read_file_failed", "es") => "Error al leer {}: {}",
+        ("read_file_failed", _) => "Erro ao ler {}: {}",
+        ("invalid_font_file", "en") => "{} is not a valid TTF/OTF font",
+        ("invalid_font_file", "es") => "{} no es una fuente TTF/OTF válida",
+        ("invalid_font_file", _) => "{} não é uma fonte TTF/OTF válida",
+        ("replace_font_failed", "en") => "Failed to replace font: {}",
+        ("replace_font_failed", "es") => "Error al reemplazar la fuente: {}",
+        ("replace_font_failed", _) => "Erro ao substituir fonte: {}",
+        ("save_font_failed", "en") => "Failed to save font: {}",
+        ("save_font_failed", "es") => "Error al guardar la fuente: {}",
+        ("save_font_failed", _) => "Erro ao salvar fonte: {}",
+        ("remove_font_failed", "en") => "Failed to remove font: {}",
+        ("remove_font_failed", "es") => "Error al eliminar la fuente: {}",
+        ("remove_font_failed", _) => "Erro ao remover fonte: {}",
+        ("font_not_found", "en") => "Font \"{}\" not found",
+        ("font_not_found", "es") => "Fuente \"{}\" no encontrada",
+        ("font_not_found", _) => "Fonte \"{}\" não encontrada",
+        ("read_font_failed", "en") => "Failed to read font {}: {}",
+        ("read_font_failed", "es") => "Error al leer la fuente {}: {}",
+        ("read_font_failed", _) => "Erro ao ler fonte {}: {}",
+        ("invalid_font", "en") => "Invalid font \"{}\"",
+        ("invalid_font", "es") => "Fuente \"{}\" no válida",
+        ("invalid_font", _) => "Fonte \"{}\" inválida",
+
+        // Códigos de barras
+        ("barcode_empty", "en") => "The item has no code for the barcode",
+        ("barcode_empty", "es") => "El artículo no tiene código para el código de barras",
+        ("barcode_empty", _) => "O item não tem código para o código de barras",
+        ("barcode_invalid_character", "en") => "Character \"{}\" cannot be used in a Code 128 barcode",
+        ("barcode_invalid_character", "es") => "El carácter \"{}\" no se puede usar en un código Code 128",
+        ("barcode_invalid_character", _) => "O caractere \"{}\" não pode ser usado no código de barras Code 128",
+        ("ean13_not_numeric", "en") => "EAN-13 codes only have digits",
+        ("ean13_not_numeric", "es") => "Los códigos EAN-13 solo tienen dígitos",
+        ("ean13_not_numeric", _) => "Códigos EAN-13 só têm dígitos",
+        ("ean13_length", "en") => "EAN-13 codes have 12 digits (or 13 with the check digit)",
+        ("ean13_length", "es") => "Los códigos EAN-13 tienen 12 dígitos (o 13 con el dígito verificador)",
+        ("ean13_length", _) => "Códigos EAN-13 têm 12 dígitos (ou 13 com o verificador)",
+        ("ean13_check_digit", "en") => "Invalid EAN-13 check digit; expected {}",
+        ("ean13_check_digit", "es") => "Dígito verificador EAN-13 no válido; se esperaba {}",
+        ("ean13_check_digit", _) => "Dígito verificador do EAN-13 inválido; o certo é {}",
+
+        // Etiquetas
+        ("label_sheet_empty", "en") => "The label sheet needs at least one row and one column of labels larger than zero",
+        ("label_sheet_empty", "es") => "La hoja necesita al menos una fila y una columna de etiquetas mayores que cero",
+        ("label_sheet_empty", _) => "A folha precisa de pelo menos uma linha e uma coluna de etiquetas maiores que zero",
+        ("label_skip_too_large", "en") => "The labels to skip must be fewer than the {} labels on a sheet",
+        ("label_skip_too_large", "es") => "Las etiquetas a saltar deben ser menos que las {} de una hoja",
+        ("label_skip_too_large", _) => "As etiquetas puladas precisam ser menos que as {} de uma folha",
+        ("label_sheet_too_large", "en") => "The labels do not fit on a {} mm sheet",
+        ("label_sheet_too_large", "es") => "Las etiquetas no caben en una hoja de {} mm",
+        ("label_sheet_too_large", _) => "As etiquetas não cabem numa folha de {} mm",
+        ("label_preset_not_found", "en") => "Label sheet preset not found",
+        ("label_preset_not_found", "es") => "No se encontró el preset de hoja de etiquetas",
+        ("label_preset_not_found", _) => "Preset de folha de etiquetas não encontrado",
+        ("labels_no_items", "en") => "No items selected for the labels",
+        ("labels_no_items", "es") => "No hay artículos seleccionados para las etiquetas",
+        ("labels_no_items", _) => "Nenhum item selecionado para as etiquetas",
+
+        _ => return None,
+    };
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    // Chaves passadas a `text` e `message` no código, fora dos testes.
+    fn used_keys(dir: &Path, keys: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                used_keys(&path, keys);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                let source = fs::read_to_string(&path).unwrap();
+                let source = source.split("#[cfg(test)]").next().unwrap_or_default();
+                for call in ["text(\"", "message(\""] {
+                    for (start, _) in source.match_indices(call) {
+                        let before = source[..start].chars().next_back();
+                        if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                            continue;
+                        }
+                        let key = &source[start + call.len()..];
+                        keys.push(key[..key.find('"').unwrap()].to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_used_key_has_a_text_in_every_language() {
+        let mut keys = Vec::new();
+        used_keys(Path::new(file!()).parent().unwrap(), &mut keys);
+        assert!(keys.len() > 50, "{} chaves", keys.len());
+        for key in &keys {
+            for language in LOCALES {
+                assert!(translate(key, language).is_some_and(|text| !text.is_empty()), "{} ({})", key, language);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_keys_come_back_as_the_key() {
+        assert_eq!(text("no_such_message"), "no_such_message");
+        assert_eq!(message("no_such_message", &[&1]), "no_such_message");
+    }
+}
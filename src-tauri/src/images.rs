@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::Path;
+
+use crate::i18n;
+
+pub const IMAGES_FOLDER: &str = "../images";
+
+pub fn sanitize_code(code: &str) -> String {
+    code.chars()
+        .map(|c: char| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+pub fn image_path_for_code(code: &str) -> String {
+    format!("{}/{}.png", IMAGES_FOLDER, sanitize_code(code))
+}
+
+// Caminho da cópia com a extensão do original ("a.JPG" -> "<código>.jpg").
+fn copy_target(source: &Path, new_code: &str) -> String {
+    match source.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{}/{}.{}", IMAGES_FOLDER, sanitize_code(new_code), extension.to_lowercase()),
+        None => image_path_for_code(new_code),
+    }
+}
+
+/// Copia a imagem de um item para o caminho do novo código, mantendo a
+/// extensão do arquivo original. Devolve `None` se o item não tem imagem ou a cópia falhar.
+pub fn copy_image(source: &str, new_code: &str) -> Option<String> {
+    let source_path = Path::new(source);
+    if source.is_empty() || !source_path.exists() {
+        return None;
+    }
+
+    let target = copy_target(source_path, new_code);
+    match fs::create_dir_all(IMAGES_FOLDER).and_then(|_| fs::copy(source, &target)) {
+        Ok(_) => Some(target),
+        Err(e) => {
+            eprintln!("{}", i18n::message("copy_image_failed", &[&source, &e]));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_keep_the_source_extension() {
+        assert_eq!(copy_target(Path::new("/fotos/anel.JPG"), "AL 1"), format!("{}/AL_1.jpg", IMAGES_FOLDER));
+        assert_eq!(copy_target(Path::new("/fotos/anel.webp"), "AL-1"), format!("{}/AL-1.webp", IMAGES_FOLDER));
+        assert_eq!(copy_target(Path::new("/fotos/anel"), "AL-1"), image_path_for_code("AL-1"));
+    }
+}
@@ -1,4 +1,7 @@
+mod catalog;
 mod db;
+mod i18n;
+mod images;
 mod updater;
 mod models;
 mod commands;
@@ -13,10 +16,13 @@ use rusqlite::Connection;
 pub use updater::{check_for_update, download_and_install_update};
 
 pub async fn run() {
+	i18n::init();
 	let conn: Connection = connection::database_connect();
 
 	tauri::Builder::default()
 		.manage(DbConn(std::sync::Mutex::new(conn)))
+		.manage(PreviewState(Default::default()))
+		.manage(CatalogJobs(Default::default()))
 		.plugin(tauri_plugin_updater::Builder::new().build())
 		.plugin(tauri_plugin_opener::init())
 		.invoke_handler(tauri::generate_handler![
@@ -26,22 +32,80 @@ pub async fn run() {
             get_section,
             get_section_by_name,
             list_sections,
+            list_section_tree,
+            list_subsections,
+            move_section,
 			has_sections,
             update_section,
             delete_section,
+            duplicate_section,
+            set_section_template,
+            get_section_template,
+            apply_section_template,
             create_item,
             get_item,
             list_items,
+            list_new_items,
             update_item,
             delete_item,
+            duplicate_item,
+            bulk_move_items,
+            bulk_delete_items,
+            bulk_duplicate_items,
+            bulk_update_infos,
+            bulk_add_tag,
+            bulk_remove_tag,
+            set_variant_axes,
+            list_variant_axes,
+            create_variant,
+            list_variants,
+            update_variant,
+            delete_variant,
+            create_tag,
+            list_tags,
+            update_tag,
+            delete_tag,
+            add_item_tag,
+            remove_item_tag,
+            list_item_tags,
+            list_items_by_tag,
             create_info,
             get_info,
             has_items,
             list_infos,
             update_info,
             delete_info,
+            create_attribute,
+            list_attributes,
+            update_attribute,
+            delete_attribute,
+            attribute_suggestions,
             save_image,
+            generate_catalog,
+            generate_from_preset,
+            check_print_images,
+            check_barcode,
+            preview_catalog_page,
+            start_catalog_job,
+            cancel_catalog_job,
+            save_catalog_preset,
+            list_catalog_presets,
+            get_catalog_preset,
+            update_catalog_preset,
+            delete_catalog_preset,
+            generate_labels,
+            list_label_models,
+            save_label_preset,
+            list_label_presets,
+            delete_label_preset,
+            set_translation,
+            list_translations,
+            list_fonts,
+            register_font,
+            remove_font,
             get_settings,
+            set_locale,
+            get_locale,
             save_user_id,
             load_user_id,
             clear_user_id
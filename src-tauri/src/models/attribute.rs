@@ -0,0 +1,207 @@
+use serde::{Serialize, Deserialize};
+
+use crate::i18n;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeType {
+    Text,
+    Number,
+    Unit,    // número + unidade fixa, ex: "4,5 g"
+    Enum,    // um dos `allowed_values`
+    Boolean,
+}
+
+impl AttributeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::Text => "text",
+            AttributeType::Number => "number",
+            AttributeType::Unit => "unit",
+            AttributeType::Enum => "enum",
+            AttributeType::Boolean => "boolean",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "number" => AttributeType::Number,
+            "unit" => AttributeType::Unit,
+            "enum" => AttributeType::Enum,
+            "boolean" => AttributeType::Boolean,
+            _ => AttributeType::Text,
+        }
+    }
+
+    /// Tipos guardados como número ("4.5").
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, AttributeType::Number | AttributeType::Unit)
+    }
+}
+
+/// Definição reutilizável de uma info (ex: "Peso", tipo unit, unidade "g").
+/// Infos com o mesmo nome passam a referenciar a definição e são validadas por ela.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: AttributeType,
+    pub unit: Option<String>,
+    pub allowed_values: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttributeInput {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: AttributeType,
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
+}
+
+/// Dados de autocompletar para o formulário de infos.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttributeSuggestion {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: Option<AttributeType>, // None para nomes livres, sem definição
+    pub unit: Option<String>,
+    pub values: Vec<String>,
+}
+
+impl AttributeDefinition {
+    /// Valida o valor digitado e devolve a forma armazenada
+    /// (números com ponto, booleanos como "true"/"false", enum com a grafia da definição).
+    /// Valor vazio é aceito em qualquer tipo: a info ainda não foi preenchida.
+    pub fn normalize(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(String::new());
+        }
+        match self.kind {
+            AttributeType::Text => Ok(value.to_string()),
+            AttributeType::Number => parse_number(value)
+                .map(|number| number.to_string())
+                .ok_or_else(|| i18n::message("not_a_number", &[&self.name, &value])),
+            AttributeType::Unit => {
+                let number = match self.unit.as_deref() {
+                    Some(unit) if !unit.is_empty() => strip_unit(value, unit),
+                    _ => value,
+                };
+                parse_number(number)
+                    .map(|number| number.to_string())
+                    .ok_or_else(|| i18n::message("invalid_measure", &[&self.name, &value]))
+            }
+            AttributeType::Enum => self
+                .allowed_values
+                .iter()
+                .find(|allowed| allowed.to_lowercase() == value.to_lowercase())
+                .cloned()
+                .ok_or_else(|| i18n::message("value_not_allowed", &[&self.name, &value, &self.allowed_values.join(", ")])),
+            AttributeType::Boolean => parse_bool(value)
+                .map(|flag| flag.to_string())
+                .ok_or_else(|| i18n::message("yes_or_no", &[&self.name, &value])),
+        }
+    }
+}
+
+/// Lê um número digitado: "4,5", "4.5", "1.234" (mil), "1.234,5" e "1,234.5".
+/// Com os dois separadores, o último é o decimal. Só com pontos, eles são
+/// milhar quando há mais de um ou quando o ponto separa exatamente três
+/// dígitos de uma parte inteira não nula ("0.125" continua decimal).
+pub fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (thousands, decimal) = match (value.rfind(','), value.rfind('.')) {
+        (Some(comma), Some(dot)) if dot > comma => (',', '.'),
+        (Some(_), _) => ('.', ','),
+        (None, Some(_)) if is_thousands_dot(value) => ('.', ','),
+        (None, _) => (',', '.'),
+    };
+    let normalized: String = value
+        .chars()
+        .filter(|c| *c != thousands)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    normalized.parse().ok()
+}
+
+fn is_thousands_dot(value: &str) -> bool {
+    let digits = value.trim_start_matches(['-', '+']);
+    let groups: Vec<&str> = digits.split('.').collect();
+    let head = groups[0];
+    let grouped = (1..=3).contains(&head.len())
+        && head.bytes().all(|b| b.is_ascii_digit())
+        && groups[1..].iter().all(|group| group.len() == 3 && group.bytes().all(|b| b.is_ascii_digit()));
+    grouped && (groups.len() > 2 || !head.trim_start_matches('0').is_empty())
+}
+
+/// Lê um valor já gravado por `normalize` ("1.234" é 1,234); valores antigos,
+/// gravados como texto livre, caem em `parse_number`.
+pub fn parse_stored_number(value: &str) -> Option<f64> {
+    value.trim().parse().ok().or_else(|| parse_number(value))
+}
+
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "sim" | "s" | "yes" | "y" | "sí" | "si" => Some(true),
+        "false" | "0" | "não" | "nao" | "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn strip_unit<'a>(value: &'a str, unit: &str) -> &'a str {
+    let Some(split) = value.len().checked_sub(unit.len()) else {
+        return value;
+    };
+    if value.is_char_boundary(split) && value[split..].to_lowercase() == unit.to_lowercase() {
+        value[..split].trim_end()
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_number_reads_brazilian_and_english_notation() {
+        assert_eq!(parse_number("4,5"), Some(4.5));
+        assert_eq!(parse_number("4.5"), Some(4.5));
+        assert_eq!(parse_number("1.234"), Some(1234.0));
+        assert_eq!(parse_number("1.234.567"), Some(1234567.0));
+        assert_eq!(parse_number("1.234,5"), Some(1234.5));
+        assert_eq!(parse_number("1,234.5"), Some(1234.5));
+        assert_eq!(parse_number("0.125"), Some(0.125));
+        assert_eq!(parse_number("12.50"), Some(12.5));
+        assert_eq!(parse_number("-1.500"), Some(-1500.0));
+        assert_eq!(parse_number("abc"), None);
+    }
+
+    #[test]
+    fn stored_numbers_keep_the_dot_as_decimal() {
+        assert_eq!(parse_stored_number("1.234"), Some(1.234));
+        assert_eq!(parse_stored_number("4,5"), Some(4.5));
+    }
+
+    #[test]
+    fn normalize_unit_strips_the_unit() {
+        let attribute = AttributeDefinition {
+            id: String::new(),
+            name: "Peso".into(),
+            kind: AttributeType::Unit,
+            unit: Some("g".into()),
+            allowed_values: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        assert_eq!(attribute.normalize("4,5 g"), Ok("4.5".to_string()));
+        assert_eq!(attribute.normalize("1.200g"), Ok("1200".to_string()));
+        assert!(attribute.normalize("pesado").is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Serialize, Deserialize};
+
+/// Resultado de uma operação em lote.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BulkSummary {
+    pub requested: usize,     // ids recebidos
+    pub affected: usize,      // itens alterados, removidos ou duplicados
+    pub missing: Vec<String>, // ids que não existem no banco
+    pub created: Vec<String>, // ids dos itens criados (duplicação)
+}
+
+/// Como gerar o código das cópias: "AL-001" vira "AL-001-COPIA", "AL-001-1" ou "AL-001<sufixo>".
+/// Se o código já existir, um contador é acrescentado ("AL-001-COPIA-2").
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "strategy", content = "value")]
+pub enum CodeSuffixStrategy {
+    #[default]
+    Copy,
+    Numbered,
+    Custom(String),
+}
+
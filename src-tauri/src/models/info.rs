@@ -6,4 +6,7 @@ pub struct Info {
     pub item_code: String,  // FK que referencia a Section (id da seção)
     pub name: String,        // nome da informação
     pub details: String,     // detalhes da informação
+    pub attribute_id: Option<String>, // definição de atributo, se houver uma com o mesmo nome
+    pub created_at: String,  // "YYYY-MM-DD HH:MM:SS" (UTC)
+    pub updated_at: String,
 }
@@ -7,4 +7,6 @@ pub struct Item {
     pub description: String,
     pub section_id: String,
     pub image_path: Option<String>, // ex: "./images/123.jpg"
-}
\ No newline at end of file
+    pub created_at: String, // "YYYY-MM-DD HH:MM:SS" (UTC)
+    pub updated_at: String,
+}
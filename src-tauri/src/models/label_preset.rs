@@ -0,0 +1,13 @@
+use serde::{Serialize, Deserialize};
+
+use crate::catalog::options::LabelSheet;
+
+/// Medidas de folha de etiquetas salvas com um nome, ex: "Etiqueta de bandeja 40 × 20".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LabelPreset {
+    pub id: String,
+    pub name: String,
+    pub sheet: LabelSheet,
+    pub created_at: String,
+    pub updated_at: String,
+}
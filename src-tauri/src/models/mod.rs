@@ -1,3 +1,11 @@
 pub mod section;
 pub mod item;
 pub mod info;
+pub mod bulk;
+pub mod variant;
+pub mod tag;
+pub mod attribute;
+pub mod template;
+pub mod preset;
+pub mod translation;
+pub mod label_preset;
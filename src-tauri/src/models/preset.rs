@@ -0,0 +1,13 @@
+use serde::{Serialize, Deserialize};
+
+use crate::catalog::CatalogOptions;
+
+/// Configuração de geração salva com um nome, ex: "Alianças até R$ 500".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatalogPreset {
+    pub id: String,
+    pub name: String,
+    pub options: CatalogOptions,
+    pub created_at: String,
+    pub updated_at: String,
+}
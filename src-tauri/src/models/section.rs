@@ -4,4 +4,28 @@ use serde::{Serialize, Deserialize};
 pub struct Section {
     pub id: String,   // UUID, por exemplo
     pub name: String, // nome da seção
+    pub parent_id: Option<String>, // seção mãe; None para seções de primeiro nível
+    pub description: Option<String>,
+    pub cover_image: Option<String>, // imagem da página divisória
+    pub palette: Option<String>,     // sobrescreve `Settings.pallet` nesta seção
+    pub layout: Option<String>,      // "list" ou "grid"
+    pub created_at: String, // "YYYY-MM-DD HH:MM:SS" (UTC)
+    pub updated_at: String,
+}
+
+/// Campos opcionais da seção usados no catálogo.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SectionDetails {
+    pub description: Option<String>,
+    pub cover_image: Option<String>,
+    pub palette: Option<String>,
+    pub layout: Option<String>,
+}
+
+/// Seção com as subseções, para listagem em árvore.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SectionNode {
+    pub section: Section,
+    pub children: Vec<SectionNode>,
 }
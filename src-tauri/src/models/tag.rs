@@ -0,0 +1,10 @@
+use serde::{Serialize, Deserialize};
+
+/// Coleção que atravessa seções, ex: "promoções" ou "presentes".
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
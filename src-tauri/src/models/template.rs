@@ -0,0 +1,18 @@
+use serde::{Serialize, Deserialize};
+
+/// Info padrão de uma seção: todo item novo da seção nasce com ela.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemplateField {
+    pub id: String,
+    pub section_id: String,
+    pub name: String,
+    pub default_value: Option<String>,
+    pub position: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemplateFieldInput {
+    pub name: String,
+    #[serde(default)]
+    pub default_value: Option<String>,
+}
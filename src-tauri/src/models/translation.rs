@@ -0,0 +1,13 @@
+use serde::{Serialize, Deserialize};
+
+/// Texto de um campo de seção, item ou info em outro idioma.
+/// O valor original (sem tradução) é o texto cadastrado no próprio registro.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Translation {
+    pub entity_type: String, // "section", "item", "attribute" ou "info"
+    pub entity_id: String,
+    pub field: String,       // ex: "name", "description", "details"
+    pub locale: String,      // "pt", "en", "es" (ou com região, ex: "es-AR")
+    pub value: String,
+    pub updated_at: String,
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+/// Eixo de variação de um item, ex: "Tamanho" com 10..30 ou "Metal" com ouro/prata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VariantAxis {
+    pub id: String,
+    pub item_id: String,
+    pub name: String,
+    pub values: Vec<String>,
+    pub position: i64, // ordem das colunas na tabela do PDF
+}
+
+/// SKU filho de um item: combinação de valores dos eixos com código próprio.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Variant {
+    pub id: String,
+    pub item_id: String,
+    pub code: String,
+    pub options: BTreeMap<String, String>, // nome do eixo -> valor
+    pub price: Option<f64>,
+    pub stock: Option<i64>,
+    pub image_path: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Dados para criar/editar uma variante.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VariantInput {
+    pub code: String,
+    pub options: BTreeMap<String, String>,
+    pub price: Option<f64>,
+    pub stock: Option<i64>,
+    pub image_path: Option<String>,
+}
+
+/// Eixo recebido do frontend, sem id.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VariantAxisInput {
+    pub name: String,
+    pub values: Vec<String>,
+}
@@ -14,6 +14,11 @@ pub struct Settings {
     pub pallet: String,
     pub phone_number: String,
     pub email: String,
+    /// Fontes padrão dos catálogos (ex: "DejaVu Serif"); sem valor, Helvetica.
+    #[serde(default)]
+    pub heading_font: Option<String>,
+    #[serde(default)]
+    pub body_font: Option<String>,
 }
 
 impl Default for Settings {
@@ -28,6 +33,8 @@ impl Default for Settings {
             pallet: "default".into(),
             phone_number: "".into(),
             email: "".into(),
+            heading_font: None,
+            body_font: None,
         }
     }
 }
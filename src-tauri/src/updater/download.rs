@@ -1,5 +1,7 @@
 use tauri_plugin_updater::UpdaterExt;
 
+use crate::i18n;
+
 #[tauri::command]
 pub async fn download_and_install_update(app: tauri::AppHandle) -> Result<(), String> {
     let updater = app.updater().map_err(|e| e.to_string())?;
@@ -11,16 +13,16 @@ pub async fn download_and_install_update(app: tauri::AppHandle) -> Result<(), St
         update.download_and_install(
             |chunk_length, content_length| {
                 downloaded += chunk_length;
-                println!("[Updater] Baixado {} bytes de {:?}", downloaded, content_length);
+                println!("{}", i18n::message("update_downloaded", &[&downloaded, &format!("{:?}", content_length)]));
             },
             || {
-                println!("[Updater] Download finalizado");
+                println!("{}", i18n::text("update_download_finished"));
             },
         ).await.map_err(|e| e.to_string())?;
 
-        println!("[Updater] Atualização instalada. Reiniciando app...");
+        println!("{}", i18n::text("update_installed"));
         app.restart();
     } else {
-        Err("Nenhuma atualização disponível.".to_string())
+        Err(i18n::text("no_update_available").to_string())
     }
 }
\ No newline at end of file